@@ -0,0 +1,51 @@
+//! 结构化错误类型
+//!
+//! 库中的函数统一返回 `Box<dyn std::error::Error + Send + Sync>`。需要调用方区分处理的错误
+//! 以 [`GameboxError`] 的形式装箱返回，可以通过 `downcast_ref::<GameboxError>()` 取出。
+
+use std::fmt;
+
+/// 需要调用方区分处理的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GameboxError {
+    /// 在连接超时时间内未能与主机建立连接（主机不可达或丢弃了连接请求）
+    ///
+    /// 与连接成功但响应缓慢的读取超时不同，通常意味着主机已经不可用
+    ConnectTimeout {
+        /// 请求的地址
+        url: String,
+    },
+}
+
+impl fmt::Display for GameboxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameboxError::ConnectTimeout { url } => write!(f, "连接超时: {}", url),
+        }
+    }
+}
+
+impl std::error::Error for GameboxError {}
+
+impl GameboxError {
+    /// 将 HTTP 请求错误转换为库的错误：连接超时转换为 [`GameboxError::ConnectTimeout`]，其余原样返回
+    pub(crate) fn from_request(error: reqwest::Error) -> Box<dyn std::error::Error + Send + Sync> {
+        if error.is_connect() && error.is_timeout() {
+            let url = error.url().map(|url| url.to_string()).unwrap_or_default();
+            Box::new(GameboxError::ConnectTimeout { url })
+        } else {
+            Box::new(error)
+        }
+    }
+
+    /// 判断错误是否为连接超时（包括尚未转换的 HTTP 请求错误）
+    pub fn is_connect_timeout(error: &(dyn std::error::Error + 'static)) -> bool {
+        if let Some(GameboxError::ConnectTimeout { .. }) = error.downcast_ref::<GameboxError>() {
+            return true;
+        }
+        error
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(|error| error.is_connect() && error.is_timeout())
+    }
+}
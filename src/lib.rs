@@ -1,5 +1,7 @@
+pub mod error;
 pub mod models;
 pub mod providers;
 pub mod traits;
 pub mod scan;
-pub mod logger;
\ No newline at end of file
+pub mod logger;
+pub mod prelude;
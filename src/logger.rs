@@ -69,6 +69,8 @@ pub struct ScanProgress {
     pub current: usize,
     pub total: usize,
     pub current_item: String,
+    /// 是否是扫描完成事件
+    pub finished: bool,
 }
 
 impl ScanProgress {
@@ -77,6 +79,17 @@ impl ScanProgress {
             current,
             total,
             current_item: current_item.into(),
+            finished: false,
+        }
+    }
+
+    /// 扫描完成事件
+    pub fn completed(total: usize) -> Self {
+        Self {
+            current: total,
+            total,
+            current_item: String::new(),
+            finished: true,
         }
     }
 
@@ -1,8 +1,12 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use crate::models::game_meta_data::completeness_weight;
+use crate::scan::{extract_search_key, rank_launchers_in_dir};
+
 /// 游戏信息结构体：这个结构体是扫描以后最终呈现的信息项
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameInfo {
@@ -22,20 +26,44 @@ pub struct GameInfo {
     pub start_path_defualt: String,
     /// 游戏介绍：由GameMetadata提供，从各个平台刮削的游戏介绍
     pub description: Option<String>,
-    /// 游戏发行日期：由GameMetadata提供，从各个平台刮削的游戏发行日期
-    pub release_date: DateTime<Utc>,
+    /// 游戏发行日期：由GameMetadata提供，从各个平台刮削的游戏发行日期；未提供或无法解析时为 `None`
+    pub release_date: Option<DateTime<Utc>>,
     /// 游戏开发商：由GameMetadata提供，从各个平台刮削的游戏开发商
     pub developer: Option<String>,
     /// 游戏发行商：由GameMetadata提供，从各个平台刮削的游戏发行商
     pub publisher: Option<String>,
+    /// 游戏开发商列表：由developer拆分得到，一个字符串中包含多个公司时拆分为多项
+    #[serde(default)]
+    pub developers: Vec<String>,
+    /// 游戏发行商列表：由publisher拆分得到，一个字符串中包含多个公司时拆分为多项
+    #[serde(default)]
+    pub publishers: Vec<String>,
     /// 游戏标签：由GameMetadata提供，从各个平台刮削的游戏标签
     pub tabs: Option<String>,
+    /// 游戏标签列表：合并后的类型和标签，每项是一个完整的标签（标签本身可能包含逗号，不要拆分tabs）
+    #[serde(default)]
+    pub tab_list: Vec<String>,
     /// 游戏平台：由GameMetadata提供，从各个平台刮削的游戏平台
     pub platform: Option<String>,
+    /// 游戏引擎：由本地扫描结果提供，根据游戏目录中的文件识别（如 `RPG Maker`、`Unity`）
+    #[serde(default)]
+    pub engine: Option<String>,
     /// 游戏大小：由本地扫描结果提供，PathGroupResult.child_path中所有文件的大小累加
     pub byte_size: u64,
     /// 扫描时间：由本地扫描结果提供，即当前时间
     pub scan_time: DateTime<Utc>,
+    /// 固定的匹配结果：(提供者名称, 提供者中的游戏 ID)，设置后重新扫描时直接获取该结果而不再模糊搜索
+    #[serde(default)]
+    pub pinned_match: Option<(String, String)>,
+    /// 匹配结果的来源提供者名称：由置信度最高的查询结果提供，未找到时为空
+    #[serde(default)]
+    pub source: Option<String>,
+    /// 匹配结果在来源提供者中的原生 ID，用于链接回来源页面
+    #[serde(default)]
+    pub provider_id: Option<String>,
+    /// 用户标注：如 `owned`、`wishlist`、`hidden`，用于管理游戏库状态，扫描不会修改
+    #[serde(default)]
+    pub annotations: BTreeSet<String>,
 }
 
 impl GameInfo {
@@ -49,14 +77,180 @@ impl GameInfo {
             start_path: Vec::new(),
             start_path_defualt: String::new(),
             description: None,
-            release_date: Utc::now(),
+            release_date: None,
             developer: None,
             publisher: None,
+            developers: Vec::new(),
+            publishers: Vec::new(),
             tabs: None,
+            tab_list: Vec::new(),
             platform: None,
+            engine: None,
             byte_size: 0,
             scan_time: Utc::now(),
+            pinned_match: None,
+            source: None,
+            provider_id: None,
+            annotations: BTreeSet::new(),
+        }
+    }
+
+    /// 添加标注
+    ///
+    /// # 返回
+    /// 标注原本不存在时返回 `true`
+    pub fn add_annotation(&mut self, annotation: impl Into<String>) -> bool {
+        self.annotations.insert(annotation.into())
+    }
+
+    /// 是否带有指定标注
+    pub fn has_annotation(&self, annotation: &str) -> bool {
+        self.annotations.contains(annotation)
+    }
+
+    /// 移除标注
+    ///
+    /// # 返回
+    /// 标注原本存在时返回 `true`
+    pub fn remove_annotation(&mut self, annotation: &str) -> bool {
+        self.annotations.remove(annotation)
+    }
+
+    /// 构建匹配结果在来源站点上的页面地址
+    ///
+    /// 支持 DLsite（RJ 编号）和 TheGamesDB（数字 ID）。IGDB 的页面地址使用 slug 而非数字 ID，
+    /// 无法从 ID 构建，返回 `None`。
+    ///
+    /// # 返回
+    /// 来源页面 URL，缺少来源或 ID、或来源不支持时为 `None`
+    pub fn source_url(&self) -> Option<String> {
+        let id = self.provider_id.as_deref()?;
+        match self.source.as_deref()? {
+            "DLsite" => Some(format!("https://www.dlsite.com/maniax/work/=/product_id/{}.html", id)),
+            "TheGamesDB" => Some(format!("https://thegamesdb.net/game.php?id={}", id)),
+            _ => None,
+        }
+    }
+
+    /// 计算稳定的游戏 ID
+    ///
+    /// 对归一化后的 标题 + 开发商 + 目录名 进行 FNV-1a 哈希，得到 16 位十六进制字符串。
+    /// 不依赖任何数据源 ID，可作为重复扫描之间的合并键以及重复检测的依据。
+    ///
+    /// - 移动游戏目录（只改变上级路径）不会改变 ID
+    /// - 重命名游戏（标题或目录名改变）会得到新的 ID
+    /// - 归一化会忽略大小写、空白和标点，因此仅格式不同的标题得到相同的 ID
+    pub fn stable_id(&self) -> String {
+        fn normalize(s: &str) -> String {
+            s.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(|c| c.to_lowercase())
+                .collect()
         }
+
+        let dir_name = self
+            .dir_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let key = [
+            normalize(&self.title),
+            normalize(self.developer.as_deref().unwrap_or_default()),
+            normalize(&dir_name),
+        ]
+        .join("\u{1f}");
+
+        // FNV-1a 64 位：实现简单且结果跨平台、跨版本稳定（不同于 std 的 DefaultHasher）
+        const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+        const FNV_PRIME: u64 = 0x100000001b3;
+        let hash = key.bytes().fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+        });
+
+        format!("{:016x}", hash)
+    }
+
+    /// 计算元数据完整度评分（0.0 ~ 1.0）
+    ///
+    /// 与查询结果置信度中的数据完整度使用同一组字段权重，归一化到 0.0 ~ 1.0。
+    /// 标题与本地目录名相同（未从数据库获取到标题）视为缺失，发行日期为 `None` 时视为缺失
+    ///
+    /// 标签同时对应 genres 和 tags 两部分权重
+    pub fn completeness_score(&self) -> f32 {
+        use completeness_weight::*;
+
+        let mut score = 0.0;
+        if !self.title.is_empty() && self.title != self.sub_title { score += TITLE; }
+        if !self.cover_urls.is_empty() { score += COVER; }
+        if self.description.is_some() { score += DESCRIPTION; }
+        if self.release_date.is_some() { score += RELEASE_DATE; }
+        if self.developer.is_some() { score += DEVELOPER; }
+        if self.publisher.is_some() { score += PUBLISHER; }
+        if self.tabs.is_some() { score += GENRES + TAGS; }
+
+        (score / TOTAL).clamp(0.0, 1.0)
+    }
+
+    /// 判断元数据是否完整
+    ///
+    /// 辅助方法，扫描器本身不会调用；调用方可以用它筛选需要重新查询的游戏
+    /// （如只对不完整的游戏重新扫描）
+    ///
+    /// # 参数
+    /// - `threshold`: 完整度阈值（0.0 ~ 1.0）
+    ///
+    /// # 返回
+    /// 完整度评分不低于阈值时返回 `true`
+    pub fn is_complete(&self, threshold: f32) -> bool {
+        self.completeness_score() >= threshold
+    }
+
+    /// 对所有启动项进行打分排序
+    ///
+    /// 使用默认启动项的启发式规则：setup/uninstall/debug 等辅助程序降权，
+    /// 文件名与游戏名匹配、位于游戏根目录的启动项加分，分数相同时较大的文件优先。
+    /// 界面可以用该列表提供默认启动项以外的候选。
+    ///
+    /// # 返回
+    /// `(启动项, 分数)` 列表，按可能性从高到低排序
+    pub fn rank_launchers(&self) -> Vec<(String, f32)> {
+        let search_key = extract_search_key(&self.sub_title);
+        rank_launchers_in_dir(&self.dir_path, &self.start_path, &[&self.title, &self.sub_title, &search_key])
+    }
+
+    /// 获取默认启动项的完整路径
+    ///
+    /// 优先使用配置的默认启动项，未配置时使用第一个启动项；没有任何启动项时返回 `None`
+    pub fn default_launcher_path(&self) -> Option<PathBuf> {
+        let start_path = if !self.start_path_defualt.is_empty() {
+            &self.start_path_defualt
+        } else {
+            self.start_path.first()?
+        };
+        Some(self.dir_path.join(start_path))
+    }
+
+    /// 将游戏目录从旧的库根目录迁移到新的库根目录
+    ///
+    /// 整个游戏库被移动（如从 `D:/Games` 移到 `E:/Games`）后，用于更新 `dir_path` 而不必重新扫描。
+    /// 启动项是相对于游戏目录的路径，不需要修改。
+    ///
+    /// # 参数
+    /// - `old_root`: 旧的库根目录，必须是 `dir_path` 的前缀
+    /// - `new_root`: 新的库根目录
+    ///
+    /// # 返回
+    /// - `Ok(GameInfo)`: 迁移后的游戏信息
+    /// - `Err(String)`: `old_root` 不是游戏目录的前缀
+    pub fn rebase_path(&self, old_root: &Path, new_root: &Path) -> Result<GameInfo, String> {
+        let relative = self.dir_path.strip_prefix(old_root).map_err(|_| {
+            format!("{} 不在 {} 下", self.dir_path.display(), old_root.display())
+        })?;
+
+        Ok(GameInfo {
+            dir_path: new_root.join(relative),
+            ..self.clone()
+        })
     }
 
     /// 开始游戏
@@ -111,3 +305,112 @@ impl GameInfo {
         }
     }
 }
+
+/// 将整个游戏库从旧的根目录迁移到新的根目录
+///
+/// # 参数
+/// - `games`: 游戏列表
+/// - `old_root`: 旧的库根目录
+/// - `new_root`: 新的库根目录
+///
+/// # 返回
+/// 迁移后的游戏列表；任意游戏不在 `old_root` 下时返回错误，不做部分迁移
+pub fn rebase_library(games: &[GameInfo], old_root: &Path, new_root: &Path) -> Result<Vec<GameInfo>, String> {
+    games
+        .iter()
+        .map(|game| game.rebase_path(old_root, new_root))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(title: &str, developer: &str, dir_path: &str) -> GameInfo {
+        GameInfo {
+            title: title.to_string(),
+            developer: Some(developer.to_string()),
+            dir_path: PathBuf::from(dir_path),
+            ..GameInfo::new()
+        }
+    }
+
+    #[test]
+    fn test_stable_id_is_stable_for_equivalent_games() {
+        let a = game("Elden Ring", "FromSoftware", "D:/Games/Elden Ring");
+        // 目录被移动、标题格式略有差异、扫描时间不同
+        let b = game("elden  ring", "FROMSOFTWARE", "E:/Library/Elden Ring");
+
+        assert_eq!(a.stable_id(), b.stable_id());
+        assert_eq!(a.stable_id().len(), 16);
+
+        let renamed = game("Elden Ring Nightreign", "FromSoftware", "D:/Games/Elden Ring");
+        assert_ne!(a.stable_id(), renamed.stable_id());
+    }
+
+    #[test]
+    fn test_completeness_score() {
+        let full = GameInfo {
+            title: "Elden Ring".to_string(),
+            sub_title: "ELDEN RING v1.10".to_string(),
+            cover_urls: vec!["https://example.com/cover.jpg".to_string()],
+            description: Some("Rise, Tarnished".to_string()),
+            release_date: Some(chrono::TimeZone::with_ymd_and_hms(&Utc, 2022, 2, 25, 0, 0, 0).unwrap()),
+            developer: Some("FromSoftware".to_string()),
+            publisher: Some("Bandai Namco".to_string()),
+            tabs: Some("RPG".to_string()),
+            ..GameInfo::new()
+        };
+        assert!(full.completeness_score() > 0.99);
+        assert!(full.is_complete(0.9));
+
+        // 查询失败时的回退结果：标题即目录名，其余字段为空
+        let fallback = GameInfo {
+            title: "ELDEN RING v1.10".to_string(),
+            sub_title: "ELDEN RING v1.10".to_string(),
+            ..GameInfo::new()
+        };
+        assert!(fallback.completeness_score() < 0.1);
+        assert!(!fallback.is_complete(0.5));
+    }
+
+
+    #[test]
+    fn test_source_url_from_dlsite_rj_code() {
+        let mut game = GameInfo {
+            source: Some("DLsite".to_string()),
+            provider_id: Some("RJ01014447".to_string()),
+            ..GameInfo::new()
+        };
+        assert_eq!(
+            game.source_url().as_deref(),
+            Some("https://www.dlsite.com/maniax/work/=/product_id/RJ01014447.html")
+        );
+
+        game.source = Some("IGDB".to_string());
+        assert_eq!(game.source_url(), None);
+        game.source = None;
+        assert_eq!(game.source_url(), None);
+    }
+
+    #[test]
+    fn test_rebase_path_to_new_root() {
+        let mut elden_ring = game("Elden Ring", "FromSoftware", "D:/Games/RPG/Elden Ring");
+        elden_ring.start_path = vec!["Game/eldenring.exe".to_string()];
+
+        let moved = elden_ring.rebase_path(Path::new("D:/Games"), Path::new("E:/Games")).unwrap();
+        assert_eq!(moved.dir_path, PathBuf::from("E:/Games/RPG/Elden Ring"));
+        assert_eq!(moved.start_path, elden_ring.start_path);
+        assert_eq!(moved.stable_id(), elden_ring.stable_id());
+
+        // 旧根目录必须是游戏目录的前缀（按路径组件而不是字符串匹配）
+        assert!(elden_ring.rebase_path(Path::new("D:/Gam"), Path::new("E:/Games")).is_err());
+
+        let celeste = game("Celeste", "Maddy Makes Games", "F:/Other/Celeste");
+        let library = vec![elden_ring.clone(), celeste];
+        assert!(rebase_library(&library, Path::new("D:/Games"), Path::new("E:/Games")).is_err());
+        let moved = rebase_library(&library[..1], Path::new("D:/Games"), Path::new("E:/Games")).unwrap();
+        assert_eq!(moved.len(), 1);
+    }
+
+}
@@ -1,5 +1,63 @@
 use serde::{Deserialize, Serialize};
 
+/// 元数据完整度各字段的权重（合计 0.3）
+///
+/// 查询结果的置信度计算和 `GameInfo` 的完整度评分共用这组权重，
+/// 保证"是否需要重新查询"的判断标准一致
+pub(crate) mod completeness_weight {
+    pub const TITLE: f32 = 0.08;
+    pub const COVER: f32 = 0.05;
+    pub const DESCRIPTION: f32 = 0.04;
+    pub const RELEASE_DATE: f32 = 0.04;
+    pub const DEVELOPER: f32 = 0.04;
+    pub const PUBLISHER: f32 = 0.03;
+    pub const GENRES: f32 = 0.01;
+    pub const TAGS: f32 = 0.01;
+    pub const TOTAL: f32 = TITLE + COVER + DESCRIPTION + RELEASE_DATE + DEVELOPER + PUBLISHER + GENRES + TAGS;
+}
+
+/// `GameMetadata` 字段集合
+///
+/// 用于声明提供者能可靠提供哪些字段，可以用 `|` 组合：
+///
+/// ```
+/// use gamebox::models::game_meta_data::FieldSet;
+///
+/// let fields = FieldSet::TITLE | FieldSet::GENRES;
+/// assert!(fields.contains(FieldSet::GENRES));
+/// assert!(!fields.contains(FieldSet::COVER));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct FieldSet(u16);
+
+impl FieldSet {
+    pub const EMPTY: FieldSet = FieldSet(0);
+    pub const TITLE: FieldSet = FieldSet(1 << 0);
+    pub const COVER: FieldSet = FieldSet(1 << 1);
+    pub const DESCRIPTION: FieldSet = FieldSet(1 << 2);
+    pub const RELEASE_DATE: FieldSet = FieldSet(1 << 3);
+    pub const DEVELOPER: FieldSet = FieldSet(1 << 4);
+    pub const PUBLISHER: FieldSet = FieldSet(1 << 5);
+    pub const GENRES: FieldSet = FieldSet(1 << 6);
+    pub const TAGS: FieldSet = FieldSet(1 << 7);
+    pub const PLATFORMS: FieldSet = FieldSet(1 << 8);
+    pub const PRICE: FieldSet = FieldSet(1 << 9);
+    pub const ALL: FieldSet = FieldSet((1 << 10) - 1);
+
+    /// 是否包含 `other` 中的全部字段
+    pub fn contains(self, other: FieldSet) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl std::ops::BitOr for FieldSet {
+    type Output = FieldSet;
+
+    fn bitor(self, rhs: FieldSet) -> FieldSet {
+        FieldSet(self.0 | rhs.0)
+    }
+}
+
 /// 游戏元数据
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameMetadata {
@@ -19,6 +77,53 @@ pub struct GameMetadata {
     pub genres: Option<Vec<String>>,
     /// 游戏标签
     pub tags: Option<Vec<String>>,
+    /// 游戏平台（如 `PC`、`Android`）
+    #[serde(default)]
+    pub platforms: Option<Vec<String>>,
+    /// 当前售价（最小货币单位，如日元）
+    #[serde(default)]
+    pub price: Option<u32>,
+    /// 售价货币代码（如 `JPY`）
+    #[serde(default)]
+    pub currency: Option<String>,
+    /// 是否正在打折
+    #[serde(default)]
+    pub on_sale: Option<bool>,
+    /// 提供者中的原生 ID（如 IGDB 的数字 ID、DLsite 的 RJ 编号）
+    #[serde(default)]
+    pub provider_id: Option<String>,
+    /// 评分（0 ~ 100）
+    #[serde(default)]
+    pub rating: Option<f32>,
+    /// 各地区的发布日期：(地区, 日期)，如 `("japan", "2020-09-17")`
+    ///
+    /// `release_date` 仍为单一的发布日期（通常是最早的发布日期）
+    #[serde(default)]
+    pub release_dates: Option<Vec<(String, String)>>,
+    /// 合并搜索结果时收集的其他封面 URL（不含 `cover_url`）
+    #[serde(default)]
+    pub extra_cover_urls: Vec<String>,
+    /// 是否为体验版/试玩版（由中间件根据标题判断）
+    #[serde(default)]
+    pub is_trial: bool,
+}
+
+impl GameMetadata {
+    /// 计算数据完整度（0.0 ~ 0.3），各字段按 [`completeness_weight`] 累加
+    pub(crate) fn completeness(&self) -> f32 {
+        use completeness_weight::*;
+
+        let mut completeness = 0.0;
+        if self.title.is_some() { completeness += TITLE; }
+        if self.cover_url.is_some() { completeness += COVER; }
+        if self.description.is_some() { completeness += DESCRIPTION; }
+        if self.release_date.is_some() { completeness += RELEASE_DATE; }
+        if self.developer.is_some() { completeness += DEVELOPER; }
+        if self.publisher.is_some() { completeness += PUBLISHER; }
+        if self.genres.is_some() { completeness += GENRES; }
+        if self.tags.is_some() { completeness += TAGS; }
+        completeness
+    }
 }
 
 /// 提供默认值的trait
@@ -33,6 +138,15 @@ impl Default for GameMetadata {
             publisher: None,
             genres: None,
             tags: None,
+            platforms: None,
+            price: None,
+            currency: None,
+            on_sale: None,
+            provider_id: None,
+            rating: None,
+            release_dates: None,
+            extra_cover_urls: Vec::new(),
+            is_trial: false,
         }
     }
 }
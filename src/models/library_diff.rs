@@ -0,0 +1,258 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::models::game_info::GameInfo;
+
+/// 发生变化的游戏
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangedGame {
+    /// 旧快照中的游戏信息
+    pub before: GameInfo,
+    /// 新快照中的游戏信息
+    pub after: GameInfo,
+    /// 发生变化的字段名称
+    pub changed_fields: Vec<&'static str>,
+}
+
+/// 两次扫描结果之间的差异
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LibraryDiff {
+    /// 新增的游戏
+    pub added: Vec<GameInfo>,
+    /// 移除的游戏
+    pub removed: Vec<GameInfo>,
+    /// 元数据、版本或大小发生变化的游戏
+    pub changed: Vec<ChangedGame>,
+}
+
+/// 增量补丁的结构版本
+pub const LIBRARY_PATCH_SCHEMA_VERSION: u32 = 1;
+
+/// 单个字段的变化
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldDelta {
+    /// 旧值（字段按 `GameInfo` 的 JSON 表示）
+    pub old: serde_json::Value,
+    /// 新值
+    pub new: serde_json::Value,
+}
+
+/// 单个游戏的增量补丁
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GamePatch {
+    /// 旧快照中的稳定 ID（[`GameInfo::stable_id`]），即服务端已知的 ID
+    pub id: String,
+    /// 游戏目录
+    pub dir_path: PathBuf,
+    /// 发生变化的字段：字段名 -> 新旧值
+    pub fields: BTreeMap<String, FieldDelta>,
+}
+
+/// 用于增量同步的补丁
+///
+/// 结构固定如下，字段按名称排序，便于服务端解析：
+///
+/// ```json
+/// {
+///   "schema_version": 1,
+///   "added": ["<stable_id>"],
+///   "removed": ["<stable_id>"],
+///   "changed": [
+///     { "id": "<stable_id>", "dir_path": "...", "fields": { "version": { "old": "1.0", "new": "1.1" } } }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LibraryPatch {
+    /// 结构版本，固定为 [`LIBRARY_PATCH_SCHEMA_VERSION`]
+    pub schema_version: u32,
+    /// 新增游戏的稳定 ID
+    pub added: Vec<String>,
+    /// 移除游戏的稳定 ID
+    pub removed: Vec<String>,
+    /// 发生变化的游戏及其字段变化
+    pub changed: Vec<GamePatch>,
+}
+
+impl LibraryDiff {
+    /// 两次扫描结果是否完全相同
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// 转换为只包含 ID 和字段变化的增量补丁
+    pub fn to_patch(&self) -> LibraryPatch {
+        let changed = self
+            .changed
+            .iter()
+            .map(|change| {
+                let before = serde_json::to_value(&change.before).unwrap_or_default();
+                let after = serde_json::to_value(&change.after).unwrap_or_default();
+                let fields = change
+                    .changed_fields
+                    .iter()
+                    .map(|&field| {
+                        let delta = FieldDelta {
+                            old: before.get(field).cloned().unwrap_or_default(),
+                            new: after.get(field).cloned().unwrap_or_default(),
+                        };
+                        (field.to_string(), delta)
+                    })
+                    .collect();
+
+                GamePatch {
+                    id: change.before.stable_id(),
+                    dir_path: change.after.dir_path.clone(),
+                    fields,
+                }
+            })
+            .collect();
+
+        LibraryPatch {
+            schema_version: LIBRARY_PATCH_SCHEMA_VERSION,
+            added: self.added.iter().map(GameInfo::stable_id).collect(),
+            removed: self.removed.iter().map(GameInfo::stable_id).collect(),
+            changed,
+        }
+    }
+
+    /// 将增量补丁写入 JSON 文件
+    ///
+    /// 与完整输出 `GameInfo` 相比，只包含新增/移除游戏的 ID 和变化游戏的字段新旧值，
+    /// 适用于向服务端增量同步游戏库
+    ///
+    /// # 参数
+    /// - `path`: 输出路径
+    pub fn out_json_patch<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, &self.to_patch())?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// 比较两个游戏的内容字段，返回发生变化的字段名称（不比较扫描时间）
+fn changed_fields(before: &GameInfo, after: &GameInfo) -> Vec<&'static str> {
+    let mut fields = Vec::new();
+    if before.title != after.title { fields.push("title"); }
+    if before.version != after.version { fields.push("version"); }
+    if before.cover_urls != after.cover_urls { fields.push("cover_urls"); }
+    if before.start_path != after.start_path { fields.push("start_path"); }
+    if before.start_path_defualt != after.start_path_defualt { fields.push("start_path_defualt"); }
+    if before.description != after.description { fields.push("description"); }
+    if before.release_date != after.release_date { fields.push("release_date"); }
+    if before.developer != after.developer { fields.push("developer"); }
+    if before.publisher != after.publisher { fields.push("publisher"); }
+    if before.tabs != after.tabs || before.tab_list != after.tab_list { fields.push("tabs"); }
+    if before.platform != after.platform { fields.push("platform"); }
+    if before.engine != after.engine { fields.push("engine"); }
+    if before.byte_size != after.byte_size { fields.push("byte_size"); }
+    fields
+}
+
+/// 比较两次扫描结果
+///
+/// 以游戏目录（`dir_path`）作为同一游戏的判断依据：
+/// 只在新快照中出现的为新增，只在旧快照中出现的为移除，
+/// 两边都存在但内容字段不同的为变化（扫描时间的差异会被忽略）。
+///
+/// # 参数
+/// - `old`: 旧的扫描结果
+/// - `new`: 新的扫描结果
+///
+/// # 返回
+/// 差异结果，各列表保持输入中的顺序
+pub fn diff_libraries(old: &[GameInfo], new: &[GameInfo]) -> LibraryDiff {
+    let old_by_dir: HashMap<&PathBuf, &GameInfo> = old.iter().map(|g| (&g.dir_path, g)).collect();
+    let new_by_dir: HashMap<&PathBuf, &GameInfo> = new.iter().map(|g| (&g.dir_path, g)).collect();
+
+    let mut diff = LibraryDiff::default();
+
+    for game in new {
+        match old_by_dir.get(&game.dir_path) {
+            None => diff.added.push(game.clone()),
+            Some(before) => {
+                let fields = changed_fields(before, game);
+                if !fields.is_empty() {
+                    diff.changed.push(ChangedGame {
+                        before: (*before).clone(),
+                        after: game.clone(),
+                        changed_fields: fields,
+                    });
+                }
+            }
+        }
+    }
+
+    diff.removed = old
+        .iter()
+        .filter(|game| !new_by_dir.contains_key(&game.dir_path))
+        .cloned()
+        .collect();
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(dir: &str, version: &str) -> GameInfo {
+        GameInfo {
+            title: dir.to_string(),
+            version: Some(version.to_string()),
+            dir_path: PathBuf::from(format!("/games/{}", dir)),
+            ..GameInfo::new()
+        }
+    }
+
+    #[test]
+    fn test_diff_libraries() {
+        let old = vec![game("Kept", "1.0"), game("Removed", "1.0"), game("Updated", "1.0")];
+        let mut new = vec![game("Kept", "1.0"), game("Updated", "1.1"), game("Added", "1.0")];
+        // 扫描时间不同不视为变化
+        new[0].scan_time = old[0].scan_time + chrono::Duration::days(1);
+
+        let diff = diff_libraries(&old, &new);
+
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].title, "Added");
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].title, "Removed");
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].after.title, "Updated");
+        assert_eq!(diff.changed[0].changed_fields, vec!["version"]);
+
+        assert!(diff_libraries(&old, &old).is_empty());
+        assert!(serde_json::to_string(&diff).is_ok());
+    }
+
+    #[test]
+    fn test_json_patch_contains_only_changed_fields() {
+        let old = vec![game("Removed", "1.0"), game("Updated", "1.0")];
+        let new = vec![game("Updated", "1.1"), game("Added", "1.0")];
+
+        let diff = diff_libraries(&old, &new);
+        let file = tempfile::NamedTempFile::new().unwrap();
+        diff.out_json_patch(file.path()).unwrap();
+
+        let content = std::fs::read_to_string(file.path()).unwrap();
+        let patch: LibraryPatch = serde_json::from_str(&content).unwrap();
+
+        assert_eq!(patch.schema_version, LIBRARY_PATCH_SCHEMA_VERSION);
+        assert_eq!(patch.added, vec![new[1].stable_id()]);
+        assert_eq!(patch.removed, vec![old[0].stable_id()]);
+        assert_eq!(patch.changed.len(), 1);
+        assert_eq!(patch.changed[0].id, old[1].stable_id());
+        assert_eq!(patch.changed[0].fields.len(), 1);
+        assert_eq!(
+            patch.changed[0].fields["version"],
+            FieldDelta { old: "1.0".into(), new: "1.1".into() }
+        );
+        assert!(!content.contains("\"title\""));
+    }
+
+}
@@ -1,3 +1,4 @@
 pub mod game_info;
 pub mod game_meta_data;
 
+pub mod library_diff;
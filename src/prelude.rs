@@ -0,0 +1,43 @@
+//! 常用类型的统一导出
+//!
+//! 引入 prelude 即可使用扫描、搜索和输出的常用类型，不需要逐个写出模块路径：
+//!
+//! ```
+//! use gamebox::prelude::*;
+//! use std::sync::Arc;
+//!
+//! struct LocalProvider;
+//!
+//! #[async_trait::async_trait]
+//! impl GameDatabaseProvider for LocalProvider {
+//!     fn name(&self) -> &str {
+//!         "Local"
+//!     }
+//!
+//!     async fn search(&self, title: &str) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+//!         Ok(vec![GameMetadata {
+//!             title: Some(title.to_string()),
+//!             ..Default::default()
+//!         }])
+//!     }
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let scanner = GameScanner::new().with_provider(Arc::new(LocalProvider)).await;
+//!     let results: Vec<GameQueryResult> = scanner.search_ref("Elden Ring").await.unwrap();
+//!     log_info!(format!("找到 {} 条结果", results.len()));
+//!     assert_eq!(results[0].info.title.as_deref(), Some("Elden Ring"));
+//! }
+//! ```
+
+pub use crate::error::GameboxError;
+pub use crate::logger::{get_logger, LogEvent, LogLevel};
+pub use crate::models::game_info::GameInfo;
+pub use crate::models::game_meta_data::{FieldSet, GameMetadata};
+pub use crate::providers::{
+    GameDatabaseMiddleware, GameDatabaseProvider, GameQueryResult, SearchOutcome, SearchParams, Tier,
+};
+pub use crate::scan::{GameScanner, PathGroupResult};
+pub use crate::traits::{CsvOutput, GameLibrary, JsonOutput, QueryResultFilter};
+pub use crate::{log_debug, log_error, log_info, log_success, log_warning};
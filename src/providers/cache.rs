@@ -0,0 +1,259 @@
+//! 查询结果缓存
+//!
+//! 定义中间件内部使用的缓存条目，以及可在用户之间共享的缓存包（cache pack）文件格式。
+//!
+//! 缓存包是一个自描述的 JSON 文件，包含魔数和结构版本号，
+//! 导入时会先校验这两个字段，不兼容的文件会被明确拒绝而不是被错误解析。
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::logger::{get_logger, LogEvent, LogLevel};
+use crate::providers::GameQueryResult;
+use crate::traits::json_output::{create_json_writer, open_json_reader};
+
+/// 缓存包魔数，用于识别文件类型
+pub const CACHE_PACK_MAGIC: &str = "GAMEBOX-CACHE-PACK";
+
+/// 当前支持的缓存包结构版本
+pub const CACHE_PACK_SCHEMA_VERSION: u32 = 1;
+
+/// 缓存条目：一次搜索的全部结果及其写入时间
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    /// 查询结果（按置信度从高到低排序）
+    pub results: Vec<GameQueryResult>,
+    /// 写入缓存的时间
+    pub cached_at: DateTime<Utc>,
+    /// 写入本进程缓存的时刻，用于判断是否过期（导入的条目从导入时开始计算）
+    #[serde(skip, default = "Instant::now")]
+    pub inserted_at: Instant,
+}
+
+impl CacheEntry {
+    /// 以当前时间创建缓存条目
+    pub fn new(results: Vec<GameQueryResult>) -> Self {
+        CacheEntry {
+            results,
+            cached_at: Utc::now(),
+            inserted_at: Instant::now(),
+        }
+    }
+
+    /// 恢复之前写入的缓存条目，按写入时间推算本进程中的写入时刻
+    ///
+    /// 这样从磁盘加载的条目会在原本应过期的时间过期，而不是从加载时重新计时
+    pub fn restored(results: Vec<GameQueryResult>, cached_at: DateTime<Utc>) -> Self {
+        let age = (Utc::now() - cached_at).to_std().unwrap_or_default();
+        CacheEntry {
+            results,
+            cached_at,
+            inserted_at: Instant::now().checked_sub(age).unwrap_or_else(Instant::now),
+        }
+    }
+
+    /// 条目是否已超过有效期（有效期为 0 时总是视为过期）
+    pub fn is_expired(&self, ttl: Duration) -> bool {
+        ttl.is_zero() || self.inserted_at.elapsed() >= ttl
+    }
+
+    /// 贡献了结果的提供者名称（去重，保持首次出现的顺序）
+    pub fn providers(&self) -> Vec<String> {
+        let mut providers: Vec<String> = Vec::new();
+        for result in &self.results {
+            if !providers.contains(&result.source) {
+                providers.push(result.source.clone());
+            }
+        }
+        providers
+    }
+}
+
+/// 缓存命中统计
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct CacheStats {
+    /// 完全由缓存返回结果的搜索次数
+    pub hits: u64,
+    /// 查询了提供者的搜索次数
+    pub misses: u64,
+    /// 搜索结果写入缓存的次数
+    pub insertions: u64,
+    /// 当前缓存条目数量
+    pub entries: usize,
+}
+
+/// 缓存命中计数器
+#[derive(Debug, Default)]
+pub(crate) struct CacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    insertions: AtomicU64,
+}
+
+impl CacheCounters {
+    pub(crate) fn record_hit(&self) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_miss(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_insertion(&self) {
+        self.insertions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 生成统计快照
+    pub(crate) fn snapshot(&self, entries: usize) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            insertions: self.insertions.load(Ordering::Relaxed),
+            entries,
+        }
+    }
+}
+
+/// 缓存包中的单个条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachePackEntry {
+    /// 搜索关键词（缓存键）
+    pub query: String,
+    /// 写入缓存的时间
+    pub cached_at: DateTime<Utc>,
+    /// 来源提供者（出处）
+    pub providers: Vec<String>,
+    /// 查询结果
+    pub results: Vec<GameQueryResult>,
+}
+
+/// 可共享的缓存包
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachePack {
+    /// 魔数，固定为 [`CACHE_PACK_MAGIC`]
+    pub magic: String,
+    /// 结构版本，固定为 [`CACHE_PACK_SCHEMA_VERSION`]
+    pub schema_version: u32,
+    /// 生成该缓存包的程序及版本
+    pub generator: String,
+    /// 缓存包创建时间
+    pub created_at: DateTime<Utc>,
+    /// 缓存条目（按查询关键词排序）
+    pub entries: Vec<CachePackEntry>,
+}
+
+/// 缓存包头部，仅用于在完整解析前校验魔数和版本
+#[derive(Deserialize)]
+struct CachePackHeader {
+    magic: Option<String>,
+    schema_version: Option<u32>,
+}
+
+impl CachePack {
+    /// 从缓存条目创建缓存包
+    pub fn from_entries<'a, I>(entries: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a String, &'a CacheEntry)>,
+    {
+        let mut entries: Vec<CachePackEntry> = entries
+            .into_iter()
+            .map(|(query, entry)| CachePackEntry {
+                query: query.clone(),
+                cached_at: entry.cached_at,
+                providers: entry.providers(),
+                results: entry.results.clone(),
+            })
+            .collect();
+        entries.sort_by(|a, b| a.query.cmp(&b.query));
+
+        CachePack {
+            magic: CACHE_PACK_MAGIC.to_string(),
+            schema_version: CACHE_PACK_SCHEMA_VERSION,
+            generator: format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+            created_at: Utc::now(),
+            entries,
+        }
+    }
+
+    /// 写入缓存包文件
+    ///
+    /// 路径使用 `.zst` 扩展名时写入 zstd 压缩的文件（需要启用 `zstd` feature）
+    pub fn write_to<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut writer = create_json_writer(path.as_ref())?;
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    /// 读取缓存包文件
+    ///
+    /// 魔数不匹配或结构版本不受支持时返回错误；`.zst` 扩展名的文件自动解压
+    pub fn read_from<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let mut content = String::new();
+        open_json_reader(path.as_ref())?.read_to_string(&mut content)?;
+
+        let header: CachePackHeader = serde_json::from_str(&content)
+            .map_err(|e| format!("不是有效的缓存包: {}", e))?;
+        if header.magic.as_deref() != Some(CACHE_PACK_MAGIC) {
+            return Err("不是有效的缓存包: 缺少或错误的魔数".into());
+        }
+        match header.schema_version {
+            Some(CACHE_PACK_SCHEMA_VERSION) => {}
+            Some(version) => {
+                return Err(format!(
+                    "不支持的缓存包版本: {} (当前支持 {})",
+                    version, CACHE_PACK_SCHEMA_VERSION
+                )
+                .into());
+            }
+            None => return Err("不是有效的缓存包: 缺少结构版本".into()),
+        }
+
+        Ok(serde_json::from_str(&content)?)
+    }
+}
+
+/// 持久化缓存：释放时将缓存写回磁盘
+///
+/// 由 [`GameScanner::with_persistent_cache`](crate::scan::GameScanner::with_persistent_cache) 创建，
+/// 随扫描器一起释放。释放时缓存正被占用或写入失败只记录警告，不会 panic
+pub(crate) struct PersistentCache {
+    /// 缓存文件路径
+    pub(crate) path: PathBuf,
+    /// 中间件的缓存
+    pub(crate) cache: Arc<RwLock<HashMap<String, CacheEntry>>>,
+    /// 缓存有效期，已过期的条目不写入
+    pub(crate) ttl: Duration,
+}
+
+impl PersistentCache {
+    /// 将未过期的缓存条目写入缓存文件
+    pub(crate) fn save(&self, cache: &HashMap<String, CacheEntry>) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let pack = CachePack::from_entries(cache.iter().filter(|(_, entry)| !entry.is_expired(self.ttl)));
+        pack.write_to(&self.path)?;
+        Ok(pack.entries.len())
+    }
+}
+
+impl Drop for PersistentCache {
+    fn drop(&mut self) {
+        let result = match self.cache.try_read() {
+            Ok(cache) => self.save(&cache).map(|_| ()),
+            Err(_) => Err("缓存正被占用".into()),
+        };
+        if let Err(e) = result {
+            get_logger().log(
+                &LogEvent::new(LogLevel::Warning, "无法保存缓存")
+                    .with_details(format!("{}: {}", self.path.display(), e)),
+            );
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! 提供者熔断器
+//!
+//! 某个提供者连续失败达到阈值后进入"断开"状态，在冷却期内直接跳过该提供者，
+//! 避免每个分组都等待一个不可用的数据源超时。冷却期结束后进入"半开"状态，
+//! 放行一次查询用于探测恢复情况：成功则恢复正常，失败则重新断开。
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// 熔断器配置
+#[derive(Debug, Clone, Copy)]
+pub struct CircuitConfig {
+    /// 断开前允许的连续失败次数
+    pub failure_threshold: u32,
+    /// 统计连续失败的时间窗口，超过窗口的旧失败不再计数
+    pub window: Duration,
+    /// 断开后跳过该提供者的冷却时间
+    pub cooldown: Duration,
+}
+
+impl Default for CircuitConfig {
+    fn default() -> Self {
+        CircuitConfig {
+            failure_threshold: 5,
+            window: Duration::from_secs(60),
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+impl CircuitConfig {
+    /// 设置断开前允许的连续失败次数（链式调用）
+    pub fn with_failure_threshold(mut self, failure_threshold: u32) -> Self {
+        self.failure_threshold = failure_threshold.max(1);
+        self
+    }
+
+    /// 设置统计连续失败的时间窗口（链式调用）
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// 设置断开后的冷却时间（链式调用）
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+}
+
+/// 单个提供者的熔断状态
+#[derive(Debug, Clone, Copy)]
+enum CircuitState {
+    /// 正常：记录窗口内的连续失败次数
+    Closed { failures: u32, first_failure: Option<Instant> },
+    /// 断开：冷却结束前跳过该提供者
+    Open { until: Instant },
+    /// 半开：已放行一次探测查询，等待其结果
+    HalfOpen { since: Instant },
+}
+
+impl Default for CircuitState {
+    fn default() -> Self {
+        CircuitState::Closed { failures: 0, first_failure: None }
+    }
+}
+
+/// 对外报告的熔断状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitStatus {
+    /// 正常
+    Closed,
+    /// 断开，冷却期内跳过该提供者
+    Open,
+    /// 半开，正在探测恢复情况
+    HalfOpen,
+    /// 未启用熔断器
+    Disabled,
+}
+
+impl fmt::Display for CircuitStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            CircuitStatus::Closed => "closed",
+            CircuitStatus::Open => "open",
+            CircuitStatus::HalfOpen => "half-open",
+            CircuitStatus::Disabled => "disabled",
+        };
+        f.pad(label)
+    }
+}
+
+/// 按提供者名称记录熔断状态的熔断器
+#[derive(Debug, Default)]
+pub struct CircuitBreaker {
+    config: CircuitConfig,
+    states: Mutex<HashMap<String, CircuitState>>,
+}
+
+impl CircuitBreaker {
+    /// 使用指定配置创建熔断器
+    pub fn new(config: CircuitConfig) -> Self {
+        CircuitBreaker {
+            config,
+            states: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 判断是否允许查询该提供者
+    ///
+    /// 冷却结束的断开状态会转为半开并放行本次查询。探测查询超过冷却时间仍未记录结果时
+    /// （如被超时取消），视为探测已丢失，重新放行一次探测
+    pub fn allow(&self, provider: &str) -> bool {
+        let now = Instant::now();
+        let mut states = self.states.lock().unwrap();
+        let state = states.entry(provider.to_string()).or_default();
+
+        match *state {
+            CircuitState::Closed { .. } => true,
+            CircuitState::Open { until } if now >= until => {
+                *state = CircuitState::HalfOpen { since: now };
+                true
+            }
+            CircuitState::HalfOpen { since } if now.duration_since(since) >= self.config.cooldown => {
+                *state = CircuitState::HalfOpen { since: now };
+                true
+            }
+            CircuitState::Open { .. } | CircuitState::HalfOpen { .. } => false,
+        }
+    }
+
+    /// 记录一次成功查询，恢复正常状态
+    pub fn record_success(&self, provider: &str) {
+        let mut states = self.states.lock().unwrap();
+        states.insert(provider.to_string(), CircuitState::default());
+    }
+
+    /// 记录一次失败查询
+    pub fn record_failure(&self, provider: &str) {
+        let now = Instant::now();
+        let mut states = self.states.lock().unwrap();
+        let state = states.entry(provider.to_string()).or_default();
+
+        let failures = match *state {
+            CircuitState::Closed { failures, first_failure: Some(first) }
+                if now.duration_since(first) <= self.config.window =>
+            {
+                *state = CircuitState::Closed { failures: failures + 1, first_failure: Some(first) };
+                failures + 1
+            }
+            CircuitState::Closed { .. } => {
+                *state = CircuitState::Closed { failures: 1, first_failure: Some(now) };
+                1
+            }
+            // 半开状态下的探测失败，直接重新断开
+            CircuitState::HalfOpen { .. } | CircuitState::Open { .. } => self.config.failure_threshold,
+        };
+
+        if failures >= self.config.failure_threshold {
+            *state = CircuitState::Open { until: now + self.config.cooldown };
+        }
+    }
+
+    /// 立即断开该提供者，不再等待连续失败达到阈值
+    ///
+    /// 用于连接超时等说明主机已不可用的错误
+    pub fn trip(&self, provider: &str) {
+        let mut states = self.states.lock().unwrap();
+        states.insert(
+            provider.to_string(),
+            CircuitState::Open { until: Instant::now() + self.config.cooldown },
+        );
+    }
+
+    /// 获取提供者当前的熔断状态
+    pub fn status(&self, provider: &str) -> CircuitStatus {
+        let states = self.states.lock().unwrap();
+        match states.get(provider).copied().unwrap_or_default() {
+            CircuitState::Closed { .. } => CircuitStatus::Closed,
+            CircuitState::Open { .. } => CircuitStatus::Open,
+            CircuitState::HalfOpen { .. } => CircuitStatus::HalfOpen,
+        }
+    }
+
+    /// 判断提供者当前是否处于断开状态
+    pub fn is_open(&self, provider: &str) -> bool {
+        let states = self.states.lock().unwrap();
+        matches!(states.get(provider), Some(CircuitState::Open { .. }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COOLDOWN: Duration = Duration::from_millis(50);
+
+    #[test]
+    fn test_half_open_after_cooldown() {
+        let breaker = CircuitBreaker::new(
+            CircuitConfig::default()
+                .with_failure_threshold(2)
+                .with_cooldown(COOLDOWN),
+        );
+
+        breaker.record_failure("IGDB");
+        assert!(!breaker.is_open("IGDB"));
+        breaker.record_failure("IGDB");
+        assert!(breaker.is_open("IGDB"));
+        assert!(!breaker.allow("IGDB"));
+
+        // 冷却结束，放行一次探测查询，探测期间不再放行其他查询
+        std::thread::sleep(COOLDOWN);
+        assert!(breaker.allow("IGDB"));
+        assert!(!breaker.allow("IGDB"));
+
+        // 探测失败重新断开，探测成功恢复正常
+        breaker.record_failure("IGDB");
+        assert!(breaker.is_open("IGDB"));
+        std::thread::sleep(COOLDOWN);
+        assert!(breaker.allow("IGDB"));
+        breaker.record_success("IGDB");
+        assert!(breaker.allow("IGDB"));
+        assert!(breaker.allow("IGDB"));
+    }
+
+    #[test]
+    fn test_lost_probe_is_retried_after_cooldown() {
+        let breaker = CircuitBreaker::new(CircuitConfig::default().with_cooldown(COOLDOWN));
+        breaker.trip("IGDB");
+        std::thread::sleep(COOLDOWN);
+
+        // 探测查询被取消，没有记录结果
+        assert!(breaker.allow("IGDB"));
+        assert!(!breaker.allow("IGDB"));
+        assert_eq!(breaker.status("IGDB"), CircuitStatus::HalfOpen);
+
+        // 超过冷却时间后重新放行一次探测
+        std::thread::sleep(COOLDOWN);
+        assert!(breaker.allow("IGDB"));
+        assert!(!breaker.allow("IGDB"));
+    }
+}
@@ -1,38 +1,68 @@
+use std::future::Future;
+
 use async_trait::async_trait;
+use futures::future::join_all;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use tokio::sync::Semaphore;
 use dlsite_gamebox::DlsiteClient;
-use dlsite_gamebox::client::search::SearchProductQuery;
+use dlsite_gamebox::client::product_api::ProductApiContent;
+use dlsite_gamebox::client::search::{SearchProduct, SearchProductQuery};
 use dlsite_gamebox::interface::query::SexCategory;
-use crate::models::game_meta_data::GameMetadata;
-use crate::providers::GameDatabaseProvider;
+use crate::logger::{get_logger, LogEvent, LogLevel};
+use crate::models::game_meta_data::{FieldSet, GameMetadata};
+use crate::providers::{GameDatabaseProvider, SearchParams, Tier};
+
+/// 默认获取详细信息的搜索结果数量
+const DEFAULT_DETAIL_LIMIT: usize = 3;
+
+/// 同时进行的详细信息请求数量上限
+const DETAIL_CONCURRENCY: usize = 4;
 
 /// DLsite 数据库提供者
 pub struct DLsiteProvider {
     // 这里可以添加 DLsite 客户端配置
     dlsite_client: DlsiteClient,
+    /// 提供者层级
+    tier: Tier,
+    /// 获取详细信息的搜索结果数量
+    detail_limit: usize,
 }
 
 impl DLsiteProvider {
     pub fn new() -> Self {
         DLsiteProvider {
             dlsite_client: DlsiteClient::default(),
+            tier: Tier::Primary,
+            detail_limit: DEFAULT_DETAIL_LIMIT,
         }
     }
-}
 
-impl Default for DLsiteProvider {
-    fn default() -> Self {
-        Self::new()
+    /// 设置提供者层级（链式调用）
+    pub fn with_tier(mut self, tier: Tier) -> Self {
+        self.tier = tier;
+        self
     }
-}
 
-#[async_trait]
-impl GameDatabaseProvider for DLsiteProvider {
-    fn name(&self) -> &str {
-        "DLsite"
+    /// 设置获取详细信息的搜索结果数量（链式调用）
+    ///
+    /// 排在前面的搜索结果会额外请求作品详情（简介、发售日、类型等），
+    /// 其余结果只包含搜索结果中的基本信息。默认为 3，设置为 0 时不请求详情
+    pub fn with_detail_limit(mut self, limit: usize) -> Self {
+        self.detail_limit = limit;
+        self
     }
 
-    /// 通过标题查找
-    async fn search(&self, title: &str) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+    /// 搜索作品
+    ///
+    /// # 参数
+    /// - `title`: 搜索关键词
+    /// - `maker`: 社团/厂商名称，设置时只保留社团匹配的作品
+    async fn search_products(
+        &self,
+        title: &str,
+        maker: Option<&str>,
+    ) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
         // 使用 dlsite 库的搜索功能（新版 API）
         let search_query = SearchProductQuery {
             sex_category: Some(vec![SexCategory::Male]),
@@ -41,103 +71,251 @@ impl GameDatabaseProvider for DLsiteProvider {
         };
 
         match self.dlsite_client.search().search_product(&search_query).await {
-            Ok(search_result) => {
-                // 只对前3个结果获取详细信息，避免过多API请求
-                let mut results = Vec::new();
-
-                for (index, product) in search_result.products.into_iter().enumerate() {
-                    // 只获取前3个结果的详细信息
-                    if index < 3 {
-                        // 尝试获取详细信息（新版 API）
-                        match self.dlsite_client.product_api().get(&product.id).await {
-                            Ok(detailed_product) => {
-                                // 调试输出：查看 API 返回的原始数据
-                                eprintln!("\n=== DLsite API 详细信息 ===");
-                                eprintln!("Product ID: {}", product.id);
-                                eprintln!("work_name: {}", detailed_product.work_name);
-                                eprintln!("intro: {:?}", detailed_product.intro);
-                                eprintln!("regist_date: {:?}", detailed_product.regist_date);
-                                eprintln!("genres count: {}", detailed_product.genres.len());
-                                for (i, genre) in detailed_product.genres.iter().enumerate() {
-                                    eprintln!("  genre[{}]: {:?}", i, genre);
-                                }
-                                eprintln!("maker_name: {}", detailed_product.maker_name);
-                                eprintln!("creators: {:?}", detailed_product.creators);
-                                eprintln!("========================\n");
-
-                                results.push(GameMetadata {
-                                    title: Some(detailed_product.work_name),
-                                    cover_url: Some(product.thumbnail_url),  // 使用搜索结果的缩略图
-                                    description: detailed_product.intro,
-                                    release_date: detailed_product.regist_date,
-                                    developer: detailed_product.creators.as_ref()
-                                        .and_then(|c| c.voice_by.as_ref())
-                                        .and_then(|v| v.first())
-                                        .map(|v| v.name.clone()),
-                                    publisher: Some(detailed_product.maker_name),
-                                    genres: if detailed_product.genres.is_empty() {
-                                        None
-                                    } else {
-                                        Some(detailed_product.genres.into_iter().map(|genre| genre.name).collect())
-                                    },
-                                    tags: None,
-                                });
-                            }
-                            Err(_) => {
-                                // 如果获取详细信息失败，使用搜索结果的基本信息
-                                results.push(GameMetadata {
-                                    title: Some(product.title),
-                                    cover_url: Some(product.thumbnail_url),
-                                    description: None,
-                                    release_date: None,
-                                    developer: product.creator,
-                                    publisher: Some(product.circle_name),
-                                    genres: None,
-                                    tags: None,
-                                });
-                            }
-                        }
-                    } else {
-                        // 对于其他结果，只使用搜索结果的基本信息
-                        results.push(GameMetadata {
-                            title: Some(product.title),
-                            cover_url: Some(product.thumbnail_url),
-                            description: None,
-                            release_date: None,
-                            developer: product.creator,
-                            publisher: Some(product.circle_name),
-                            genres: None,
-                            tags: None,
-                        });
+            Ok(mut search_result) => {
+                // 按社团过滤；没有任何结果匹配时保留全部结果，避免社团名称写法不同导致漏匹配
+                if let Some(maker) = maker {
+                    if search_result.products.iter().any(|product| maker_matches(&product.circle_name, maker)) {
+                        search_result.products.retain(|product| maker_matches(&product.circle_name, maker));
                     }
                 }
 
+                // 并发获取前 detail_limit 个结果的详细信息，其余结果及获取失败的结果使用搜索结果的基本信息
+                let client = &self.dlsite_client;
+                let products = fetch_details(
+                    search_result.products,
+                    self.detail_limit,
+                    |product| product.id.clone(),
+                    |id| async move { client.product_api().get(&id).await.ok() },
+                )
+                .await;
+
+                let results = products
+                    .into_iter()
+                    .map(|(product, detail)| match detail {
+                        Some(detail) => detailed_metadata(product, detail),
+                        None => basic_metadata(product),
+                    })
+                    .collect();
+
                 Ok(results)
             }
             Err(e) => Err(Box::new(e)),
         }
     }
+}
+
+/// 并发获取前 `limit` 个作品的详细信息，同时进行的请求数量不超过 [`DETAIL_CONCURRENCY`]
+///
+/// # 参数
+/// - `products`: 搜索结果
+/// - `limit`: 获取详细信息的作品数量
+/// - `id`: 取得作品 ID
+/// - `fetch`: 按作品 ID 获取详细信息，失败时返回 `None`
+///
+/// # 返回
+/// 保持原有顺序的 `(作品, 详细信息)` 列表，未获取或获取失败的详细信息为 `None`
+async fn fetch_details<P, D, F, Fut>(
+    products: Vec<P>,
+    limit: usize,
+    id: impl Fn(&P) -> String,
+    fetch: F,
+) -> Vec<(P, Option<D>)>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Option<D>>,
+{
+    let semaphore = Semaphore::new(DETAIL_CONCURRENCY);
+    let details = join_all(products.iter().take(limit).map(|product| {
+        let id = id(product);
+        let semaphore = &semaphore;
+        let fetch = &fetch;
+        async move {
+            let _permit = semaphore.acquire().await.ok()?;
+            fetch(id).await
+        }
+    }))
+    .await;
+
+    let mut details = details.into_iter();
+    products
+        .into_iter()
+        .map(|product| (product, details.next().flatten()))
+        .collect()
+}
+
+/// 使用搜索结果的基本信息构造元数据
+fn basic_metadata(product: SearchProduct) -> GameMetadata {
+    GameMetadata {
+        title: Some(product.title),
+        cover_url: Some(product.thumbnail_url),
+        description: None,
+        release_date: None,
+        developer: product.creator,
+        publisher: Some(product.circle_name),
+        genres: None,
+        tags: None,
+        provider_id: Some(product.id),
+        ..with_price(
+            product.price_sale.unwrap_or(product.price_original),
+            product.price_original,
+            product.price_sale.is_some(),
+        )
+    }
+}
+
+/// 根据作品编号构造主图 URL
+///
+/// 图片按编号向上取整到千位的目录存放，如 `RJ01014447` 位于 `RJ01015000` 目录下；
+/// `RJ` 为同人作品，`VJ` 为商业游戏，`BJ` 为电子书
+fn cover_url_from_code(code: &str) -> Option<String> {
+    let (prefix, number) = code.split_at_checked(2)?;
+    let category = match prefix.to_ascii_uppercase().as_str() {
+        "RJ" => "doujin",
+        "VJ" => "professional",
+        "BJ" => "books",
+        _ => return None,
+    };
+    let value: u64 = number.parse().ok()?;
+    let folder = value.div_ceil(1000) * 1000;
+
+    Some(format!(
+        "https://img.dlsite.jp/modpub/images2/work/{}/{}{:0width$}/{}_img_main.jpg",
+        category,
+        prefix.to_ascii_uppercase(),
+        folder,
+        code.to_ascii_uppercase(),
+        width = number.len(),
+    ))
+}
+
+/// 使用作品详情构造元数据
+///
+/// # 参数
+/// - `id`: 作品编号
+/// - `detail`: 作品详情
+/// - `cover_url`: 封面 URL
+fn metadata_from_detail(id: String, detail: ProductApiContent, cover_url: Option<String>) -> GameMetadata {
+    GameMetadata {
+        title: Some(detail.work_name),
+        cover_url,
+        description: detail.intro,
+        release_date: detail.regist_date,
+        genres: if detail.genres.is_empty() {
+            None
+        } else {
+            Some(detail.genres.into_iter().map(|genre| genre.name).collect())
+        },
+        tags: None,
+        provider_id: Some(id),
+        ..with_maker(detail.maker_name, with_price(detail.price, detail.official_price, detail.is_sale))
+    }
+}
+
+/// 使用作品详情构造元数据（封面使用搜索结果的缩略图）
+fn detailed_metadata(product: SearchProduct, detail: ProductApiContent) -> GameMetadata {
+    // 调试输出：API 返回的原始数据（仅在详细日志模式下输出）
+    let genres: Vec<&str> = detail.genres.iter().map(|genre| genre.name.as_str()).collect();
+    get_logger().log(
+        &LogEvent::new(LogLevel::Debug, format!("DLsite 作品详情: {}", product.id)).with_details(format!(
+            "work_name={} regist_date={:?} maker_name={} genres=[{}] intro={:?} creators={:?}",
+            detail.work_name,
+            detail.regist_date,
+            detail.maker_name,
+            genres.join(", "),
+            detail.intro,
+            detail.creators,
+        )),
+    );
+
+    metadata_from_detail(product.id, detail, Some(product.thumbnail_url))
+}
+
+/// 构造只包含价格信息的元数据，用于补全其余字段
+///
+/// DLsite 的价格以日元计价；当前价格低于原价时也视为打折
+///
+/// # 参数
+/// - `price`: 当前售价
+/// - `official_price`: 原价
+/// - `is_sale`: DLsite 标记的打折状态
+fn with_price(price: i32, official_price: i32, is_sale: bool) -> GameMetadata {
+    GameMetadata {
+        price: u32::try_from(price).ok(),
+        currency: Some("JPY".to_string()),
+        on_sale: Some(is_sale || price < official_price),
+        ..Default::default()
+    }
+}
+
+/// 将社团（maker）记为开发商和发行商
+///
+/// 作品详情中的 `creators.voice_by` 是参演声优而不是制作方。DLsite 的作品由社团制作并发行，
+/// 开发商取社团名称后，游戏信息的开发商列表、稳定 ID 和社团提示匹配都指向同一个社团
+///
+/// # 参数
+/// - `maker_name`: 社团名称
+/// - `metadata`: 需要补全公司信息的元数据
+fn with_maker(maker_name: String, metadata: GameMetadata) -> GameMetadata {
+    GameMetadata {
+        developer: Some(maker_name.clone()),
+        publisher: Some(maker_name),
+        ..metadata
+    }
+}
+
+/// 方括号标签匹配正则：`[汉化]`、`【RPG】`、`［体験版］`
+static BRACKET_TAG_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"[\[【［][^\]】］]*[\]】］]").unwrap()
+});
+
+/// 判断社团/厂商名称是否与过滤条件匹配（忽略大小写和空白，双向包含）
+fn maker_matches(circle_name: &str, maker: &str) -> bool {
+    let normalize = |s: &str| -> String {
+        s.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase).collect()
+    };
+    let circle_name = normalize(circle_name);
+    let maker = normalize(maker);
+    !maker.is_empty() && (circle_name.contains(&maker) || maker.contains(&circle_name))
+}
+
+impl Default for DLsiteProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl GameDatabaseProvider for DLsiteProvider {
+    fn name(&self) -> &str {
+        "DLsite"
+    }
+
+    /// 通过标题查找
+    async fn search(&self, title: &str) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+        self.search_products(title, None).await
+    }
+
+    /// 通过标题查找，开发商/发行商提示作为社团过滤条件
+    async fn search_with_params(
+        &self,
+        title: &str,
+        params: &SearchParams,
+    ) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+        self.search_products(title, params.developer_hint.as_deref()).await
+    }
+
+    /// DLsite 的关键词搜索会因方括号标签而匹配不到结果，搜索前去除这些标签
+    fn preprocess_query(&self, title: &str) -> String {
+        let stripped = BRACKET_TAG_PATTERN.replace_all(title, " ");
+        let query = stripped.split_whitespace().collect::<Vec<_>>().join(" ");
+        if query.is_empty() { title.to_string() } else { query }
+    }
 
     /// 通过ID查找，在Dlsite中是指它专用的站点作品的ID，如：RJ01014447
     async fn get_by_id(&self, id: &str) -> Result<GameMetadata, Box<dyn std::error::Error + Send + Sync>> {
         // 使用 dlsite 库的 API 获取游戏详细信息（新版 API）
         match self.dlsite_client.product_api().get(id).await {
-            Ok(product) => {
-                Ok(GameMetadata {
-                    title: Some(product.work_name),
-                    cover_url: None,
-                    description: product.intro,
-                    release_date: product.regist_date,
-                    developer: product.creators.as_ref().and_then(|c| c.voice_by.as_ref()).and_then(|v| v.first()).map(|v| v.name.clone()),
-                    publisher: Some(product.maker_name),
-                    genres: if product.genres.is_empty() {
-                        None
-                    } else {
-                        Some(product.genres.into_iter().map(|genre| genre.name).collect())
-                    },
-                    tags: None,
-                })
-            }
+            Ok(product) => Ok(metadata_from_detail(id.to_string(), product, cover_url_from_code(id))),
             Err(e) => Err(Box::new(e)),
         }
     }
@@ -150,4 +328,125 @@ impl GameDatabaseProvider for DLsiteProvider {
     fn supports_game_type(&self, game_type: &str) -> bool {
         matches!(game_type, "visual_novel" | "japanese_rpg" | "doujin" | "all")
     }
-}
\ No newline at end of file
+
+    fn tier(&self) -> Tier {
+        self.tier
+    }
+
+    fn provided_fields(&self) -> FieldSet {
+        FieldSet::TITLE
+            | FieldSet::COVER
+            | FieldSet::DESCRIPTION
+            | FieldSet::RELEASE_DATE
+            | FieldSet::DEVELOPER
+            | FieldSet::PUBLISHER
+            | FieldSet::GENRES
+            | FieldSet::PRICE
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_price_from_dlsite_fields() {
+        // 打折中的作品
+        let on_sale = with_price(1320, 2200, true);
+        assert_eq!(on_sale.price, Some(1320));
+        assert_eq!(on_sale.currency.as_deref(), Some("JPY"));
+        assert_eq!(on_sale.on_sale, Some(true));
+
+        // 未标记打折但售价低于原价
+        assert_eq!(with_price(990, 1100, false).on_sale, Some(true));
+
+        // 原价出售
+        let regular = with_price(1100, 1100, false);
+        assert_eq!(regular.price, Some(1100));
+        assert_eq!(regular.on_sale, Some(false));
+
+        // 无效价格
+        assert_eq!(with_price(-1, 0, false).price, None);
+    }
+
+    #[test]
+    fn test_with_maker_credits_circle_as_developer() {
+        let metadata = with_maker("ぷらずまそふと".to_string(), with_price(1320, 2200, true));
+        assert_eq!(metadata.developer.as_deref(), Some("ぷらずまそふと"));
+        assert_eq!(metadata.publisher.as_deref(), Some("ぷらずまそふと"));
+        assert_eq!(metadata.price, Some(1320));
+    }
+
+    #[test]
+    fn test_price_fields_default_when_missing() {
+        let metadata: GameMetadata = serde_json::from_str(r#"{
+            "title": "Old Cache Entry",
+            "cover_url": null,
+            "description": null,
+            "release_date": null,
+            "developer": null,
+            "publisher": null,
+            "genres": null,
+            "tags": null
+        }"#).unwrap();
+
+        assert_eq!(metadata.price, None);
+        assert_eq!(metadata.currency, None);
+        assert_eq!(metadata.on_sale, None);
+    }
+
+    #[test]
+    fn test_preprocess_query_strips_bracket_tags() {
+        let provider = DLsiteProvider::new();
+        assert_eq!(provider.preprocess_query("【RPG】魔女の夜宴 [体験版]"), "魔女の夜宴");
+        assert_eq!(provider.preprocess_query("［サークル］ Title"), "Title");
+        assert_eq!(provider.preprocess_query("[Only Tag]"), "[Only Tag]");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_details_respects_limit() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let calls = AtomicUsize::new(0);
+        let fetch = |id: String| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async move { (id != "RJ02").then(|| format!("detail {}", id)) }
+        };
+        let products = || vec!["RJ01".to_string(), "RJ02".to_string(), "RJ03".to_string()];
+
+        // 上限为 0 时不请求详情，全部使用基本信息
+        let results = fetch_details(products(), 0, |id| id.clone(), fetch).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+        assert!(results.iter().all(|(_, detail)| detail.is_none()));
+
+        // 获取失败的作品回退到基本信息，顺序保持不变
+        let results = fetch_details(products(), 2, |id| id.clone(), fetch).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        let details: Vec<Option<&str>> = results.iter().map(|(_, detail)| detail.as_deref()).collect();
+        assert_eq!(details, vec![Some("detail RJ01"), None, None]);
+        assert_eq!(results[2].0, "RJ03");
+    }
+
+    #[test]
+    fn test_cover_url_from_code() {
+        assert_eq!(
+            cover_url_from_code("RJ01014447").as_deref(),
+            Some("https://img.dlsite.jp/modpub/images2/work/doujin/RJ01015000/RJ01014447_img_main.jpg")
+        );
+        assert_eq!(
+            cover_url_from_code("VJ012000").as_deref(),
+            Some("https://img.dlsite.jp/modpub/images2/work/professional/VJ012000/VJ012000_img_main.jpg")
+        );
+        assert_eq!(cover_url_from_code("XX123456"), None);
+        assert_eq!(cover_url_from_code("RJ"), None);
+    }
+
+    #[test]
+    fn test_maker_matches_circle_names() {
+        assert!(maker_matches("ぷらずまそふと", "ぷらずまそふと"));
+        assert!(maker_matches("Team Cherry", "teamcherry"));
+        assert!(maker_matches("スタジオ・ハッピー 公式", "スタジオ・ハッピー"));
+        assert!(!maker_matches("ねこまんま堂", "ぷらずまそふと"));
+        assert!(!maker_matches("ねこまんま堂", " "));
+    }
+
+}
@@ -0,0 +1,260 @@
+use async_trait::async_trait;
+use std::future::Future;
+use std::path::Path;
+use std::sync::Arc;
+use crate::models::game_meta_data::{FieldSet, GameMetadata};
+use crate::providers::{GameDatabaseProvider, SearchParams, Tier};
+
+/// 顺序回退的组合提供者
+///
+/// 按顺序查询包装的提供者：第一个返回非空结果的提供者的结果即为最终结果，
+/// 之后的提供者不再查询。出错的提供者视为没有结果，继续查询下一个。
+/// 带参数、按平台和按目录的查找同样按此顺序转发给成员提供者。
+///
+/// 作为一个整体注册到中间件，与其他提供者并行查询。
+///
+/// # 示例
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use gamebox::providers::dlsite_provider::DLsiteProvider;
+/// use gamebox::providers::thegamesdb_provider::TheGamesDBProvider;
+/// use gamebox::providers::fallback_provider::FallbackProvider;
+///
+/// let provider = FallbackProvider::new(vec![
+///     Arc::new(DLsiteProvider::new()),
+///     Arc::new(TheGamesDBProvider::new()),
+/// ]);
+/// ```
+pub struct FallbackProvider {
+    providers: Vec<Arc<dyn GameDatabaseProvider>>,
+    /// 组合名称，如 `DLsite>TheGamesDB`
+    name: String,
+}
+
+impl FallbackProvider {
+    /// 创建组合提供者
+    ///
+    /// # 参数
+    /// - `providers`: 按查询顺序排列的提供者，第一个为主要提供者
+    pub fn new(providers: Vec<Arc<dyn GameDatabaseProvider>>) -> Self {
+        let name = providers
+            .iter()
+            .map(|p| p.name())
+            .collect::<Vec<_>>()
+            .join(">");
+
+        FallbackProvider { providers, name }
+    }
+
+    /// 按顺序调用成员提供者，返回第一个非空结果
+    ///
+    /// 出错的提供者视为没有结果；所有提供者都没有结果时，只有全部出错才返回错误
+    ///
+    /// # 参数
+    /// - `query`: 对单个成员提供者执行的查询
+    async fn first_non_empty<'a, F, Fut>(
+        &'a self,
+        query: F,
+    ) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>>
+    where
+        F: Fn(&'a Arc<dyn GameDatabaseProvider>) -> Fut,
+        Fut: Future<Output = Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>>>,
+    {
+        let mut errors = Vec::new();
+
+        for provider in &self.providers {
+            match query(provider).await {
+                Ok(results) if !results.is_empty() => return Ok(results),
+                Ok(_) => {}
+                Err(e) => errors.push(e),
+            }
+        }
+
+        // 所有提供者都没有结果；只有全部出错时才返回错误
+        if !errors.is_empty() && errors.len() == self.providers.len() {
+            return Err(errors.pop().unwrap());
+        }
+        Ok(Vec::new())
+    }
+}
+
+#[async_trait]
+impl GameDatabaseProvider for FallbackProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn search(&self, title: &str) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+        self.first_non_empty(|provider| async move { provider.search(&provider.preprocess_query(title)).await })
+            .await
+    }
+
+    async fn search_for_platform(
+        &self,
+        title: &str,
+        platform: &str,
+    ) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+        self.first_non_empty(|provider| async move {
+            provider.search_for_platform(&provider.preprocess_query(title), platform).await
+        })
+        .await
+    }
+
+    async fn search_with_params(
+        &self,
+        title: &str,
+        params: &SearchParams,
+    ) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+        self.first_non_empty(|provider| async move {
+            provider.search_with_params(&provider.preprocess_query(title), params).await
+        })
+        .await
+    }
+
+    async fn search_in_dir(
+        &self,
+        dir: &Path,
+        title: &str,
+    ) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+        self.first_non_empty(|provider| provider.search_in_dir(dir, title)).await
+    }
+
+    async fn get_by_id(&self, id: &str) -> Result<GameMetadata, Box<dyn std::error::Error + Send + Sync>> {
+        let mut last_error: Box<dyn std::error::Error + Send + Sync> = "No providers configured".into();
+
+        for provider in &self.providers {
+            match provider.get_by_id(id).await {
+                Ok(metadata) => return Ok(metadata),
+                Err(e) => last_error = e,
+            }
+        }
+
+        Err(last_error)
+    }
+
+    fn priority(&self) -> u32 {
+        self.providers.first().map(|p| p.priority()).unwrap_or(50)
+    }
+
+    fn supports_game_type(&self, game_type: &str) -> bool {
+        self.providers
+            .first()
+            .is_some_and(|p| p.supports_game_type(game_type))
+    }
+
+    /// 所有成员提供者都是本地提供者时才视为本地
+    fn is_local(&self) -> bool {
+        !self.providers.is_empty() && self.providers.iter().all(|p| p.is_local())
+    }
+
+    fn tier(&self) -> Tier {
+        self.providers.first().map(|p| p.tier()).unwrap_or_default()
+    }
+
+    /// 任意成员提供者能提供的字段
+    fn provided_fields(&self) -> FieldSet {
+        self.providers
+            .iter()
+            .fold(FieldSet::EMPTY, |fields, p| fields | p.provided_fields())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::mock_provider::{metadata, MockProvider};
+    use crate::providers::sidecar_provider::SidecarProvider;
+
+    #[tokio::test]
+    async fn test_fallback_order() {
+        let dlsite = Arc::new(
+            MockProvider::new("DLsite").with_results_for("Doujin Game", vec![metadata("Doujin Game", "Circle")]),
+        );
+        let bangumi = Arc::new(
+            MockProvider::new("Bangumi")
+                .with_results_for("Doujin Game", vec![metadata("Doujin Game", "Other")])
+                .with_results_for("Anime Game", vec![metadata("Anime Game", "Studio")]),
+        );
+        let provider = FallbackProvider::new(vec![dlsite.clone(), bangumi.clone()]);
+        assert_eq!(provider.name(), "DLsite>Bangumi");
+
+        // 主要提供者有结果时不再查询后续提供者
+        let results = provider.search("Doujin Game").await.unwrap();
+        assert_eq!(results[0].developer.as_deref(), Some("Circle"));
+        assert_eq!(bangumi.calls(), 0);
+
+        // 主要提供者没有结果时回退
+        let results = provider.search("Anime Game").await.unwrap();
+        assert_eq!(results[0].developer.as_deref(), Some("Studio"));
+        assert_eq!(dlsite.calls(), 2);
+        assert_eq!(bangumi.calls(), 1);
+    }
+
+    /// 只在带社团提示或平台时返回结果的提供者
+    struct HintedProvider;
+
+    #[async_trait]
+    impl GameDatabaseProvider for HintedProvider {
+        fn name(&self) -> &str {
+            "Hinted"
+        }
+
+        async fn search(&self, _title: &str) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+            Ok(Vec::new())
+        }
+
+        async fn search_for_platform(
+            &self,
+            title: &str,
+            platform: &str,
+        ) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+            Ok(vec![metadata(title, platform)])
+        }
+
+        async fn search_with_params(
+            &self,
+            title: &str,
+            params: &SearchParams,
+        ) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+            match &params.developer_hint {
+                Some(developer) => Ok(vec![metadata(title, developer)]),
+                None => self.search(title).await,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fallback_forwards_hints_and_dir_lookups() {
+        let fallback = Arc::new(MockProvider::new("Fallback").with_results(vec![metadata("Game", "Fallback")]));
+        let provider = FallbackProvider::new(vec![Arc::new(HintedProvider), fallback.clone()]);
+
+        let params = SearchParams::new().with_developer_hint("Circle");
+        let results = provider.search_with_params("Game", &params).await.unwrap();
+        assert_eq!(results[0].developer.as_deref(), Some("Circle"));
+        let results = provider.search_for_platform("Game", "PC").await.unwrap();
+        assert_eq!(results[0].developer.as_deref(), Some("PC"));
+        assert_eq!(fallback.calls(), 0);
+
+        // 按目录查找同样转发给成员提供者
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("gamebox.json"), r#"{"title": "Local Game"}"#).unwrap();
+        let provider = FallbackProvider::new(vec![Arc::new(HintedProvider), Arc::new(SidecarProvider::new())]);
+        let results = provider.search_in_dir(dir.path(), "Game").await.unwrap();
+        assert_eq!(results[0].title.as_deref(), Some("Local Game"));
+    }
+
+    #[tokio::test]
+    async fn test_fallback_skips_failing_provider() {
+        let down = Arc::new(MockProvider::new("Down").failing());
+        let up = Arc::new(MockProvider::new("Up").with_results(vec![metadata("Game", "Studio")]));
+        let provider = FallbackProvider::new(vec![down, up]);
+
+        let results = provider.search("Game").await.unwrap();
+        assert_eq!(results.len(), 1);
+
+        // 全部出错时返回错误
+        let all_down = FallbackProvider::new(vec![Arc::new(MockProvider::new("Down").failing())]);
+        assert!(all_down.search("Game").await.is_err());
+    }
+}
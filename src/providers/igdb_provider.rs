@@ -1,10 +1,21 @@
 use async_trait::async_trait;
-use crate::models::game_meta_data::GameMetadata;
-use crate::providers::GameDatabaseProvider;
+use crate::error::GameboxError;
+use crate::models::game_meta_data::{FieldSet, GameMetadata};
+use crate::providers::{GameDatabaseProvider, Tier};
 use serde::Deserialize;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
+/// Twitch OAuth 令牌接口地址
+const DEFAULT_TOKEN_URL: &str = "https://id.twitch.tv/oauth2/token";
+
+/// IGDB 游戏查询接口地址
+const DEFAULT_API_URL: &str = "https://api.igdb.com/v4/games";
+
+/// 令牌剩余有效期小于该值时提前刷新
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
 /// IGDB OAuth 令牌响应
 #[derive(Debug, Deserialize)]
 struct TwitchTokenResponse {
@@ -13,6 +24,20 @@ struct TwitchTokenResponse {
     token_type: String,
 }
 
+/// 缓存的访问令牌及其过期时刻
+#[derive(Debug, Clone)]
+struct CachedToken {
+    value: String,
+    expires_at: Instant,
+}
+
+impl CachedToken {
+    /// 令牌是否已过期或即将过期（剩余时间不足 [`TOKEN_REFRESH_MARGIN`]）
+    fn is_expiring(&self) -> bool {
+        Instant::now() + TOKEN_REFRESH_MARGIN >= self.expires_at
+    }
+}
+
 /// IGDB 封面响应
 #[derive(Debug, Deserialize)]
 struct IGDBCover {
@@ -33,6 +58,76 @@ struct IGDBCompany {
     name: Option<String>,
 }
 
+/// IGDB 平台信息
+#[derive(Debug, Deserialize)]
+struct IGDBPlatform {
+    name: Option<String>,
+}
+
+/// IGDB 关联对象（类型、主题）
+///
+/// 查询中展开了 `genres.name` 时为带名称的对象；未展开时 IGDB 只返回 ID
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum IGDBNamedRef {
+    Expanded { name: Option<String> },
+    Id(serde::de::IgnoredAny),
+}
+
+/// 提取关联对象的名称，未展开的 ID 被忽略；没有任何名称时返回 `None`
+fn named_refs(refs: Option<&Vec<IGDBNamedRef>>) -> Option<Vec<String>> {
+    let names: Vec<String> = refs?
+        .iter()
+        .filter_map(|named| match named {
+            IGDBNamedRef::Expanded { name } => name.clone(),
+            IGDBNamedRef::Id(_) => None,
+        })
+        .collect();
+    (!names.is_empty()).then_some(names)
+}
+
+/// IGDB 地区发布日期
+///
+/// 查询中展开了 `release_dates.date` 时为带日期的对象；未展开时 IGDB 只返回 ID
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum IGDBReleaseDate {
+    Expanded { date: Option<u64>, region: Option<u32> },
+    Id(serde::de::IgnoredAny),
+}
+
+/// IGDB 地区代码对应的名称
+fn region_name(region: u32) -> &'static str {
+    match region {
+        1 => "europe",
+        2 => "north_america",
+        3 => "australia",
+        4 => "new_zealand",
+        5 => "japan",
+        6 => "china",
+        7 => "asia",
+        9 => "korea",
+        10 => "brazil",
+        _ => "worldwide",
+    }
+}
+
+/// 提取各地区的发布日期，未展开的 ID 被忽略；没有任何日期时返回 `None`
+fn regional_release_dates(dates: Option<&Vec<IGDBReleaseDate>>) -> Option<Vec<(String, String)>> {
+    let mut regional: Vec<(String, String)> = Vec::new();
+    for release in dates? {
+        if let IGDBReleaseDate::Expanded { date: Some(date), region } = release {
+            let region = region_name(region.unwrap_or(8)).to_string();
+            if let Some(date) = format_release_date(*date) {
+                if !regional.contains(&(region.clone(), date.clone())) {
+                    regional.push((region, date));
+                }
+            }
+        }
+    }
+    (!regional.is_empty()).then_some(regional)
+}
+
 /// IGDB 游戏响应
 #[derive(Debug, Deserialize)]
 struct IGDBGame {
@@ -43,14 +138,40 @@ struct IGDBGame {
     first_release_date: Option<u64>,
     cover: Option<IGDBCover>,
     involved_companies: Option<Vec<IGDBInvolvedCompany>>,
+    platforms: Option<Vec<IGDBPlatform>>,
+    genres: Option<Vec<IGDBNamedRef>>,
+    themes: Option<Vec<IGDBNamedRef>>,
+    release_dates: Option<Vec<IGDBReleaseDate>>,
+    /// 用户评分（0 ~ 100）
+    rating: Option<f64>,
+    /// 媒体评分（0 ~ 100）
+    aggregated_rating: Option<f64>,
+}
+
+impl IGDBGame {
+    /// 评分：优先使用用户评分，没有时使用媒体评分
+    fn rating(&self) -> Option<f32> {
+        self.rating.or(self.aggregated_rating).map(|rating| rating as f32)
+    }
+}
+
+/// 将 IGDB 的 Unix 时间戳格式化为 `YYYY-MM-DD`
+fn format_release_date(timestamp: u64) -> Option<String> {
+    chrono::DateTime::from_timestamp(timestamp as i64, 0).map(|dt| dt.format("%Y-%m-%d").to_string())
 }
 
 /// IGDB 数据库提供者
 pub struct IGDBProvider {
     client_id: String,
     client_secret: String,
-    access_token: Arc<RwLock<Option<String>>>,
+    access_token: Arc<RwLock<Option<CachedToken>>>,
     http_client: reqwest::Client,
+    /// OAuth 令牌接口地址
+    token_url: String,
+    /// 游戏查询接口地址
+    api_url: String,
+    /// 提供者层级
+    tier: Tier,
 }
 
 impl IGDBProvider {
@@ -61,19 +182,54 @@ impl IGDBProvider {
             client_secret: String::new(),
             access_token: Arc::new(RwLock::new(None)),
             http_client: reqwest::Client::new(),
+            token_url: DEFAULT_TOKEN_URL.to_string(),
+            api_url: DEFAULT_API_URL.to_string(),
+            tier: Tier::Primary,
         }
     }
 
     /// 使用凭证创建 IGDB 提供者
     pub fn with_credentials(client_id: String, client_secret: String) -> Self {
+        Self::with_client(client_id, client_secret, reqwest::Client::new())
+    }
+
+    /// 使用凭证和自定义 HTTP 客户端创建 IGDB 提供者
+    ///
+    /// 用于配置代理、自定义 TLS 根证书或连接超时；与其他提供者共用同一个客户端时还可以复用连接池
+    pub fn with_client(client_id: String, client_secret: String, http_client: reqwest::Client) -> Self {
         IGDBProvider {
             client_id,
             client_secret,
             access_token: Arc::new(RwLock::new(None)),
-            http_client: reqwest::Client::new(),
+            http_client,
+            token_url: DEFAULT_TOKEN_URL.to_string(),
+            api_url: DEFAULT_API_URL.to_string(),
+            tier: Tier::Primary,
         }
     }
 
+    /// 设置提供者层级（链式调用）
+    pub fn with_tier(mut self, tier: Tier) -> Self {
+        self.tier = tier;
+        self
+    }
+
+    /// 设置 OAuth 令牌接口地址（链式调用）
+    ///
+    /// 默认使用 Twitch 官方地址，主要用于代理或测试
+    pub fn with_token_url(mut self, token_url: impl Into<String>) -> Self {
+        self.token_url = token_url.into();
+        self
+    }
+
+    /// 设置游戏查询接口地址（链式调用）
+    ///
+    /// 默认使用 IGDB 官方地址，主要用于代理或测试
+    pub fn with_api_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = api_url.into();
+        self
+    }
+
     /// 设置凭证
     pub fn set_credentials(&mut self, client_id: String, client_secret: String) {
         self.client_id = client_id;
@@ -81,25 +237,44 @@ impl IGDBProvider {
     }
 
     /// 获取访问令牌
+    ///
+    /// 缓存的令牌即将过期（剩余不足 60 秒）时自动重新请求
     async fn get_access_token(&self) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        // 检查是否已有令牌
+        // 检查是否已有未过期的令牌
         {
             let token = self.access_token.read().await;
-            if let Some(t) = token.as_ref() {
-                return Ok(t.clone());
+            if let Some(t) = token.as_ref().filter(|t| !t.is_expiring()) {
+                return Ok(t.value.clone());
             }
         }
 
-        // 请求新令牌
-        let url = format!(
-            "https://id.twitch.tv/oauth2/token?client_id={}&client_secret={}&grant_type=client_credentials",
-            self.client_id, self.client_secret
-        );
+        self.request_token().await
+    }
 
+    /// 强制刷新访问令牌
+    ///
+    /// 无论当前是否已有令牌，都会重新请求并替换缓存的令牌，
+    /// 可用于在令牌过期前或配置重载后主动刷新
+    ///
+    /// # 返回
+    /// 刷新失败时返回错误，原有令牌保持不变
+    pub async fn refresh_token(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.request_token().await?;
+        Ok(())
+    }
+
+    /// 请求新令牌并写入缓存
+    async fn request_token(&self) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
         let response = self.http_client
-            .post(&url)
+            .post(&self.token_url)
+            .query(&[
+                ("client_id", self.client_id.as_str()),
+                ("client_secret", self.client_secret.as_str()),
+                ("grant_type", "client_credentials"),
+            ])
             .send()
-            .await?;
+            .await
+            .map_err(GameboxError::from_request)?;
 
         if !response.status().is_success() {
             return Err(format!("Failed to get access token: {}", response.status()).into());
@@ -110,64 +285,77 @@ impl IGDBProvider {
         // 保存令牌
         {
             let mut token = self.access_token.write().await;
-            *token = Some(token_response.access_token.clone());
+            *token = Some(CachedToken {
+                value: token_response.access_token.clone(),
+                expires_at: Instant::now() + Duration::from_secs(token_response.expires_in),
+            });
         }
 
         Ok(token_response.access_token)
     }
-}
 
-impl Default for IGDBProvider {
-    fn default() -> Self {
-        Self::new()
-    }
-}
+    /// 向游戏查询接口发送查询
+    ///
+    /// 返回 401 时说明令牌已失效（如被提前吊销），刷新令牌后重试一次
+    async fn query_games(&self, query: String) -> Result<Vec<IGDBGame>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut access_token = self.get_access_token().await?;
+        let mut refreshed = false;
+
+        loop {
+            let response = self.http_client
+                .post(&self.api_url)
+                .header("Client-ID", &self.client_id)
+                .header("Authorization", format!("Bearer {}", access_token))
+                .body(query.clone())
+                .send()
+                .await
+                .map_err(GameboxError::from_request)?;
+
+            if response.status() == reqwest::StatusCode::UNAUTHORIZED && !refreshed {
+                access_token = self.request_token().await?;
+                refreshed = true;
+                continue;
+            }
 
-#[async_trait]
-impl GameDatabaseProvider for IGDBProvider {
-    fn name(&self) -> &str {
-        "IGDB"
+            if !response.status().is_success() {
+                return Err(format!("IGDB API error: {}", response.status()).into());
+            }
+
+            return Ok(response.json().await?);
+        }
     }
 
-    async fn search(&self, title: &str) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+    /// 搜索游戏，可按平台名称过滤
+    async fn search_games(
+        &self,
+        title: &str,
+        platform: Option<&str>,
+    ) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
         // 检查凭证
         if self.client_id.is_empty() || self.client_secret.is_empty() {
             return Err("IGDB credentials not configured".into());
         }
 
-        // 获取访问令牌
-        let access_token = self.get_access_token().await?;
-
-        // 构建 IGDB API 查询（扩展 cover 和 involved_companies 字段）
+        // 构建 IGDB API 查询（扩展 cover、involved_companies 和 platforms 字段）
+        let platform_filter = platform
+            .map(|platform| format!(" where platforms.name ~ *\"{}\"*;", platform.replace('"', "")))
+            .unwrap_or_default();
         let query = format!(
-            "search \"{}\"; fields name,summary,first_release_date,cover.image_id,involved_companies.company.name,involved_companies.developer,involved_companies.publisher; limit 10;",
-            title.replace('"', "\\\"")
+            "search \"{}\"; fields name,summary,first_release_date,cover.image_id,involved_companies.company.name,involved_companies.developer,involved_companies.publisher,platforms.name,genres.name,themes.name,release_dates.date,release_dates.region,rating,aggregated_rating;{} limit 10;",
+            title.replace('"', "\\\""),
+            platform_filter
         );
 
         // 发送请求到 IGDB API
-        let response = self.http_client
-            .post("https://api.igdb.com/v4/games")
-            .header("Client-ID", &self.client_id)
-            .header("Authorization", format!("Bearer {}", access_token))
-            .body(query)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(format!("IGDB API error: {}", response.status()).into());
-        }
-
-        let games: Vec<IGDBGame> = response.json().await?;
+        let games = self.query_games(query).await?;
 
         // 转换为 GameMetadata
         let results: Vec<GameMetadata> = games
             .into_iter()
             .map(|game| {
-                let release_date = game.first_release_date.map(|timestamp| {
-                    // 转换 Unix 时间戳为年份
-                    let datetime = chrono::DateTime::from_timestamp(timestamp as i64, 0);
-                    datetime.map(|dt| dt.format("%Y").to_string()).unwrap_or_default()
-                });
+                // 转换 Unix 时间戳为完整日期
+                let release_date = game.first_release_date.and_then(format_release_date);
+                let rating = game.rating();
 
                 // 提取开发商和发行商
                 let mut developer = None;
@@ -200,14 +388,51 @@ impl GameDatabaseProvider for IGDBProvider {
                     publisher,
                     description: game.summary,
                     cover_url,
-                    genres: None,
-                    tags: None,
+                    genres: named_refs(game.genres.as_ref()),
+                    tags: named_refs(game.themes.as_ref()),
+                    platforms: game.platforms.map(|platforms| {
+                        platforms.into_iter().filter_map(|platform| platform.name).collect()
+                    }),
+                    price: None,
+                    currency: None,
+                    on_sale: None,
+                    provider_id: game.id.map(|id| id.to_string()),
+                    rating,
+                    release_dates: regional_release_dates(game.release_dates.as_ref()),
+                    extra_cover_urls: Vec::new(),
+                    is_trial: false,
                 }
             })
             .collect();
 
         Ok(results)
     }
+}
+
+impl Default for IGDBProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl GameDatabaseProvider for IGDBProvider {
+    fn name(&self) -> &str {
+        "IGDB"
+    }
+
+    async fn search(&self, title: &str) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+        self.search_games(title, None).await
+    }
+
+    /// 按平台名称过滤搜索结果（如 `Android`、`PC`）
+    async fn search_for_platform(
+        &self,
+        title: &str,
+        platform: &str,
+    ) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+        self.search_games(title, Some(platform)).await
+    }
 
     async fn get_by_id(&self, id: &str) -> Result<GameMetadata, Box<dyn std::error::Error + Send + Sync>> {
         // 检查凭证
@@ -215,39 +440,21 @@ impl GameDatabaseProvider for IGDBProvider {
             return Err("IGDB credentials not configured".into());
         }
 
-        // 获取访问令牌
-        let access_token = self.get_access_token().await?;
-
         // 构建查询（扩展字段）
         let query = format!(
-            "fields name,summary,first_release_date,cover.image_id,involved_companies.company.name,involved_companies.developer,involved_companies.publisher; where id = {};",
+            "fields name,summary,first_release_date,cover.image_id,involved_companies.company.name,involved_companies.developer,involved_companies.publisher,platforms.name,genres.name,themes.name,release_dates.date,release_dates.region,rating,aggregated_rating; where id = {};",
             id
         );
 
         // 发送请求
-        let response = self.http_client
-            .post("https://api.igdb.com/v4/games")
-            .header("Client-ID", &self.client_id)
-            .header("Authorization", format!("Bearer {}", access_token))
-            .body(query)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(format!("IGDB API error: {}", response.status()).into());
-        }
-
-        let games: Vec<IGDBGame> = response.json().await?;
+        let games = self.query_games(query).await?;
 
         if games.is_empty() {
             return Err(format!("Game with ID {} not found", id).into());
         }
 
         let game = &games[0];
-        let release_date = game.first_release_date.map(|timestamp| {
-            let datetime = chrono::DateTime::from_timestamp(timestamp as i64, 0);
-            datetime.map(|dt| dt.format("%Y").to_string()).unwrap_or_default()
-        });
+        let release_date = game.first_release_date.and_then(format_release_date);
 
         // 提取开发商和发行商
         let mut developer = None;
@@ -280,8 +487,19 @@ impl GameDatabaseProvider for IGDBProvider {
             publisher,
             description: game.summary.clone(),
             cover_url,
-            genres: None,
-            tags: None,
+            genres: named_refs(game.genres.as_ref()),
+            tags: named_refs(game.themes.as_ref()),
+            platforms: game.platforms.as_ref().map(|platforms| {
+                platforms.iter().filter_map(|platform| platform.name.clone()).collect()
+            }),
+            price: None,
+            currency: None,
+            on_sale: None,
+            provider_id: game.id.map(|id| id.to_string()),
+            rating: game.rating(),
+            release_dates: regional_release_dates(game.release_dates.as_ref()),
+            extra_cover_urls: Vec::new(),
+            is_trial: false,
         })
     }
 
@@ -292,6 +510,22 @@ impl GameDatabaseProvider for IGDBProvider {
     fn supports_game_type(&self, game_type: &str) -> bool {
         matches!(game_type, "western_game" | "aaa_game" | "indie_game" | "all")
     }
+
+    fn tier(&self) -> Tier {
+        self.tier
+    }
+
+    fn provided_fields(&self) -> FieldSet {
+        FieldSet::TITLE
+            | FieldSet::COVER
+            | FieldSet::DESCRIPTION
+            | FieldSet::RELEASE_DATE
+            | FieldSet::DEVELOPER
+            | FieldSet::PUBLISHER
+            | FieldSet::PLATFORMS
+            | FieldSet::GENRES
+            | FieldSet::TAGS
+    }
 }
 
 #[cfg(test)]
@@ -320,5 +554,188 @@ mod tests {
         assert!(provider.supports_game_type("all"));
         assert!(!provider.supports_game_type("visual_novel"));
     }
+
+    #[test]
+    fn test_igdb_game_deserializes_genres_and_themes() {
+        let games: Vec<IGDBGame> = serde_json::from_str(
+            r#"[
+                {"id": 1, "name": "Hades", "genres": [{"id": 12, "name": "Role-playing (RPG)"}, {"id": 31, "name": "Adventure"}], "themes": [{"id": 1, "name": "Action"}]},
+                {"id": 2, "name": "Celeste", "genres": [8, 31]}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            named_refs(games[0].genres.as_ref()),
+            Some(vec!["Role-playing (RPG)".to_string(), "Adventure".to_string()])
+        );
+        assert_eq!(named_refs(games[0].themes.as_ref()), Some(vec!["Action".to_string()]));
+        // 未展开的类型 ID 不会被当作名称
+        assert_eq!(named_refs(games[1].genres.as_ref()), None);
+        assert_eq!(named_refs(games[1].themes.as_ref()), None);
+    }
+
+    #[test]
+    fn test_igdb_game_rating_and_full_release_date() {
+        let games: Vec<IGDBGame> = serde_json::from_str(
+            r#"[
+                {"id": 1, "name": "Hades", "first_release_date": 1600905600, "rating": 92.5, "aggregated_rating": 93.1},
+                {"id": 2, "name": "Celeste", "aggregated_rating": 88.0},
+                {"id": 3, "name": "Unrated"}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(games[0].first_release_date.and_then(format_release_date).as_deref(), Some("2020-09-24"));
+        assert_eq!(games[0].rating(), Some(92.5));
+        assert_eq!(games[1].rating(), Some(88.0));
+        assert_eq!(games[2].rating(), None);
+    }
+
+    #[test]
+    fn test_igdb_regional_release_dates() {
+        let games: Vec<IGDBGame> = serde_json::from_str(
+            r#"[
+                {"id": 1, "name": "Hades", "release_dates": [
+                    {"date": 1600905600, "region": 8},
+                    {"date": 1600905600, "region": 8},
+                    {"date": 1628553600, "region": 5},
+                    {"region": 1}
+                ]},
+                {"id": 2, "name": "Celeste", "release_dates": [101, 102]}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            regional_release_dates(games[0].release_dates.as_ref()),
+            Some(vec![
+                ("worldwide".to_string(), "2020-09-24".to_string()),
+                ("japan".to_string(), "2021-08-10".to_string()),
+            ])
+        );
+        assert_eq!(regional_release_dates(games[1].release_dates.as_ref()), None);
+    }
+
+    #[tokio::test]
+    async fn test_igdb_refresh_token_replaces_cached_token() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        for token in ["first-token", "second-token"] {
+            Mock::given(method("POST"))
+                .and(path("/oauth2/token"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "access_token": token,
+                    "expires_in": 3600,
+                    "token_type": "bearer",
+                })))
+                .up_to_n_times(1)
+                .mount(&server)
+                .await;
+        }
+
+        let provider = IGDBProvider::with_credentials("id".to_string(), "secret".to_string())
+            .with_token_url(format!("{}/oauth2/token", server.uri()));
+
+        assert_eq!(provider.get_access_token().await.unwrap(), "first-token");
+        // 已缓存的令牌不会重复请求
+        assert_eq!(provider.get_access_token().await.unwrap(), "first-token");
+
+        provider.refresh_token().await.unwrap();
+        assert_eq!(
+            provider.access_token.read().await.as_ref().map(|t| t.value.as_str()),
+            Some("second-token")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_igdb_reauthenticates_on_expiry_and_unauthorized() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        // 令牌有效期短于刷新余量，每次搜索前都会重新认证
+        Mock::given(method("POST"))
+            .and(path("/oauth2/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "short-lived",
+                "expires_in": 1,
+                "token_type": "bearer",
+            })))
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v4/games"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .mount(&server)
+            .await;
+
+        let provider = IGDBProvider::with_credentials("id".to_string(), "secret".to_string())
+            .with_token_url(format!("{}/oauth2/token", server.uri()))
+            .with_api_url(format!("{}/v4/games", server.uri()));
+        provider.search("first").await.unwrap();
+        provider.search("second").await.unwrap();
+        server.verify().await;
+
+        // 游戏接口返回 401 时刷新令牌并重试，而不是直接返回错误
+        let server = MockServer::start().await;
+        for token in ["revoked", "fresh"] {
+            Mock::given(method("POST"))
+                .and(path("/oauth2/token"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "access_token": token,
+                    "expires_in": 3600,
+                    "token_type": "bearer",
+                })))
+                .up_to_n_times(1)
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("POST"))
+            .and(path("/v4/games"))
+            .and(header("Authorization", "Bearer revoked"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v4/games"))
+            .and(header("Authorization", "Bearer fresh"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([{"id": 7, "name": "Celeste"}])))
+            .mount(&server)
+            .await;
+
+        let provider = IGDBProvider::with_credentials("id".to_string(), "secret".to_string())
+            .with_token_url(format!("{}/oauth2/token", server.uri()))
+            .with_api_url(format!("{}/v4/games", server.uri()));
+        let results = provider.search("celeste").await.unwrap();
+        assert_eq!(results[0].title.as_deref(), Some("Celeste"));
+    }
+
+    #[tokio::test]
+    async fn test_igdb_with_client_uses_client_timeout() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/oauth2/token"))
+            .respond_with(ResponseTemplate::new(200).set_delay(std::time::Duration::from_millis(500)))
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_millis(1))
+            .build()
+            .unwrap();
+        let provider = IGDBProvider::with_client("id".to_string(), "secret".to_string(), client)
+            .with_token_url(format!("{}/oauth2/token", server.uri()));
+
+        let error = provider.search("test game").await.unwrap_err();
+        let error = error.downcast_ref::<reqwest::Error>().expect("应为 reqwest 错误");
+        assert!(error.is_timeout());
+    }
 }
 
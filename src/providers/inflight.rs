@@ -0,0 +1,127 @@
+//! 提供者查询合并
+//!
+//! 一次扫描中，不同分组可能在中间件缓存写入之前向同一个提供者发起相同的查询。
+//! 这里按 提供者 + 归一化查询 记录进行中的查询，相同查询的并发调用共享同一次请求的结果，
+//! 查询完成后立即移除记录，不承担缓存职责。
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::OnceCell;
+
+use crate::models::game_meta_data::GameMetadata;
+
+/// 共享的查询结果（错误以字符串形式共享）
+pub(crate) type SharedResult = Result<Vec<GameMetadata>, String>;
+
+/// 进行中的提供者查询
+#[derive(Default)]
+pub(crate) struct InflightQueries {
+    queries: Mutex<HashMap<String, Arc<OnceCell<SharedResult>>>>,
+}
+
+/// 查询结束（完成或被取消）时移除进行中的记录
+///
+/// 发起者被取消（如外层超时）时立即移除记录，之后的查询重新发起请求；等待中的调用者
+/// 仍持有同一个 `OnceCell`，会接替执行查询。记录已被替换时不做处理
+struct RemoveOnDrop<'a> {
+    queries: &'a Mutex<HashMap<String, Arc<OnceCell<SharedResult>>>>,
+    key: String,
+    cell: Arc<OnceCell<SharedResult>>,
+    is_leader: bool,
+}
+
+impl Drop for RemoveOnDrop<'_> {
+    fn drop(&mut self) {
+        if !self.is_leader && !self.cell.initialized() {
+            return;
+        }
+        let mut queries = self.queries.lock().unwrap();
+        if queries.get(&self.key).is_some_and(|cell| Arc::ptr_eq(cell, &self.cell)) {
+            queries.remove(&self.key);
+        }
+    }
+}
+
+/// 归一化查询关键词：忽略大小写和多余空白
+///
+/// 只去除首尾空白、合并连续空白并转为小写；中日韩文字没有大小写，不受影响
+pub(crate) fn normalize_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+impl InflightQueries {
+    /// 执行查询；同一提供者的相同查询正在进行时，等待并共享其结果
+    ///
+    /// 发起者被取消时由等待中的调用者接替执行，查询结束后记录总会被移除，
+    /// 不会把某次结果（包括错误）永久固定下来
+    ///
+    /// # 参数
+    /// - `provider`: 提供者名称
+    /// - `query`: 查询键（关键词及搜索参数）
+    /// - `run`: 实际执行查询的 future，只有第一个调用者会执行
+    pub(crate) async fn run<F>(&self, provider: &str, query: &str, run: F) -> SharedResult
+    where
+        F: Future<Output = SharedResult>,
+    {
+        let key = format!("{}\u{1f}{}", provider, normalize_query(query));
+        let (cell, is_leader) = {
+            let mut queries = self.queries.lock().unwrap();
+            match queries.get(&key) {
+                Some(cell) => (Arc::clone(cell), false),
+                None => {
+                    let cell = Arc::new(OnceCell::new());
+                    queries.insert(key.clone(), Arc::clone(&cell));
+                    (cell, true)
+                }
+            }
+        };
+
+        // 查询完成或发起者被取消后移除记录，之后的查询由中间件缓存负责
+        let _guard = RemoveOnDrop { queries: &self.queries, key, cell: Arc::clone(&cell), is_leader };
+
+        cell.get_or_init(|| run).await.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_cancelled_leader_does_not_pin_result() {
+        let inflight = Arc::new(InflightQueries::default());
+
+        // 发起者被取消，等待中的调用者接替执行
+        let leader = {
+            let inflight = Arc::clone(&inflight);
+            tokio::spawn(async move {
+                inflight.run("Mock", "game", async {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    Ok(Vec::new())
+                }).await
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let follower = {
+            let inflight = Arc::clone(&inflight);
+            tokio::spawn(async move {
+                inflight.run("Mock", "game", async { Err("first".to_string()) }).await
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        leader.abort();
+        assert_eq!(follower.await.unwrap().unwrap_err(), "first");
+
+        // 之后的查询重新执行，不会沿用接替者的结果
+        let result = inflight.run("Mock", "game", async { Err("second".to_string()) }).await;
+        assert_eq!(result.unwrap_err(), "second");
+        assert!(inflight.queries.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,178 @@
+//! 测试用的模拟提供者
+//!
+//! 按查询关键词返回预设结果，供各模块的单元测试共用。
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::models::game_meta_data::{FieldSet, GameMetadata};
+use crate::providers::{GameDatabaseProvider, Tier};
+
+/// 模拟提供者
+pub(crate) struct MockProvider {
+    name: String,
+    /// 未单独配置的查询返回的结果
+    default_results: Vec<GameMetadata>,
+    /// 按查询关键词配置的结果
+    query_results: HashMap<String, Vec<GameMetadata>>,
+    /// 提供者层级
+    tier: Tier,
+    /// 按 ID 配置的结果
+    items: HashMap<String, GameMetadata>,
+    /// 声明可靠提供的字段
+    provided_fields: FieldSet,
+    /// 是否总是返回错误
+    failing: bool,
+    /// 前若干次搜索返回错误，之后正常返回
+    failures_before_success: usize,
+    /// 搜索前的查询预处理
+    query_preprocessor: Option<fn(&str) -> String>,
+    /// 每次搜索或按 ID 获取前等待的时间，用于模拟慢速数据源
+    delay: Option<Duration>,
+    /// 支持的游戏类型（`None` 表示支持所有类型）
+    game_types: Option<Vec<String>>,
+    /// 搜索调用次数
+    calls: AtomicUsize,
+}
+
+impl MockProvider {
+    pub(crate) fn new(name: &str) -> Self {
+        MockProvider {
+            name: name.to_string(),
+            default_results: Vec::new(),
+            query_results: HashMap::new(),
+            tier: Tier::Primary,
+            items: HashMap::new(),
+            provided_fields: FieldSet::ALL,
+            failing: false,
+            failures_before_success: 0,
+            query_preprocessor: None,
+            delay: None,
+            game_types: None,
+            calls: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn with_results(mut self, results: Vec<GameMetadata>) -> Self {
+        self.default_results = results;
+        self
+    }
+
+    pub(crate) fn with_results_for(mut self, query: &str, results: Vec<GameMetadata>) -> Self {
+        self.query_results.insert(query.to_string(), results);
+        self
+    }
+
+    pub(crate) fn with_item(mut self, id: &str, metadata: GameMetadata) -> Self {
+        self.items.insert(id.to_string(), metadata);
+        self
+    }
+
+    pub(crate) fn with_tier(mut self, tier: Tier) -> Self {
+        self.tier = tier;
+        self
+    }
+
+    pub(crate) fn with_provided_fields(mut self, fields: FieldSet) -> Self {
+        self.provided_fields = fields;
+        self
+    }
+
+    pub(crate) fn failing(mut self) -> Self {
+        self.failing = true;
+        self
+    }
+
+    pub(crate) fn failing_times(mut self, failures: usize) -> Self {
+        self.failures_before_success = failures;
+        self
+    }
+
+    pub(crate) fn with_query_preprocessor(mut self, preprocessor: fn(&str) -> String) -> Self {
+        self.query_preprocessor = Some(preprocessor);
+        self
+    }
+
+    pub(crate) fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    pub(crate) fn with_game_types(mut self, game_types: &[&str]) -> Self {
+        self.game_types = Some(game_types.iter().map(|t| t.to_string()).collect());
+        self
+    }
+
+    pub(crate) fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+/// 构造只带标题和开发商的元数据
+pub(crate) fn metadata(title: &str, developer: &str) -> GameMetadata {
+    GameMetadata {
+        title: Some(title.to_string()),
+        developer: Some(developer.to_string()),
+        ..Default::default()
+    }
+}
+
+#[async_trait]
+impl GameDatabaseProvider for MockProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn search(&self, title: &str) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst);
+
+        if let Some(delay) = self.delay {
+            tokio::time::sleep(delay).await;
+        }
+
+        if self.failing || call < self.failures_before_success {
+            return Err(format!("{} 不可用", self.name).into());
+        }
+
+        Ok(self
+            .query_results
+            .get(title)
+            .cloned()
+            .unwrap_or_else(|| self.default_results.clone()))
+    }
+
+    fn preprocess_query(&self, title: &str) -> String {
+        match self.query_preprocessor {
+            Some(preprocessor) => preprocessor(title),
+            None => title.to_string(),
+        }
+    }
+
+    async fn get_by_id(&self, id: &str) -> Result<GameMetadata, Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(delay) = self.delay {
+            tokio::time::sleep(delay).await;
+        }
+
+        self.items
+            .get(id)
+            .cloned()
+            .ok_or_else(|| format!("Game with ID {} not found", id).into())
+    }
+
+    fn supports_game_type(&self, game_type: &str) -> bool {
+        self.game_types
+            .as_ref()
+            .is_none_or(|types| types.iter().any(|t| t == game_type))
+    }
+
+    fn tier(&self) -> Tier {
+        self.tier
+    }
+
+    fn provided_fields(&self) -> FieldSet {
+        self.provided_fields
+    }
+}
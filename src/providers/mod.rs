@@ -1,14 +1,42 @@
 pub mod dlsite_provider;
 pub mod igdb_provider;
 pub mod thegamesdb_provider;
+pub mod fallback_provider;
+pub mod sidecar_provider;
+pub mod cache;
+pub mod circuit_breaker;
+pub mod retry_budget;
+pub mod retry;
+pub mod status;
+pub mod scoring;
+mod inflight;
+mod named_provider;
+#[cfg(feature = "rusqlite")]
+pub mod sqlite_provider;
+#[cfg(test)]
+pub(crate) mod mock_provider;
 
 use async_trait::async_trait;
+use futures::StreamExt;
 use std::collections::HashMap;
+use std::future::Future;
+use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::{RwLock, Semaphore};
 use serde::{Serialize, Deserialize};
-use crate::models::game_meta_data::GameMetadata;
+use crate::error::GameboxError;
+use crate::models::game_meta_data::{FieldSet, GameMetadata};
 use crate::logger::{get_logger, LogEvent, LogLevel};
+use crate::providers::cache::{CacheCounters, CacheEntry, CachePack, CacheStats, PersistentCache};
+use crate::providers::circuit_breaker::{CircuitBreaker, CircuitConfig};
+use crate::providers::inflight::{normalize_query, InflightQueries};
+use crate::providers::named_provider::NamedProvider;
+use crate::scan::{fold_case, is_trial_title, normalize_company_field};
+use crate::providers::retry::{is_retryable_error, RetryPolicy};
+use crate::providers::retry_budget::RetryBudget;
+use crate::providers::status::{ProviderMetricsRegistry, ProviderStatus};
+use crate::providers::scoring::{ConfidenceScorer, DefaultScorer};
+use crate::providers::circuit_breaker::CircuitStatus;
 
 /// 计算两个字符串的相似度（Levenshtein 距离）
 fn string_similarity(s1: &str, s2: &str) -> f32 {
@@ -71,15 +99,20 @@ fn levenshtein_distance(s1: &str, s2: &str) -> usize {
     prev_row[len2]
 }
 
+/// 按置信度从高到低排序
+fn sort_by_confidence(results: &mut [GameQueryResult]) {
+    results.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
+}
+
 /// 计算搜索结果的置信度
-/// 基于标题匹配度和数据完整度
+/// 基于标题匹配度、开发商/发行商匹配和数据完整度，体验版结果会被扣分
 fn calculate_confidence(search_title: &str, metadata: &GameMetadata) -> f32 {
     let mut confidence = 0.0;
 
     // 1. 标题匹配度 (最高 0.7)
     if let Some(title) = &metadata.title {
-        let search_lower = search_title.to_lowercase();
-        let title_lower = title.to_lowercase();
+        let search_lower = fold_case(search_title);
+        let title_lower = fold_case(title);
 
         // 完全匹配
         if search_lower == title_lower {
@@ -131,23 +164,236 @@ fn calculate_confidence(search_title: &str, metadata: &GameMetadata) -> f32 {
         }
     }
 
-    // 2. 数据完整度 (最高 0.3)
-    let mut completeness = 0.0;
-    if metadata.title.is_some() { completeness += 0.08; }
-    if metadata.cover_url.is_some() { completeness += 0.05; }
-    if metadata.description.is_some() { completeness += 0.04; }
-    if metadata.release_date.is_some() { completeness += 0.04; }
-    if metadata.developer.is_some() { completeness += 0.04; }
-    if metadata.publisher.is_some() { completeness += 0.03; }
-    if metadata.genres.is_some() { completeness += 0.01; }
-    if metadata.tags.is_some() { completeness += 0.01; }
+    // 2. 开发商/发行商匹配 (最高 0.1)
+    confidence += company_match_bonus(search_title, metadata);
 
-    confidence += completeness;
+    // 3. 数据完整度 (最高 0.3，按公司加成的上限等比缩小，保证总分不超过 1.0)
+    confidence += metadata.completeness() * (MAX_COMPLETENESS - COMPANY_MATCH_BONUS) / MAX_COMPLETENESS;
+
+    // 4. 体验版扣分（搜索词本身指明体验版时不扣分）
+    let is_trial = metadata.is_trial || metadata.title.as_deref().is_some_and(is_trial_title);
+    if is_trial && !is_trial_title(search_title) {
+        confidence -= TRIAL_PENALTY;
+    }
 
     // 确保置信度在 0.0 到 1.0 之间
     confidence.max(0.0).min(1.0)
 }
 
+/// 按标题标记体验版/试玩版结果
+///
+/// 本地提供者（附属文件等）的数据由用户维护，按原样信任，不根据标题标记
+///
+/// # 参数
+/// - `provider`: 返回该结果的提供者
+/// - `info`: 需要标记的元数据
+fn flag_trial(provider: &dyn GameDatabaseProvider, info: &mut GameMetadata) {
+    if !provider.is_local() {
+        info.is_trial |= info.title.as_deref().is_some_and(is_trial_title);
+    }
+}
+
+/// 体验版/试玩版结果的置信度扣分
+const TRIAL_PENALTY: f32 = 0.2;
+
+/// 数据完整度能提供的最高置信度
+const MAX_COMPLETENESS: f32 = 0.3;
+
+/// 搜索词包含开发商或发行商名称时的置信度加成
+const COMPANY_MATCH_BONUS: f32 = 0.1;
+
+/// 公司名称中不参与匹配的通用后缀
+const COMPANY_STOP_WORDS: [&str; 8] = ["inc", "ltd", "llc", "co", "corp", "corporation", "games", "studio"];
+
+/// 计算搜索词命中开发商或发行商名称带来的置信度加成
+///
+/// 搜索词按空白和标点切分为词语，公司名称去掉通用后缀（如 `Inc.`、`Ltd.`）后，
+/// 所有词语都出现在搜索词中即视为命中
+fn company_match_bonus(search_title: &str, metadata: &GameMetadata) -> f32 {
+    let words = |s: &str| -> Vec<String> {
+        fold_case(s)
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+    let query_words = words(search_title);
+
+    let matched = [&metadata.developer, &metadata.publisher]
+        .iter()
+        .filter_map(|company| company.as_deref())
+        .flat_map(normalize_company_field)
+        .any(|company| {
+            let company_words: Vec<String> = words(&company)
+                .into_iter()
+                .filter(|w| !COMPANY_STOP_WORDS.contains(&w.as_str()))
+                .collect();
+            !company_words.is_empty() && company_words.iter().all(|w| query_words.contains(w))
+        });
+
+    if matched { COMPANY_MATCH_BONUS } else { 0.0 }
+}
+
+/// 开发商提示命中时的置信度加成
+const DEVELOPER_HINT_BONUS: f32 = 0.15;
+
+/// 判断公司名称是否与提示模糊匹配（按 [`fold_case`] 忽略大小写，包含关系或高相似度）
+fn company_matches_hint(company: &str, hint: &str) -> bool {
+    let company = fold_case(company.trim());
+    let hint = fold_case(hint.trim());
+
+    if company.is_empty() || hint.is_empty() {
+        return false;
+    }
+
+    company.contains(&hint) || hint.contains(&company) || string_similarity(&company, &hint) >= 0.9
+}
+
+/// 计算开发商提示带来的置信度加成
+/// developer 或 publisher 任意一个与提示匹配即可获得加成
+fn developer_hint_bonus(hint: &str, metadata: &GameMetadata) -> f32 {
+    let matched = [&metadata.developer, &metadata.publisher]
+        .iter()
+        .any(|company| company.as_deref().is_some_and(|c| company_matches_hint(c, hint)));
+
+    if matched { DEVELOPER_HINT_BONUS } else { 0.0 }
+}
+
+/// 平台提示命中时的置信度加成
+const PLATFORM_HINT_BONUS: f32 = 0.1;
+
+/// 计算平台提示带来的置信度加成
+/// 结果的任意平台与提示匹配（忽略大小写，包含关系）即可获得加成
+fn platform_hint_bonus(hint: &str, metadata: &GameMetadata) -> f32 {
+    let hint = hint.trim().to_lowercase();
+    if hint.is_empty() {
+        return 0.0;
+    }
+
+    let matched = metadata.platforms.iter().flatten().any(|platform| {
+        let platform = platform.trim().to_lowercase();
+        !platform.is_empty() && (platform.contains(&hint) || hint.contains(&platform))
+    });
+
+    if matched { PLATFORM_HINT_BONUS } else { 0.0 }
+}
+
+/// 合并搜索结果时，归一化标题的相似度达到该值即视为同一游戏
+const DEFAULT_MERGE_THRESHOLD: f32 = 0.85;
+
+/// 归一化标题：忽略大小写、空白和标点，用于判断不同提供者的结果是否为同一游戏
+fn merge_key(metadata: &GameMetadata) -> String {
+    fold_case(metadata.title.as_deref().unwrap_or_default())
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
+/// 提取标题结尾的续作编号：阿拉伯数字（`Portal 2`、`ゲーム2`）或罗马数字（`Final Fantasy VII`）
+///
+/// 编号不同（包括一方没有编号）的标题即使相似度很高也不是同一游戏
+fn sequel_number(title: &str) -> Option<u32> {
+    const ROMAN: [&str; 15] = ["i", "ii", "iii", "iv", "v", "vi", "vii", "viii", "ix", "x", "xi", "xii", "xiii", "xiv", "xv"];
+
+    let folded = fold_case(title);
+    let last_word = folded.split(|c: char| !c.is_alphanumeric()).rfind(|w| !w.is_empty())?;
+    if let Some(index) = ROMAN.iter().position(|numeral| *numeral == last_word) {
+        return Some(index as u32 + 1);
+    }
+
+    let digits = last_word.len() - last_word.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    last_word[last_word.len() - digits..].parse().ok()
+}
+
+/// 将另一个结果的元数据合并到主结果：主结果缺失的字段使用另一结果的值，类型、标签、平台和封面取并集
+fn merge_metadata(primary: &mut GameMetadata, other: GameMetadata) {
+    fn fill<T>(field: &mut Option<T>, value: Option<T>) {
+        if field.is_none() {
+            *field = value;
+        }
+    }
+    fn union(field: &mut Option<Vec<String>>, values: Option<Vec<String>>) {
+        let Some(values) = values else { return };
+        let merged = field.get_or_insert_with(Vec::new);
+        for value in values {
+            if !merged.iter().any(|v| v.eq_ignore_ascii_case(&value)) {
+                merged.push(value);
+            }
+        }
+    }
+
+    fill(&mut primary.title, other.title);
+    // 另一结果的封面加入封面列表，主结果没有封面时取第一个
+    for url in other.cover_url.into_iter().chain(other.extra_cover_urls) {
+        if primary.cover_url.is_none() {
+            primary.cover_url = Some(url);
+        } else if primary.cover_url.as_deref() != Some(url.as_str()) && !primary.extra_cover_urls.contains(&url) {
+            primary.extra_cover_urls.push(url);
+        }
+    }
+    fill(&mut primary.description, other.description);
+    fill(&mut primary.release_date, other.release_date);
+    fill(&mut primary.developer, other.developer);
+    fill(&mut primary.publisher, other.publisher);
+    fill(&mut primary.price, other.price);
+    fill(&mut primary.currency, other.currency);
+    fill(&mut primary.on_sale, other.on_sale);
+    fill(&mut primary.provider_id, other.provider_id);
+    fill(&mut primary.rating, other.rating);
+    fill(&mut primary.release_dates, other.release_dates);
+    union(&mut primary.genres, other.genres);
+    union(&mut primary.tags, other.tags);
+    union(&mut primary.platforms, other.platforms);
+}
+
+/// 合并不同提供者返回的同一游戏
+///
+/// 结果先按置信度从高到低排序，再按归一化标题的相似度分组，每组以置信度最高的结果为主合并为一条结果，
+/// 来源为各提供者名称以 `+` 连接（如 `DLsite+IGDB`），置信度取组内最高值。
+/// 同一提供者返回的多个结果是不同的游戏，不会合并；结尾续作编号不同的标题
+/// （如 `Portal` 与 `Portal 2`）也不会合并
+///
+/// # 参数
+/// - `results`: 需要合并的结果
+/// - `threshold`: 视为同一游戏的最低标题相似度
+fn merge_results(mut results: Vec<GameQueryResult>, threshold: f32) -> Vec<GameQueryResult> {
+    // 每组的第一个结果就是主结果
+    sort_by_confidence(&mut results);
+
+    // (归一化标题, 续作编号, 合并后的结果, 来源提供者)
+    let mut clusters: Vec<(String, Option<u32>, GameQueryResult, Vec<String>)> = Vec::new();
+
+    for result in results {
+        let key = merge_key(&result.info);
+        let sequel = sequel_number(result.info.title.as_deref().unwrap_or_default());
+        let cluster = clusters.iter_mut().find(|(cluster_key, cluster_sequel, _, sources)| {
+            !key.is_empty()
+                && !sources.contains(&result.source)
+                && *cluster_sequel == sequel
+                && string_similarity(cluster_key, &key) >= threshold
+        });
+        match cluster {
+            Some((_, _, merged, sources)) => {
+                sources.push(result.source);
+                merged.confidence = merged.confidence.max(result.confidence);
+                merge_metadata(&mut merged.info, result.info);
+            }
+            None => {
+                let sources = vec![result.source.clone()];
+                clusters.push((key, sequel, result, sources));
+            }
+        }
+    }
+
+    clusters
+        .into_iter()
+        .map(|(_, _, mut merged, sources)| {
+            merged.source = sources.join("+");
+            merged
+        })
+        .collect()
+}
+
 /// 游戏中间件
 /// 游戏数据库查询结果
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -160,6 +406,113 @@ pub struct GameQueryResult {
     pub confidence: f32,
 }
 
+/// 详细搜索结果：查询结果以及出错的提供者
+///
+/// 用于区分"没有找到结果"和"提供者全部出错"（如认证失败）
+#[derive(Debug, Clone, Default)]
+pub struct SearchOutcome {
+    /// 查询结果（按置信度从高到低排序）
+    pub results: Vec<GameQueryResult>,
+    /// 出错的提供者：(提供者名称, 错误信息)
+    pub errors: Vec<(String, String)>,
+}
+
+/// 按 ID 获取的结果及其在其他提供者中的交叉引用
+#[derive(Debug, Clone)]
+pub struct CrossReferencedResult {
+    /// 按 ID 获取的结果
+    pub primary: GameQueryResult,
+    /// 其他提供者中按标题匹配到的同一游戏（按置信度从高到低排序）
+    pub cross_references: Vec<GameQueryResult>,
+}
+
+/// 搜索参数
+///
+/// 为单次搜索提供额外的提示信息，用于在基础置信度之上调整结果排序
+#[derive(Debug, Clone, Default)]
+pub struct SearchParams {
+    /// 开发商/发行商提示：结果的 developer 或 publisher 与之模糊匹配时获得置信度加成，
+    /// 用于区分同名游戏（如重制版、同名不同作）
+    pub developer_hint: Option<String>,
+    /// 平台提示：支持按平台过滤的提供者只返回该平台的结果，
+    /// 其他提供者中平台匹配的结果获得置信度加成，用于区分同名游戏的不同平台版本
+    pub platform_hint: Option<String>,
+    /// 游戏类型（如 `visual_novel`、`western_game`）：设置后只查询 `supports_game_type` 支持该类型的提供者
+    pub game_type: Option<String>,
+}
+
+impl SearchParams {
+    /// 创建空的搜索参数（不带任何提示）
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置开发商/发行商提示
+    pub fn with_developer_hint(mut self, hint: impl Into<String>) -> Self {
+        self.developer_hint = Some(hint.into());
+        self
+    }
+
+    /// 设置平台提示
+    pub fn with_platform_hint(mut self, platform: impl Into<String>) -> Self {
+        self.platform_hint = Some(platform.into());
+        self
+    }
+
+    /// 设置游戏类型
+    pub fn with_game_type(mut self, game_type: impl Into<String>) -> Self {
+        self.game_type = Some(game_type.into());
+        self
+    }
+
+    /// 带参数搜索的缓存键：参数可能改变提供者返回的结果，因此不同参数分开缓存
+    ///
+    /// 标题忽略大小写和多余空白，`Elden Ring` 与 `elden  ring ` 共用同一个缓存条目
+    fn cache_key(&self, title: &str) -> String {
+        let mut key = normalize_query(title);
+        if let Some(game_type) = &self.game_type {
+            key.push_str(&format!("\u{1f}type={}", game_type));
+        }
+        if let Some(platform) = &self.platform_hint {
+            key.push_str(&format!("\u{1f}platform={}", platform));
+        }
+        if let Some(developer) = &self.developer_hint {
+            key.push_str(&format!("\u{1f}developer={}", developer));
+        }
+        key
+    }
+
+    /// 将搜索参数的提示加成应用到查询结果（不重新排序）
+    fn apply(&self, results: &mut [GameQueryResult]) {
+        for result in results.iter_mut() {
+            let mut bonus = 0.0;
+            if let Some(hint) = &self.developer_hint {
+                bonus += developer_hint_bonus(hint, &result.info);
+            }
+            if let Some(hint) = &self.platform_hint {
+                bonus += platform_hint_bonus(hint, &result.info);
+            }
+            result.confidence = (result.confidence + bonus).min(1.0);
+        }
+    }
+}
+
+/// 提供者层级
+///
+/// 与 [`GameDatabaseProvider::priority`] 相互独立：优先级只决定同一层级内的排序，
+/// 层级决定是否需要查询该提供者。中间件先查询 `Primary` 层级，
+/// 只有在高层级没有产生足够可信的结果时才会继续查询 `Secondary` 和 `Fallback` 层级，
+/// 从而在保持覆盖率的同时减少 API 调用。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Tier {
+    /// 主要数据源，总是查询
+    #[default]
+    Primary,
+    /// 次要数据源，主要数据源没有可信结果时查询
+    Secondary,
+    /// 兜底数据源，前两个层级都没有可信结果时查询
+    Fallback,
+}
 
 /// 游戏数据库提供者特征
 #[async_trait]
@@ -170,6 +523,52 @@ pub trait GameDatabaseProvider: Send + Sync {
     /// 搜索游戏
     async fn search(&self, title: &str) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>>;
 
+    /// 搜索指定平台的游戏
+    ///
+    /// 支持按平台过滤的提供者可以覆盖此方法只返回该平台的结果；默认忽略平台，等同于 [`search`](Self::search)
+    async fn search_for_platform(
+        &self,
+        title: &str,
+        _platform: &str,
+    ) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+        self.search(title).await
+    }
+
+    /// 带搜索参数搜索游戏
+    ///
+    /// 能利用提示信息缩小结果范围的提供者可以覆盖此方法（如按社团/厂商过滤）；
+    /// 默认设置了平台提示时调用 [`search_for_platform`](Self::search_for_platform)，否则调用 [`search`](Self::search)
+    async fn search_with_params(
+        &self,
+        title: &str,
+        params: &SearchParams,
+    ) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+        match &params.platform_hint {
+            Some(platform) => self.search_for_platform(title, platform).await,
+            None => self.search(title).await,
+        }
+    }
+
+    /// 按游戏目录查找（如读取目录中的附属元数据文件）
+    ///
+    /// 能利用本地目录的提供者可以覆盖此方法，扫描器会传入分组的游戏根目录；
+    /// 默认不支持按目录查找，返回空结果
+    async fn search_in_dir(
+        &self,
+        _dir: &Path,
+        _title: &str,
+    ) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+
+    /// 搜索前对标题进行数据源特定的预处理
+    ///
+    /// 不同数据源对关键词的要求不同（如 DLsite 需要去除方括号标签，而其他数据源需要保留），
+    /// 中间件在调用该提供者的搜索前使用此方法处理标题。默认原样返回
+    fn preprocess_query(&self, title: &str) -> String {
+        title.to_string()
+    }
+
     /// 获取游戏详情（如果支持）
     async fn get_by_id(&self, _id: &str) -> Result<GameMetadata, Box<dyn std::error::Error + Send + Sync>> {
         Err("Not implemented".into())
@@ -184,6 +583,25 @@ pub trait GameDatabaseProvider: Send + Sync {
     fn supports_game_type(&self, _game_type: &str) -> bool {
         true
     }
+
+    /// 是否只读取本地数据（附属文件、本地数据库等），不访问网络
+    ///
+    /// 中间件启用仅本地模式时只查询返回 `true` 的提供者。默认为 `false`
+    fn is_local(&self) -> bool {
+        false
+    }
+
+    /// 获取提供者所在的层级（默认为主要数据源）
+    fn tier(&self) -> Tier {
+        Tier::Primary
+    }
+
+    /// 提供者能可靠提供的字段（默认为全部字段）
+    ///
+    /// 合并多个提供者的结果时，优先采用声明提供该字段的提供者的数据
+    fn provided_fields(&self) -> FieldSet {
+        FieldSet::ALL
+    }
 }
 
 
@@ -191,24 +609,237 @@ pub trait GameDatabaseProvider: Send + Sync {
 
 pub struct GameDatabaseMiddleware {
     providers: Arc<RwLock<Vec<Arc<dyn GameDatabaseProvider>>>>,
-    cache: Arc<RwLock<HashMap<String, Vec<GameQueryResult>>>>,  // 修改为存储 Vec
+    cache: Arc<RwLock<HashMap<String, CacheEntry>>>,
+    /// 按 ID 获取的结果缓存：(提供者名称, ID) -> 结果
+    /// 与搜索缓存分开存放，不计入缓存统计，也不会写入缓存包和持久化缓存
+    id_cache: RwLock<HashMap<(String, String), CacheEntry>>,
     cache_ttl: std::time::Duration,
+    /// 缓存命中、未命中和写入次数
+    cache_counters: Arc<CacheCounters>,
     /// API 速率限制器：限制并发 API 请求数量
-    /// 默认最多同时进行 5 个 API 请求，避免触发速率限制
+    /// 默认最多同时进行 5 个 API 请求，避免触发速率限制（可通过 `with_max_concurrency` 调整）
     rate_limiter: Arc<Semaphore>,
+    /// 层级置信度下限：已有结果的置信度达到该值时，不再查询更低层级的提供者
+    tier_confidence_floor: f32,
+    /// 合并搜索结果时视为同一游戏的最低标题相似度
+    merge_threshold: f32,
+    /// 单次搜索中同时查询的提供者数量上限（`None` 表示同时查询所有提供者）
+    /// 与 `rate_limiter` 相互独立：后者限制的是所有搜索的 API 请求总数
+    provider_fanout: Option<usize>,
+    /// 提供者熔断器（`None` 表示不启用）
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+    /// 严格模式：任意提供者出错时搜索返回错误，而不是忽略该提供者
+    strict_errors: bool,
+    /// 全局重试预算（`None` 表示失败的查询不重试）
+    retry_budget: Option<Arc<RetryBudget>>,
+    /// 重试策略（`None` 且未设置重试预算时不重试）
+    retry_policy: Option<RetryPolicy>,
+    /// 单个提供者查询的超时时间：超时的提供者视为出错，不影响其他提供者的结果
+    per_provider_timeout: std::time::Duration,
+    /// 缺少封面的结果扣减的置信度（0 表示不扣减）
+    cover_preference: f32,
+    /// 返回缓存结果前按当前查询重新计算置信度
+    recompute_confidence: bool,
+    /// 置信度评分
+    scorer: Arc<dyn ConfidenceScorer>,
+    /// 仅本地模式：只查询不访问网络的提供者
+    local_only: bool,
+    /// 进行中的提供者查询：合并对同一提供者的相同并发查询
+    inflight: Arc<InflightQueries>,
+    /// 各提供者的查询次数、错误次数和最近一次耗时
+    metrics: Arc<ProviderMetricsRegistry>,
+    /// 共享的 HTTP 客户端，提供者可以借用它以复用连接池和代理等配置
+    http_client: reqwest::Client,
 }
 
+/// 默认最多同时进行的 API 请求数量
+const DEFAULT_MAX_CONCURRENCY: usize = 5;
+
+/// 只设置了重试预算时，单次提供者查询最多重试的次数
+const MAX_RETRIES_PER_QUERY: u32 = 2;
+
 impl GameDatabaseMiddleware {
     /// 创建新的游戏数据库中间件（不注册任何提供者）
     pub fn new() -> Self {
         GameDatabaseMiddleware {
             providers: Arc::new(RwLock::new(Vec::new())),
             cache: Arc::new(RwLock::new(HashMap::new())),
+            id_cache: RwLock::new(HashMap::new()),
             cache_ttl: std::time::Duration::from_secs(3600), // 1 小时缓存
-            rate_limiter: Arc::new(Semaphore::new(5)), // 最多同时 5 个 API 请求
+            cache_counters: Arc::new(CacheCounters::default()),
+            rate_limiter: Arc::new(Semaphore::new(DEFAULT_MAX_CONCURRENCY)),
+            tier_confidence_floor: 0.7,
+            merge_threshold: DEFAULT_MERGE_THRESHOLD,
+            provider_fanout: None,
+            circuit_breaker: None,
+            strict_errors: false,
+            retry_budget: None,
+            retry_policy: None,
+            per_provider_timeout: std::time::Duration::from_secs(20),
+            cover_preference: 0.0,
+            recompute_confidence: false,
+            scorer: Arc::new(DefaultScorer),
+            local_only: false,
+            inflight: Arc::new(InflightQueries::default()),
+            metrics: Arc::new(ProviderMetricsRegistry::default()),
+            http_client: reqwest::Client::new(),
         }
     }
 
+    /// 设置全局重试预算
+    ///
+    /// 设置后提供者查询失败时最多重试 2 次，每次重试从预算中扣减；
+    /// 预算在所有搜索和提供者之间共享，耗尽后失败的查询不再重试。默认不重试
+    pub fn with_total_retry_budget(mut self, budget: u32) -> Self {
+        self.retry_budget = Some(Arc::new(RetryBudget::new(budget)));
+        self
+    }
+
+    /// 设置失败重试策略
+    ///
+    /// 提供者查询遇到临时错误（网络错误、5xx）时最多重试 `max_retries` 次，
+    /// 重试前按 `base_delay` 指数退避并加入随机抖动；认证失败、凭据未配置等错误不重试。
+    /// 同时设置了重试预算时，每次重试仍从预算中扣减
+    pub fn with_retry(mut self, max_retries: u32, base_delay: std::time::Duration) -> Self {
+        self.retry_policy = Some(RetryPolicy::new(max_retries, base_delay));
+        self
+    }
+
+    /// 设置缓存有效期（默认 1 小时）
+    ///
+    /// 超过有效期的条目在读取时被丢弃并重新查询；设置为 0 时不使用缓存
+    pub fn with_cache_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// 设置共享的 HTTP 客户端
+    ///
+    /// 用于统一配置代理、TLS 根证书和超时。该客户端不会自动应用到已注册的提供者，
+    /// 创建提供者时通过 [`http_client`](Self::http_client) 获取并传入（如 `IGDBProvider::with_client`）
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = client;
+        self
+    }
+
+    /// 使用指定的超时时间重新创建共享的 HTTP 客户端
+    ///
+    /// 连接超时只限制建立连接的时间，总超时限制整个请求（包括读取响应）。
+    /// 在连接超时内无法建立连接的请求以 [`GameboxError::ConnectTimeout`] 报告，
+    /// 启用熔断器时会立即断开该提供者。会替换之前通过 [`with_http_client`](Self::with_http_client) 设置的客户端
+    ///
+    /// # 参数
+    /// - `connect_timeout`: 连接超时时间
+    /// - `timeout`: 单个请求的总超时时间
+    pub fn with_http_timeouts(mut self, connect_timeout: std::time::Duration, timeout: std::time::Duration) -> Self {
+        self.http_client = reqwest::Client::builder()
+            .connect_timeout(connect_timeout)
+            .timeout(timeout)
+            .build()
+            .expect("Failed to build HTTP client");
+        self
+    }
+
+    /// 获取共享的 HTTP 客户端（克隆的客户端共用同一个连接池）
+    pub fn http_client(&self) -> reqwest::Client {
+        self.http_client.clone()
+    }
+
+    /// 设置最多同时进行的 API 请求数量（默认 5）
+    ///
+    /// 只关心延迟时可以调高，网络不稳定时可以降到 1；传入 0 时按 1 处理，避免所有请求永久等待
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.rate_limiter = Arc::new(Semaphore::new(max_concurrency.max(1)));
+        self
+    }
+
+    /// 设置单个提供者查询的超时时间（默认 20 秒）
+    ///
+    /// 超时的提供者被视为出错（非严格模式下没有结果），其他提供者的结果照常返回；
+    /// 整个搜索的超时（`search_with_timeout`）仍作为外层上限
+    pub fn with_per_provider_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.per_provider_timeout = timeout;
+        self
+    }
+
+    /// 设置封面偏好权重（默认 0，不生效）
+    ///
+    /// 没有封面的结果置信度扣减该值，使置信度相近时带封面的结果排在前面，
+    /// 适用于以封面墙展示结果的界面。与完整度加成不同，只针对封面是否存在
+    pub fn with_cover_preference(mut self, weight: f32) -> Self {
+        self.cover_preference = weight.max(0.0);
+        self
+    }
+
+    /// 设置是否按当前查询重新计算缓存结果的置信度
+    ///
+    /// 缓存中的置信度是按写入缓存时的查询计算的。启用后，返回缓存结果前会按本次查询
+    /// 重新计算每条结果的置信度并重新排序，保证排在首位的结果对应实际的搜索词。默认关闭
+    pub fn with_recompute_confidence(mut self, recompute: bool) -> Self {
+        self.recompute_confidence = recompute;
+        self
+    }
+
+    /// 设置置信度评分（默认为 [`DefaultScorer`]）
+    ///
+    /// 评分用于查询结果和重新计算缓存结果的置信度；
+    /// 封面偏好、开发商提示等调整在评分之后进行，不受影响
+    pub fn with_scorer(mut self, scorer: Arc<dyn ConfidenceScorer>) -> Self {
+        self.scorer = scorer;
+        self
+    }
+
+    /// 设置严格模式
+    ///
+    /// 启用后任意提供者查询出错（认证失败、网络错误等）都会使搜索返回错误；
+    /// 默认关闭，出错的提供者被视为没有结果
+    pub fn with_strict_errors(mut self, strict: bool) -> Self {
+        self.strict_errors = strict;
+        self
+    }
+
+    /// 启用提供者熔断器
+    ///
+    /// 提供者在时间窗口内连续失败达到阈值后，冷却期内直接跳过（视为无结果），
+    /// 冷却结束后放行一次查询探测是否恢复
+    pub fn with_circuit_breaker(mut self, config: CircuitConfig) -> Self {
+        self.circuit_breaker = Some(Arc::new(CircuitBreaker::new(config)));
+        self
+    }
+
+    /// 设置单次搜索同时查询的提供者数量上限
+    ///
+    /// 用于在共享代理等场景下控制对不同数据源的并发访问；传入 0 时按 1 处理
+    pub fn with_provider_fanout(mut self, fanout: usize) -> Self {
+        self.provider_fanout = Some(fanout.max(1));
+        self
+    }
+
+    /// 设置层级置信度下限（默认 0.7）
+    ///
+    /// 某一层级查询完成后，如果已有结果的置信度达到该值，则跳过更低层级的提供者
+    pub fn with_tier_confidence_floor(mut self, floor: f32) -> Self {
+        self.tier_confidence_floor = floor;
+        self
+    }
+
+    /// 设置是否只查询本地提供者
+    ///
+    /// 启用后所有搜索和 ID 查找都跳过 [`GameDatabaseProvider::is_local`] 为 `false` 的提供者，
+    /// 保证不访问网络
+    pub fn with_local_only(mut self, local_only: bool) -> Self {
+        self.local_only = local_only;
+        self
+    }
+
+    /// 设置合并搜索结果时视为同一游戏的最低标题相似度（默认 0.85）
+    ///
+    /// 只影响 [`search_merged`](Self::search_merged)
+    pub fn with_merge_threshold(mut self, threshold: f32) -> Self {
+        self.merge_threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+
     /// 注册游戏数据库提供者
     pub async fn register_provider(&self, provider: Arc<dyn GameDatabaseProvider>) {
         let mut providers = self.providers.write().await;
@@ -217,6 +848,18 @@ impl GameDatabaseMiddleware {
         providers.sort_by(|a, b| b.priority().cmp(&a.priority()));
     }
 
+    /// 以指定的实例名称注册提供者
+    ///
+    /// 同一类型的提供者注册多个实例时（如指向不同数据库的两个 SQLite 提供者），
+    /// 使用不同的实例名称区分它们：结果的 `source`、注销、固定匹配和熔断都使用该名称
+    ///
+    /// # 参数
+    /// - `name`: 实例名称，代替提供者自身的 `name()`
+    /// - `provider`: 提供者
+    pub async fn register_provider_as(&self, name: impl Into<String>, provider: Arc<dyn GameDatabaseProvider>) {
+        self.register_provider(Arc::new(NamedProvider::new(name, provider))).await;
+    }
+
     /// 注销数据库提供者
     pub async fn unregister_provider(&self, name: &str) {
         let mut providers = self.providers.write().await;
@@ -228,65 +871,181 @@ impl GameDatabaseMiddleware {
         self.search_with_timeout(title, std::time::Duration::from_secs(30)).await
     }
 
+    /// 搜索游戏（带搜索参数）
+    ///
+    /// 参数会传给提供者用于过滤结果（平台、社团/厂商），提示带来的置信度加成在评分后立即应用，
+    /// 与结果一起缓存。不同参数下提供者返回的结果可能不同，因此按参数分开缓存。
+    pub async fn search_with_params(
+        &self,
+        title: &str,
+        params: &SearchParams,
+    ) -> Result<Vec<GameQueryResult>, Box<dyn std::error::Error + Send + Sync>> {
+        self.search_internal(title, params, std::time::Duration::from_secs(30)).await
+    }
+
+    /// 搜索游戏，并将不同提供者返回的同一游戏合并为一条结果
+    ///
+    /// 标题（忽略大小写、空白和标点）相似度达到合并阈值的结果视为同一游戏：
+    /// 以置信度最高的结果为主，缺失的字段由其他结果补全，类型、标签和平台取并集，
+    /// 来源列出所有贡献了结果的提供者（如 `DLsite+IGDB`）。
+    /// 启用 [`with_recompute_confidence`](Self::with_recompute_confidence) 时，
+    /// 结果会先按本次查询重新评分，再选出每组的主结果
+    ///
+    /// # 参数
+    /// - `title`: 搜索关键词
+    ///
+    /// # 返回
+    /// 合并后的结果，按置信度从高到低排序
+    pub async fn search_merged(&self, title: &str) -> Result<Vec<GameQueryResult>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut results = self.search(title).await?;
+        if self.recompute_confidence {
+            self.rescore(&mut results, title, &SearchParams::default());
+        }
+        Ok(merge_results(results, self.merge_threshold))
+    }
+
+    /// 按游戏类型搜索：只查询支持该类型的提供者
+    ///
+    /// 已知游戏类型时（如同人视觉小说）可以避免向不相关的数据源发送请求；
+    /// [`search`](Self::search) 仍然查询所有提供者
+    ///
+    /// # 参数
+    /// - `title`: 搜索关键词
+    /// - `game_type`: 游戏类型，传给各提供者的 `supports_game_type`
+    pub async fn search_with_type(
+        &self,
+        title: &str,
+        game_type: &str,
+    ) -> Result<Vec<GameQueryResult>, Box<dyn std::error::Error + Send + Sync>> {
+        self.search_with_params(title, &SearchParams::new().with_game_type(game_type))
+            .await
+    }
+
     /// 搜索游戏（带超时）
     pub async fn search_with_timeout(
         &self,
         title: &str,
         timeout: std::time::Duration
     ) -> Result<Vec<GameQueryResult>, Box<dyn std::error::Error + Send + Sync>> {
+        self.search_internal(title, &SearchParams::default(), timeout).await
+    }
+
+    /// 搜索游戏，同时返回出错的提供者
+    ///
+    /// 与 [`search`](Self::search) 相同，但不会忽略提供者错误：出错的提供者及错误信息
+    /// 记录在 [`SearchOutcome::errors`] 中，便于排查认证失败等问题。命中缓存时没有错误。
+    pub async fn search_detailed(&self, title: &str) -> Result<SearchOutcome, Box<dyn std::error::Error + Send + Sync>> {
+        self.search_outcome(title, &SearchParams::default(), std::time::Duration::from_secs(30))
+            .await
+    }
+
+    /// 搜索实现：按层级查询提供者并缓存结果
+    ///
+    /// 严格模式下任意提供者出错时返回错误，否则忽略出错的提供者
+    ///
+    /// # 参数
+    /// - `title`: 搜索关键词
+    /// - `params`: 搜索参数，传给能利用提示过滤结果的提供者
+    /// - `timeout`: 整个搜索的超时时间
+    async fn search_internal(
+        &self,
+        title: &str,
+        params: &SearchParams,
+        timeout: std::time::Duration,
+    ) -> Result<Vec<GameQueryResult>, Box<dyn std::error::Error + Send + Sync>> {
+        let outcome = self.search_outcome(title, params, timeout).await?;
+        if self.strict_errors {
+            if let Some((provider, error)) = outcome.errors.into_iter().next() {
+                return Err(format!("{} 查询失败: {}", provider, error).into());
+            }
+        }
+        Ok(outcome.results)
+    }
+
+    /// 按层级查询提供者并缓存结果，返回结果和出错的提供者
+    async fn search_outcome(
+        &self,
+        title: &str,
+        params: &SearchParams,
+        timeout: std::time::Duration,
+    ) -> Result<SearchOutcome, Box<dyn std::error::Error + Send + Sync>> {
         let logger = get_logger();
+        let cache_key = params.cache_key(title);
 
         // 检查缓存
         let cache = self.cache.read().await;
-        if let Some(cached_results) = cache.get(title) {
-            logger.log(&LogEvent::new(
-                LogLevel::Info,
-                format!("从缓存获取: {} 条结果", cached_results.len())
-            ));
-            return Ok(cached_results.clone());  // 返回所有缓存的结果
-        }
+        let expired = match cache.get(&cache_key) {
+            Some(cached) if !cached.is_expired(self.cache_ttl) => {
+                logger.log(&LogEvent::new(
+                    LogLevel::Info,
+                    format!("从缓存获取: {} 条结果", cached.results.len())
+                ));
+                self.cache_counters.record_hit();
+                let mut results = cached.results.clone();  // 返回所有缓存的结果
+                // 旧版本写入的缓存没有体验版标记
+                let providers = self.providers.read().await;
+                for result in results.iter_mut() {
+                    if let Some(provider) = providers.iter().find(|p| p.name() == result.source) {
+                        flag_trial(provider.as_ref(), &mut result.info);
+                    }
+                }
+                drop(providers);
+                if self.recompute_confidence {
+                    self.rescore(&mut results, title, params);
+                }
+                return Ok(SearchOutcome {
+                    results,
+                    errors: Vec::new(),
+                });
+            }
+            Some(_) => true,
+            None => false,
+        };
         drop(cache);
+        self.cache_counters.record_miss();
 
-        let providers = self.providers.read().await;
-        let mut results = Vec::new();
+        // 丢弃过期的缓存条目
+        if expired {
+            self.cache.write().await.remove(&cache_key);
+        }
 
-        // 并发查询所有提供者（使用速率限制器）
-        let mut futures = Vec::new();
-        for provider in providers.iter() {
-            let provider = Arc::clone(provider);
-            let title_clone = title.to_string();
-            let provider_name = provider.name().to_string();
-            let rate_limiter = Arc::clone(&self.rate_limiter);
+        // 指定了游戏类型时跳过不支持该类型的提供者
+        let providers: Vec<Arc<dyn GameDatabaseProvider>> = self
+            .active_providers()
+            .await
+            .into_iter()
+            .filter(|p| params.game_type.as_deref().is_none_or(|game_type| p.supports_game_type(game_type)))
+            .collect();
 
-            futures.push(async move {
-                // 获取速率限制许可（最多同时 5 个请求）
-                let _permit = rate_limiter.acquire().await.unwrap();
-
-                match provider.search(&title_clone).await {
-                    Ok(games) => {
-                        games.into_iter().map(|info| {
-                            // 动态计算置信度
-                            let confidence = calculate_confidence(&title_clone, &info);
-
-                            GameQueryResult {
-                                info,
-                                source: provider_name.clone(),
-                                confidence,
-                            }
-                        }).collect::<Vec<_>>()
-                    },
-                    Err(_e) => {
-                        Vec::new()
-                    },
+        // 按层级依次查询：高层级出现足够可信的结果时，不再查询低层级提供者
+        let tiered_query = async {
+            let mut results: Vec<GameQueryResult> = Vec::new();
+            let mut errors: Vec<(String, String)> = Vec::new();
+            for tier in [Tier::Primary, Tier::Secondary, Tier::Fallback] {
+                let tier_providers: Vec<Arc<dyn GameDatabaseProvider>> = providers
+                    .iter()
+                    .filter(|p| p.tier() == tier)
+                    .cloned()
+                    .collect();
+                if tier_providers.is_empty() {
+                    continue;
                 }
-                // _permit 在这里自动释放
-            });
-        }
+                if results.iter().any(|r| r.confidence >= self.tier_confidence_floor) {
+                    break;
+                }
+                let (tier_results, tier_errors) = self.query_providers(&tier_providers, title, params).await;
+                results.extend(tier_results);
+                errors.extend(tier_errors);
+                if self.strict_errors && !errors.is_empty() {
+                    break;
+                }
+            }
+            (results, errors)
+        };
 
         // 等待所有查询完成（带超时）
-        let query_future = futures::future::join_all(futures);
-        let query_results = match tokio::time::timeout(timeout, query_future).await {
-            Ok(results) => results,
+        let (mut results, errors) = match tokio::time::timeout(timeout, tiered_query).await {
+            Ok(outcome) => outcome,
             Err(_) => {
                 logger.log(&LogEvent::new(
                     LogLevel::Warning,
@@ -296,29 +1055,254 @@ impl GameDatabaseMiddleware {
             }
         };
 
-        for query_result in query_results {
-            results.extend(query_result);
-        }
-
         // 按置信度排序（从高到低）
-        results.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
+        sort_by_confidence(&mut results);
 
-        // 缓存所有结果
-        if !results.is_empty() {
+        // 缓存所有结果（有效期为 0 时不缓存）
+        if !results.is_empty() && !self.cache_ttl.is_zero() {
             let mut cache = self.cache.write().await;
-            cache.insert(title.to_string(), results.clone());
+            cache.insert(cache_key, CacheEntry::new(results.clone()));
+            self.cache_counters.record_insertion();
         }
 
-        Ok(results)
+        Ok(SearchOutcome { results, errors })
+    }
+
+    /// 复制当前可查询的提供者列表（仅本地模式下只包含本地提供者），避免在网络请求期间持有读锁
+    async fn active_providers(&self) -> Vec<Arc<dyn GameDatabaseProvider>> {
+        self.providers
+            .read()
+            .await
+            .iter()
+            .filter(|p| !self.local_only || p.is_local())
+            .cloned()
+            .collect()
+    }
+
+    /// 没有封面的结果按封面偏好扣减置信度
+    fn apply_cover_preference(&self, results: &mut [GameQueryResult]) {
+        if self.cover_preference > 0.0 {
+            for result in results.iter_mut().filter(|r| r.info.cover_url.is_none()) {
+                result.confidence = (result.confidence - self.cover_preference).max(0.0);
+            }
+        }
+    }
+
+    /// 评分之后的置信度调整：搜索参数的提示加成和缺少封面的扣减
+    ///
+    /// 查询提供者和重新评分缓存结果都经过这里，同一查询无论是否命中缓存排序都相同
+    fn adjust_confidence(&self, results: &mut [GameQueryResult], params: &SearchParams) {
+        params.apply(results);
+        self.apply_cover_preference(results);
+    }
+
+    /// 按查询关键词重新计算一组结果的置信度并重新排序
+    ///
+    /// # 参数
+    /// - `results`: 需要重新评分的结果（通常来自缓存）
+    /// - `title`: 当前的搜索关键词
+    /// - `params`: 当前的搜索参数
+    fn rescore(&self, results: &mut [GameQueryResult], title: &str, params: &SearchParams) {
+        for result in results.iter_mut() {
+            result.confidence = self.scorer.score(title, &result.info);
+        }
+        self.adjust_confidence(results, params);
+        sort_by_confidence(results);
+    }
+
+    /// 在速率限制、重试、单个提供者超时的保护下调用提供者，并记录查询统计和熔断状态
+    ///
+    /// 熔断检查由调用方负责：搜索时跳过熔断中的提供者，按 ID 获取时返回错误
+    ///
+    /// # 参数
+    /// - `provider_name`: 提供者名称
+    /// - `call`: 发起一次请求的函数，重试时会再次调用
+    async fn call_provider<T, F, Fut>(&self, provider_name: &str, call: F) -> Result<T, String>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, Box<dyn std::error::Error + Send + Sync>>>,
+    {
+        // 获取速率限制许可
+        let _permit = self.rate_limiter.acquire().await.unwrap();
+        let started = std::time::Instant::now();
+        let retry_policy = self.retry_policy.or_else(|| {
+            self.retry_budget
+                .as_ref()
+                .map(|_| RetryPolicy::new(MAX_RETRIES_PER_QUERY, std::time::Duration::ZERO))
+        });
+
+        let query_with_retries = async {
+            let mut attempt = 0;
+            loop {
+                let result = call().await;
+
+                // 临时错误在重试次数和预算允许的范围内重试
+                let policy = match (&result, retry_policy) {
+                    (Err(e), Some(policy)) if attempt < policy.max_retries && is_retryable_error(e.as_ref()) => policy,
+                    _ => break result,
+                };
+                if !self.retry_budget.as_ref().is_none_or(|budget| budget.try_consume()) {
+                    break result;
+                }
+                attempt += 1;
+                get_logger().log(&LogEvent::new(
+                    LogLevel::Debug,
+                    format!("{} 查询失败，第 {} 次重试", provider_name, attempt),
+                ));
+                tokio::time::sleep(policy.backoff(attempt)).await;
+            }
+        };
+
+        // 单个提供者超时只丢弃该提供者的结果
+        let mut connect_timeout = false;
+        let result = match tokio::time::timeout(self.per_provider_timeout, query_with_retries).await {
+            Ok(Ok(value)) => Ok(value),
+            Ok(Err(e)) => {
+                connect_timeout = GameboxError::is_connect_timeout(e.as_ref());
+                Err(e.to_string())
+            }
+            Err(_) => {
+                get_logger().log(&LogEvent::new(
+                    LogLevel::Warning,
+                    format!("{} 查询超时", provider_name),
+                ));
+                Err("查询超时".to_string())
+            }
+        };
+        self.metrics.record(provider_name, started.elapsed(), result.as_ref().err().map(String::as_str));
+        if let Some(breaker) = &self.circuit_breaker {
+            match &result {
+                Ok(_) => breaker.record_success(provider_name),
+                // 连接超时说明主机不可达，不必等待连续失败达到阈值
+                Err(_) if connect_timeout => breaker.trip(provider_name),
+                Err(_) => breaker.record_failure(provider_name),
+            }
+        }
+        result
+        // _permit 在这里自动释放
+    }
+
+    /// 并发查询一组提供者（使用速率限制器），并计算每条结果的置信度（包括评分之后的调整）
+    ///
+    /// # 返回
+    /// `(查询结果, 出错的提供者及错误信息)`
+    async fn query_providers(
+        &self,
+        providers: &[Arc<dyn GameDatabaseProvider>],
+        title: &str,
+        params: &SearchParams,
+    ) -> (Vec<GameQueryResult>, Vec<(String, String)>) {
+        let mut futures = Vec::new();
+        for (index, provider) in providers.iter().enumerate() {
+            let provider = Arc::clone(provider);
+            let title_clone = title.to_string();
+            // 提供者自己的查询预处理，置信度仍按原标题计算
+            let query = provider.preprocess_query(title);
+            let provider_name = provider.name().to_string();
+            let params = params.clone();
+            let scorer = Arc::clone(&self.scorer);
+            let query_key = params.cache_key(&query);
+
+            futures.push(async move {
+                // 熔断中的提供者直接跳过
+                if let Some(breaker) = &self.circuit_breaker {
+                    if !breaker.allow(&provider_name) {
+                        get_logger().log(&LogEvent::new(
+                            LogLevel::Debug,
+                            format!("{} 已熔断，跳过查询", provider_name),
+                        ));
+                        return (index, Ok(Vec::new()));
+                    }
+                }
+
+                // 同一提供者的相同查询正在进行时共享其结果，不重复请求
+                let search_result = self
+                    .inflight
+                    .run(
+                        &provider_name,
+                        &query_key,
+                        self.call_provider(&provider_name, || provider.search_with_params(&query, &params)),
+                    )
+                    .await;
+
+                let results = match search_result {
+                    Ok(games) => Ok(games.into_iter().map(|mut info| {
+                        flag_trial(provider.as_ref(), &mut info);
+
+                        // 动态计算置信度
+                        let confidence = scorer.score(&title_clone, &info);
+
+                        GameQueryResult {
+                            info,
+                            source: provider_name.clone(),
+                            confidence,
+                        }
+                    }).collect::<Vec<_>>()),
+                    Err(e) => Err((provider_name.clone(), e)),
+                };
+                (index, results)
+            });
+        }
+
+        // 限制同时进行的提供者查询数量
+        let fanout = self.provider_fanout.unwrap_or(futures.len()).max(1);
+        let mut query_results: Vec<_> = futures::stream::iter(futures)
+            .buffer_unordered(fanout)
+            .collect()
+            .await;
+
+        // 按提供者注册顺序还原，保证结果顺序稳定
+        query_results.sort_by_key(|(index, _)| *index);
+
+        let mut results = Vec::new();
+        let mut errors = Vec::new();
+        for (_, query_result) in query_results {
+            match query_result {
+                Ok(provider_results) => results.extend(provider_results),
+                Err(error) => errors.push(error),
+            }
+        }
+        self.adjust_confidence(&mut results, params);
+        (results, errors)
+    }
+
+    /// 预热缓存：提前搜索一组标题并写入缓存
+    ///
+    /// 将网络查询阶段与磁盘扫描阶段分离，之后对这些标题的扫描或搜索会直接命中缓存。
+    /// 每个标题的搜索同样受速率限制约束。
+    ///
+    /// # 参数
+    /// - `titles`: 要预先搜索的标题
+    /// - `concurrency`: 同时进行的搜索数量（传入 0 时按 1 处理）
+    ///
+    /// # 返回
+    /// 成功获取到结果并写入缓存的标题数量
+    pub async fn warm_cache(&self, titles: Vec<String>, concurrency: usize) -> usize {
+        let succeeded = futures::stream::iter(titles)
+            .map(|title| async move {
+                matches!(self.search(&title).await, Ok(results) if !results.is_empty())
+            })
+            .buffer_unordered(concurrency.max(1))
+            .filter(|ok| futures::future::ready(*ok))
+            .count()
+            .await;
+
+        get_logger().log(&LogEvent::new(
+            LogLevel::Info,
+            format!("缓存预热完成: {} 个标题", succeeded),
+        ));
+
+        succeeded
     }
 
     /// 通过 ID 获取游戏
     pub async fn get_by_id(&self, id: &str) -> Result<GameQueryResult, Box<dyn std::error::Error + Send + Sync>> {
-        let providers = self.providers.read().await;
+        let providers = self.active_providers().await;
 
         for provider in providers.iter() {
             match provider.get_by_id(id).await {
-                Ok(info) => {
+                Ok(mut info) => {
+                    flag_trial(provider.as_ref(), &mut info);
                     return Ok(GameQueryResult {
                         info,
                         source: provider.name().to_string(),
@@ -332,16 +1316,170 @@ impl GameDatabaseMiddleware {
         Err("Game not found".into())
     }
 
+    /// 通过 ID 获取游戏，并按标题在其他提供者中查找交叉引用
+    ///
+    /// 交叉引用只保留置信度不低于 `min_confidence` 的结果，避免标题相近的其他游戏混入
+    ///
+    /// # 参数
+    /// - `id`: 游戏 ID
+    /// - `min_confidence`: 交叉引用的最低置信度（0.0 ~ 1.0）
+    ///
+    /// # 返回
+    /// ID 对应的结果及其交叉引用；结果没有标题时交叉引用为空
+    pub async fn get_by_id_with_crossref(
+        &self,
+        id: &str,
+        min_confidence: f32,
+    ) -> Result<CrossReferencedResult, Box<dyn std::error::Error + Send + Sync>> {
+        let primary = self.get_by_id(id).await?;
+
+        let cross_references = match primary.info.title.as_deref() {
+            Some(title) => self
+                .search(title)
+                .await?
+                .into_iter()
+                .filter(|result| result.source != primary.source && result.confidence >= min_confidence)
+                .collect(),
+            None => Vec::new(),
+        };
+
+        Ok(CrossReferencedResult { primary, cross_references })
+    }
+
+    /// 通过指定提供者的 ID 获取游戏
+    ///
+    /// 用于获取用户固定（pin）的匹配结果，不会回退到其他提供者。与搜索一样经过速率限制、
+    /// 单个提供者超时和熔断器，熔断中的提供者直接返回错误。结果缓存在单独的 ID 缓存中，
+    /// 不影响搜索缓存的统计、缓存包和持久化缓存
+    ///
+    /// # 参数
+    /// - `provider_name`: 提供者名称
+    /// - `id`: 该提供者的游戏 ID
+    pub async fn get_by_id_from(
+        &self,
+        provider_name: &str,
+        id: &str,
+    ) -> Result<GameQueryResult, Box<dyn std::error::Error + Send + Sync>> {
+        let provider = self
+            .active_providers()
+            .await
+            .into_iter()
+            .find(|p| p.name() == provider_name)
+            .ok_or_else(|| format!("未注册的提供者: {}", provider_name))?;
+
+        let cache_key = (provider_name.to_string(), id.to_string());
+        if let Some(cached) = self.id_cache.read().await.get(&cache_key) {
+            if let Some(result) = cached.results.first().filter(|_| !cached.is_expired(self.cache_ttl)) {
+                return Ok(result.clone());
+            }
+        }
+
+        if let Some(breaker) = &self.circuit_breaker {
+            if !breaker.allow(provider_name) {
+                return Err(format!("{} 已熔断", provider_name).into());
+            }
+        }
+
+        let mut info = self.call_provider(provider_name, || provider.get_by_id(id)).await?;
+        flag_trial(provider.as_ref(), &mut info);
+        let result = GameQueryResult {
+            info,
+            source: provider.name().to_string(),
+            confidence: 1.0,
+        };
+
+        if !self.cache_ttl.is_zero() {
+            self.id_cache.write().await.insert(cache_key, CacheEntry::new(vec![result.clone()]));
+        }
+        Ok(result)
+    }
+
+    /// 按游戏目录查找
+    ///
+    /// 依次调用各提供者的 [`GameDatabaseProvider::search_in_dir`]，结果来自用户放置的本地文件，
+    /// 置信度为 1.0，不写入缓存。查找失败的提供者（如附属文件损坏）记录警告后视为没有结果
+    ///
+    /// # 参数
+    /// - `dir`: 游戏根目录
+    /// - `title`: 搜索关键词
+    pub async fn search_in_dir(&self, dir: &Path, title: &str) -> Vec<GameQueryResult> {
+        let providers = self.active_providers().await;
+
+        let mut results = Vec::new();
+        for provider in providers {
+            match provider.search_in_dir(dir, title).await {
+                Ok(games) => results.extend(games.into_iter().map(|mut info| {
+                    flag_trial(provider.as_ref(), &mut info);
+                    GameQueryResult {
+                        info,
+                        source: provider.name().to_string(),
+                        confidence: 1.0,
+                    }
+                })),
+                Err(e) => {
+                    get_logger().log(
+                        &LogEvent::new(LogLevel::Warning, format!("{} 按目录查找失败", provider.name()))
+                            .with_details(e.to_string()),
+                    );
+                }
+            }
+        }
+        results
+    }
+
     /// 获取所有提供者
     pub async fn list_providers(&self) -> Vec<String> {
         let providers = self.providers.read().await;
         providers.iter().map(|p| p.name().to_string()).collect()
     }
 
-    /// 清空缓存
+    /// 获取所有提供者的状态报告
+    ///
+    /// 包括最近一次查询是否成功及其耗时、查询和出错次数、熔断状态、
+    /// 当前可用的 API 请求许可数和剩余重试预算。可以通过
+    /// [`ProviderStatus::format_table`] 格式化为文本表格，或序列化为 JSON
+    pub async fn status_report(&self) -> Vec<ProviderStatus> {
+        let providers = self.providers.read().await;
+        providers
+            .iter()
+            .map(|provider| {
+                let name = provider.name().to_string();
+                let metrics = self.metrics.get(&name);
+                let circuit = self
+                    .circuit_breaker
+                    .as_ref()
+                    .map_or(CircuitStatus::Disabled, |breaker| breaker.status(&name));
+                ProviderStatus {
+                    tier: provider.tier(),
+                    priority: provider.priority(),
+                    reachable: metrics.last_ok,
+                    last_latency_ms: metrics.last_latency.map(|latency| latency.as_millis() as u64),
+                    queries: metrics.queries,
+                    errors: metrics.errors,
+                    last_error: metrics.last_error,
+                    circuit,
+                    rate_limit_remaining: self.rate_limiter.available_permits(),
+                    retry_budget_remaining: self.retry_budget.as_ref().map(|budget| budget.remaining()),
+                    name,
+                }
+            })
+            .collect()
+    }
+
+    /// 获取所有提供者声明可靠提供的字段：提供者名称 -> 字段集合
+    pub async fn provided_fields(&self) -> HashMap<String, FieldSet> {
+        let providers = self.providers.read().await;
+        providers
+            .iter()
+            .map(|p| (p.name().to_string(), p.provided_fields()))
+            .collect()
+    }
+
+    /// 清空缓存（包括按 ID 获取的结果）
     pub async fn clear_cache(&self) {
         let mut cache = self.cache.write().await;
         cache.clear();
+        self.id_cache.write().await.clear();
     }
 
     /// 获取缓存大小
@@ -349,4 +1487,1001 @@ impl GameDatabaseMiddleware {
         let cache = self.cache.read().await;
         cache.len()
     }
-}
\ No newline at end of file
+
+    /// 获取缓存命中统计
+    ///
+    /// 完全由缓存返回结果的搜索计为命中，查询了提供者的搜索计为未命中
+    pub async fn cache_stats(&self) -> CacheStats {
+        let entries = self.cache_size().await;
+        self.cache_counters.snapshot(entries)
+    }
+
+    /// 将缓存导出为可共享的缓存包
+    ///
+    /// 缓存包包含魔数、结构版本、每个条目的写入时间和来源提供者，
+    /// 可以分发给其他用户通过 [`import_cache_pack`](Self::import_cache_pack) 导入，免去重复刮削。
+    ///
+    /// # 返回
+    /// 导出的条目数量
+    pub async fn export_cache_pack<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let cache = self.cache.read().await;
+        let pack = CachePack::from_entries(cache.iter());
+        drop(cache);
+
+        pack.write_to(path)?;
+        Ok(pack.entries.len())
+    }
+
+    /// 从缓存包导入缓存
+    ///
+    /// # 参数
+    /// - `path`: 缓存包文件路径
+    /// - `merge`: 为 `true` 时与现有缓存合并（同一关键词保留写入时间较新的条目）；
+    ///   为 `false` 时先清空现有缓存再导入
+    ///
+    /// # 返回
+    /// 实际写入缓存的条目数量。魔数或结构版本不兼容时返回错误，现有缓存保持不变。
+    pub async fn import_cache_pack<P: AsRef<Path>>(
+        &self,
+        path: P,
+        merge: bool,
+    ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let pack = CachePack::read_from(path)?;
+
+        let mut cache = self.cache.write().await;
+        if !merge {
+            cache.clear();
+        }
+
+        let mut imported = 0;
+        for entry in pack.entries {
+            let is_newer = cache
+                .get(&entry.query)
+                .is_none_or(|existing| existing.cached_at < entry.cached_at);
+            if is_newer {
+                cache.insert(
+                    entry.query,
+                    CacheEntry {
+                        results: entry.results,
+                        cached_at: entry.cached_at,
+                        inserted_at: std::time::Instant::now(),
+                    },
+                );
+                imported += 1;
+            }
+        }
+
+        Ok(imported)
+    }
+
+    /// 将缓存保存到文件，供之后的进程通过 [`load_cache`](Self::load_cache) 加载
+    ///
+    /// 文件格式与缓存包相同，每个条目带有写入时间；已过期的条目不会写入
+    ///
+    /// # 返回
+    /// 写入的条目数量
+    pub async fn save_cache<P: AsRef<Path>>(&self, path: P) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let cache = self.cache.read().await;
+        let pack = CachePack::from_entries(cache.iter().filter(|(_, entry)| !entry.is_expired(self.cache_ttl)));
+        drop(cache);
+
+        pack.write_to(path)?;
+        Ok(pack.entries.len())
+    }
+
+    /// 从文件加载之前保存的缓存
+    ///
+    /// 按条目的写入时间计算是否过期：已过期的条目被跳过，其余条目在原本应过期的时间过期。
+    /// 同一关键词保留写入时间较新的条目
+    ///
+    /// # 返回
+    /// 实际写入缓存的条目数量
+    pub async fn load_cache<P: AsRef<Path>>(&self, path: P) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let pack = CachePack::read_from(path)?;
+
+        let mut cache = self.cache.write().await;
+        let mut loaded = 0;
+        for entry in pack.entries {
+            let restored = CacheEntry::restored(entry.results, entry.cached_at);
+            if restored.is_expired(self.cache_ttl) {
+                continue;
+            }
+            let is_newer = cache
+                .get(&entry.query)
+                .is_none_or(|existing| existing.cached_at < restored.cached_at);
+            if is_newer {
+                cache.insert(entry.query, restored);
+                loaded += 1;
+            }
+        }
+
+        Ok(loaded)
+    }
+
+    /// 创建在释放时将缓存写入指定文件的持久化缓存
+    pub(crate) fn persistent_cache(&self, path: std::path::PathBuf) -> PersistentCache {
+        PersistentCache {
+            path,
+            cache: Arc::clone(&self.cache),
+            ttl: self.cache_ttl,
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::mock_provider::{metadata, MockProvider};
+
+    #[test]
+    fn test_company_matches_hint_folds_case() {
+        assert!(company_matches_hint("Team Cherry", "team cherry"));
+        // 词尾的 Σ 小写为 ς，折叠后与 σ 相同
+        assert!(company_matches_hint("ΟΔΥΣΣΕΑΣ", "οδυσσεασ"));
+        assert!(!company_matches_hint("Nitroplus", "Kogado"));
+    }
+
+    #[tokio::test]
+    async fn test_developer_hint_reorders_same_title_results() {
+        let mut original = metadata("Same Game", "Kogado Studio");
+        original.cover_url = Some("https://example.com/cover.jpg".to_string());
+        let remake = metadata("Same Game", "Nitroplus");
+
+        let middleware = GameDatabaseMiddleware::new();
+        middleware
+            .register_provider(Arc::new(MockProvider::new("Mock").with_results(vec![original, remake])))
+            .await;
+
+        // 无提示时，数据更完整的结果排在前面
+        let results = middleware.search_with_params("Same Game", &SearchParams::new()).await.unwrap();
+        assert_eq!(results[0].info.developer.as_deref(), Some("Kogado Studio"));
+
+        // 带提示时，开发商匹配的结果获得加成并排到前面
+        let params = SearchParams::new().with_developer_hint("nitroplus");
+        let results = middleware.search_with_params("Same Game", &params).await.unwrap();
+        assert_eq!(results[0].info.developer.as_deref(), Some("Nitroplus"));
+        assert!(results[0].confidence > results[1].confidence);
+    }
+
+    #[tokio::test]
+    async fn test_fallback_tier_skipped_when_primary_is_confident() {
+        let primary = Arc::new(MockProvider::new("Primary").with_results(vec![metadata("Elden Ring", "FromSoftware")]));
+        let fallback = Arc::new(
+            MockProvider::new("Fallback")
+                .with_tier(Tier::Fallback)
+                .with_results(vec![metadata("Elden Ring", "Unknown")]),
+        );
+
+        let middleware = GameDatabaseMiddleware::new();
+        middleware.register_provider(primary.clone()).await;
+        middleware.register_provider(fallback.clone()).await;
+
+        let results = middleware.search("Elden Ring").await.unwrap();
+        assert_eq!(primary.calls(), 1);
+        assert_eq!(fallback.calls(), 0);
+        assert!(results.iter().all(|r| r.source == "Primary"));
+
+        // 主要数据源没有结果时才会查询兜底数据源
+        let results = middleware.search("Unknown Title").await.unwrap();
+        assert_eq!(primary.calls(), 2);
+        assert_eq!(fallback.calls(), 1);
+        assert!(results.iter().any(|r| r.source == "Fallback"));
+    }
+
+    /// 记录同时进行的搜索数量的提供者
+    struct ConcurrencyProvider {
+        name: String,
+        current: Arc<std::sync::atomic::AtomicUsize>,
+        max_seen: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl GameDatabaseProvider for ConcurrencyProvider {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        async fn search(&self, title: &str) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+            use std::sync::atomic::Ordering;
+
+            let now = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_seen.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            self.current.fetch_sub(1, Ordering::SeqCst);
+
+            Ok(vec![metadata(title, &self.name)])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_provider_fanout_limits_concurrent_providers() {
+        let current = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let max_seen = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+        let middleware = GameDatabaseMiddleware::new().with_provider_fanout(2);
+        for i in 0..6 {
+            middleware
+                .register_provider(Arc::new(ConcurrencyProvider {
+                    name: format!("Provider{}", i),
+                    current: Arc::clone(&current),
+                    max_seen: Arc::clone(&max_seen),
+                }))
+                .await;
+        }
+
+        let results = middleware.search("Game").await.unwrap();
+        assert_eq!(results.len(), 6);
+        assert_eq!(max_seen.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_skips_failing_provider() {
+        let failing = Arc::new(MockProvider::new("Down").failing());
+        let healthy = Arc::new(MockProvider::new("Up").with_results(vec![metadata("Game", "Studio")]));
+
+        let middleware = GameDatabaseMiddleware::new().with_circuit_breaker(
+            CircuitConfig::default()
+                .with_failure_threshold(2)
+                .with_cooldown(std::time::Duration::from_secs(3600)),
+        );
+        middleware.register_provider(failing.clone()).await;
+        middleware.register_provider(healthy.clone()).await;
+
+        for i in 0..5 {
+            let results = middleware.search(&format!("Game {}", i)).await.unwrap();
+            assert_eq!(results.len(), 1);
+        }
+
+        // 达到阈值后不再调用失败的提供者
+        assert_eq!(failing.calls(), 2);
+        assert_eq!(healthy.calls(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_warm_cache_makes_later_searches_cache_hits() {
+        let provider = Arc::new(
+            MockProvider::new("Mock")
+                .with_results_for("Game A", vec![metadata("Game A", "Studio")])
+                .with_results_for("Game B", vec![metadata("Game B", "Studio")]),
+        );
+        let middleware = GameDatabaseMiddleware::new();
+        middleware.register_provider(provider.clone()).await;
+
+        let warmed = middleware
+            .warm_cache(vec!["Game A".to_string(), "Game B".to_string(), "Unknown".to_string()], 2)
+            .await;
+        assert_eq!(warmed, 2);
+        assert_eq!(middleware.cache_size().await, 2);
+        assert_eq!(provider.calls(), 3);
+
+        middleware.search("Game A").await.unwrap();
+        middleware.search("Game B").await.unwrap();
+        assert_eq!(provider.calls(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_platform_hint_prefers_matching_edition() {
+        let mut pc = metadata("Pixel Quest", "Studio");
+        pc.platforms = Some(vec!["PC (Microsoft Windows)".to_string()]);
+        let mut android = metadata("Pixel Quest", "Studio");
+        android.platforms = Some(vec!["Android".to_string(), "iOS".to_string()]);
+
+        let middleware = GameDatabaseMiddleware::new();
+        middleware
+            .register_provider(Arc::new(MockProvider::new("Mock").with_results(vec![pc, android])))
+            .await;
+
+        let plain = middleware.search("Pixel Quest").await.unwrap();
+        assert!(plain[0].info.platforms.as_ref().unwrap()[0].starts_with("PC"));
+
+        let params = SearchParams::new().with_platform_hint("Android");
+        let results = middleware.search_with_params("Pixel Quest", &params).await.unwrap();
+        assert_eq!(results[0].info.platforms.as_ref().unwrap()[0], "Android");
+        assert!(results[0].confidence > results[1].confidence);
+    }
+
+    #[tokio::test]
+    async fn test_retry_budget_is_shared_across_searches() {
+        let provider = Arc::new(MockProvider::new("Flaky").failing());
+        let middleware = GameDatabaseMiddleware::new().with_total_retry_budget(3);
+        middleware.register_provider(provider.clone()).await;
+
+        // 第一次搜索：1 次查询 + 2 次重试；第二次：1 + 1；之后预算耗尽不再重试
+        for title in ["A", "B", "C", "D"] {
+            assert!(middleware.search(title).await.unwrap().is_empty());
+        }
+        assert_eq!(provider.calls(), 3 + 2 + 1 + 1);
+    }
+
+    #[tokio::test]
+    async fn test_cache_entries_expire_after_ttl() {
+        let provider = Arc::new(MockProvider::new("Mock").with_results(vec![metadata("Cached", "Studio")]));
+        let middleware = GameDatabaseMiddleware::new().with_cache_ttl(std::time::Duration::from_millis(50));
+        middleware.register_provider(provider.clone()).await;
+
+        middleware.search("Cached").await.unwrap();
+        middleware.search("Cached").await.unwrap();
+        assert_eq!(provider.calls(), 1);
+
+        tokio::time::sleep(std::time::Duration::from_millis(80)).await;
+        middleware.search("Cached").await.unwrap();
+        assert_eq!(provider.calls(), 2);
+
+        // 有效期为 0 时不缓存
+        let provider = Arc::new(MockProvider::new("Mock").with_results(vec![metadata("Cached", "Studio")]));
+        let middleware = GameDatabaseMiddleware::new().with_cache_ttl(std::time::Duration::ZERO);
+        middleware.register_provider(provider.clone()).await;
+        middleware.search("Cached").await.unwrap();
+        middleware.search("Cached").await.unwrap();
+        assert_eq!(provider.calls(), 2);
+        assert_eq!(middleware.cache_size().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_cover_preference_prefers_results_with_cover() {
+        let mut with_cover = metadata("Same Game", "Cover Studio");
+        with_cover.cover_url = Some("https://example.com/cover.jpg".to_string());
+        let mut without_cover = metadata("Same Game", "Text Studio");
+        without_cover.description = Some("No cover, but a description".to_string());
+        without_cover.publisher = Some("Publisher".to_string());
+        let provider = || MockProvider::new("Mock").with_results(vec![with_cover.clone(), without_cover.clone()]);
+
+        // 默认不生效：数据更完整的无封面结果排在前面
+        let middleware = GameDatabaseMiddleware::new();
+        middleware.register_provider(Arc::new(provider())).await;
+        let results = middleware.search("Same Game").await.unwrap();
+        assert_eq!(results[0].info.developer.as_deref(), Some("Text Studio"));
+
+        let middleware = GameDatabaseMiddleware::new().with_cover_preference(0.1);
+        middleware.register_provider(Arc::new(provider())).await;
+        let results = middleware.search("Same Game").await.unwrap();
+        assert_eq!(results[0].info.developer.as_deref(), Some("Cover Studio"));
+        assert!(results[0].confidence > results[1].confidence);
+    }
+
+    #[tokio::test]
+    async fn test_searches_complete_with_fewer_permits_than_futures() {
+        // 0 按 1 处理，不会死锁
+        let middleware = GameDatabaseMiddleware::new().with_max_concurrency(0);
+        middleware
+            .register_provider(Arc::new(MockProvider::new("Mock").with_results(vec![metadata("Game", "Studio")])))
+            .await;
+
+        let searches = (0..8).map(|i| {
+            let middleware = &middleware;
+            async move { middleware.search(&format!("Game {}", i)).await }
+        });
+        let results = tokio::time::timeout(std::time::Duration::from_secs(5), futures::future::join_all(searches))
+            .await
+            .expect("所有搜索应在许可数量不足时依次完成");
+        assert_eq!(results.len(), 8);
+        assert!(results.iter().all(|r| r.as_ref().is_ok_and(|r| r.len() == 1)));
+    }
+
+    #[tokio::test]
+    async fn test_provider_futures_and_errors_cross_spawn_boundary() {
+        // 编译期约束：提供者的搜索 future 及其错误类型必须是 Send，才能在 tokio::spawn 中等待
+        let provider: Box<dyn GameDatabaseProvider> = Box::new(MockProvider::new("Mock").failing());
+        let error = tokio::spawn(async move { provider.search("Game").await.map(|_| ()) })
+            .await
+            .unwrap()
+            .unwrap_err();
+        let error = tokio::spawn(async move { error.to_string() }).await.unwrap();
+        assert_eq!(error, "Mock 不可用");
+    }
+
+    #[tokio::test]
+    async fn test_slow_provider_timeout_keeps_other_results() {
+        let fast = Arc::new(MockProvider::new("Fast").with_results(vec![metadata("Elden Ring", "FromSoftware")]));
+        let slow = Arc::new(
+            MockProvider::new("Slow")
+                .with_results(vec![metadata("Elden Ring", "Slow Studio")])
+                .with_delay(std::time::Duration::from_secs(5)),
+        );
+        let middleware = GameDatabaseMiddleware::new()
+            .with_per_provider_timeout(std::time::Duration::from_millis(100));
+        middleware.register_provider(fast).await;
+        middleware.register_provider(slow.clone()).await;
+
+        let results = middleware
+            .search_with_timeout("Elden Ring", std::time::Duration::from_secs(2))
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].source, "Fast");
+        assert_eq!(slow.calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_identical_queries_coalesce_per_provider() {
+        let provider = Arc::new(
+            MockProvider::new("Mock")
+                .with_results(vec![metadata("Elden Ring", "FromSoftware")])
+                .with_delay(std::time::Duration::from_millis(100)),
+        );
+        let middleware = GameDatabaseMiddleware::new();
+        middleware.register_provider(provider.clone()).await;
+
+        // 缓存写入之前的并发查询（归一化后相同）只请求一次提供者
+        let queries = ["Elden Ring", "elden  ring", "Elden Ring", "ELDEN RING"];
+        let results = futures::future::join_all(queries.iter().map(|q| middleware.search(q))).await;
+        assert!(results.iter().all(|r| r.as_ref().is_ok_and(|r| r.len() == 1)));
+        assert_eq!(provider.calls(), 1);
+
+        // 查询完成后不再合并（由中间件缓存负责）
+        middleware.search("Elden Ring Nightreign").await.unwrap();
+        assert_eq!(provider.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_search_detailed_surfaces_provider_errors() {
+        let middleware = GameDatabaseMiddleware::new();
+        middleware
+            .register_provider(Arc::new(MockProvider::new("Working").with_results(vec![metadata("Hades", "Supergiant")])))
+            .await;
+        middleware.register_provider(Arc::new(MockProvider::new("IGDB").failing())).await;
+
+        // search 忽略出错的提供者
+        assert_eq!(middleware.search("Hades II").await.unwrap().len(), 1);
+
+        let outcome = middleware.search_detailed("Hades").await.unwrap();
+        assert_eq!(outcome.results.len(), 1);
+        assert_eq!(outcome.errors, vec![("IGDB".to_string(), "IGDB 不可用".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_backoff_recovers_from_transient_failures() {
+        let provider = Arc::new(
+            MockProvider::new("Flaky")
+                .with_results(vec![metadata("Hades", "Supergiant")])
+                .failing_times(2),
+        );
+        let middleware = GameDatabaseMiddleware::new().with_retry(3, std::time::Duration::from_millis(10));
+        middleware.register_provider(provider.clone()).await;
+
+        let results = middleware.search("Hades").await.unwrap();
+        assert_eq!(provider.calls(), 3);
+        assert_eq!(results[0].info.developer.as_deref(), Some("Supergiant"));
+
+        // 未设置重试时失败直接按无结果处理
+        let provider = Arc::new(
+            MockProvider::new("Flaky")
+                .with_results(vec![metadata("Hades", "Supergiant")])
+                .failing_times(2),
+        );
+        let middleware = GameDatabaseMiddleware::new();
+        middleware.register_provider(provider.clone()).await;
+        assert!(middleware.search("Hades").await.unwrap().is_empty());
+        assert_eq!(provider.calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_preprocess_query_is_per_provider() {
+        let stripping = Arc::new(
+            MockProvider::new("Stripping")
+                .with_results_for("Game", vec![metadata("Game", "Studio A")])
+                .with_query_preprocessor(|title| title.trim_start_matches("[Tag] ").to_string()),
+        );
+        let plain = Arc::new(
+            MockProvider::new("Plain").with_results_for("[Tag] Game", vec![metadata("[Tag] Game", "Studio B")]),
+        );
+        let middleware = GameDatabaseMiddleware::new();
+        middleware.register_provider(stripping).await;
+        middleware.register_provider(plain).await;
+
+        let results = middleware.search("[Tag] Game").await.unwrap();
+        let mut sources: Vec<&str> = results.iter().map(|r| r.source.as_str()).collect();
+        sources.sort();
+        assert_eq!(sources, vec!["Plain", "Stripping"]);
+    }
+
+    #[tokio::test]
+    async fn test_register_same_provider_type_with_instance_names() {
+        let middleware = GameDatabaseMiddleware::new();
+        middleware
+            .register_provider_as(
+                "Library A",
+                Arc::new(MockProvider::new("Mock").with_results(vec![metadata("Shared", "Studio A")])),
+            )
+            .await;
+        middleware
+            .register_provider_as(
+                "Library B",
+                Arc::new(MockProvider::new("Mock").with_results(vec![metadata("Shared", "Studio B")])),
+            )
+            .await;
+
+        let results = middleware.search("Shared").await.unwrap();
+        let mut sources: Vec<&str> = results.iter().map(|r| r.source.as_str()).collect();
+        sources.sort();
+        assert_eq!(sources, vec!["Library A", "Library B"]);
+
+        middleware.unregister_provider("Library A").await;
+        assert_eq!(middleware.list_providers().await, vec!["Library B"]);
+    }
+
+    #[tokio::test]
+    async fn test_recompute_confidence_reselects_primary_from_cache() {
+        // 缓存中的置信度是按原分组查询 "Hollow Knight" 计算的
+        let stale = vec![
+            GameQueryResult { info: metadata("Hollow Knight", "Team Cherry"), source: "A".to_string(), confidence: 0.9 },
+            GameQueryResult { info: metadata("Hollow Knight Silksong", "Team Cherry"), source: "B".to_string(), confidence: 0.5 },
+        ];
+
+        for (recompute, expected) in [(false, "Hollow Knight"), (true, "Hollow Knight Silksong")] {
+            let middleware = GameDatabaseMiddleware::new()
+                .with_merge_threshold(0.5)
+                .with_recompute_confidence(recompute);
+            middleware
+                .cache
+                .write()
+                .await
+                .insert(SearchParams::new().cache_key("Hollow Knight Silksong"), CacheEntry::new(stale.clone()));
+
+            let results = middleware.search("Hollow Knight Silksong").await.unwrap();
+            assert_eq!(results[0].info.title.as_deref(), Some(expected));
+
+            // 合并后每组的主结果同样按本次查询选出
+            let merged = middleware.search_merged("Hollow Knight Silksong").await.unwrap();
+            assert_eq!(merged.len(), 1);
+            assert_eq!(merged[0].source, if recompute { "B+A" } else { "A+B" });
+            assert_eq!(merged[0].info.title.as_deref(), Some(expected));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recompute_confidence_matches_cache_miss() {
+        let mut remake = metadata("Same Game", "Nitroplus");
+        remake.cover_url = Some("https://example.com/cover.jpg".to_string());
+        let mut original = metadata("Same Game", "Kogado Studio");
+        original.platforms = Some(vec!["PC".to_string()]);
+        let params = SearchParams::new().with_developer_hint("Kogado").with_platform_hint("PC");
+
+        for recompute in [false, true] {
+            let middleware = GameDatabaseMiddleware::new()
+                .with_cover_preference(0.05)
+                .with_recompute_confidence(recompute);
+            middleware
+                .register_provider(Arc::new(MockProvider::new("Mock").with_results(vec![remake.clone(), original.clone()])))
+                .await;
+
+            // 第一次查询提供者，第二次命中缓存：提示加成和封面扣减都相同
+            let miss = middleware.search_with_params("Same Game", &params).await.unwrap();
+            let hit = middleware.search_with_params("Same Game", &params).await.unwrap();
+            assert_eq!(middleware.cache_stats().await.hits, 1);
+
+            let ranking = |results: &[GameQueryResult]| -> Vec<(Option<String>, f32)> {
+                results.iter().map(|r| (r.info.developer.clone(), r.confidence)).collect()
+            };
+            assert_eq!(ranking(&miss), ranking(&hit));
+            assert_eq!(hit[0].info.developer.as_deref(), Some("Kogado Studio"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connect_timeout_is_distinct_and_trips_breaker() {
+        use crate::providers::igdb_provider::IGDBProvider;
+
+        // 不可路由地址在没有网络的环境中会立即失败而不是超时，
+        // 这里用积压队列已满的本地端口模拟丢弃连接请求的主机
+        let socket = tokio::net::TcpSocket::new_v4().unwrap();
+        socket.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let listener = socket.listen(0).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut held = Vec::new();
+        for _ in 0..3 {
+            let connect = tokio::net::TcpStream::connect(addr);
+            match tokio::time::timeout(std::time::Duration::from_millis(50), connect).await {
+                Ok(Ok(stream)) => held.push(stream),
+                _ => break,
+            }
+        }
+
+        let middleware = GameDatabaseMiddleware::new()
+            .with_http_timeouts(std::time::Duration::from_millis(100), std::time::Duration::from_secs(5))
+            .with_circuit_breaker(CircuitConfig::default().with_failure_threshold(5));
+        let provider = IGDBProvider::with_client("id".to_string(), "secret".to_string(), middleware.http_client())
+            .with_token_url(format!("http://{}/oauth2/token", addr));
+
+        let error = provider.search("test game").await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<GameboxError>(),
+            Some(GameboxError::ConnectTimeout { .. })
+        ));
+
+        middleware.register_provider(Arc::new(provider)).await;
+        let outcome = middleware.search_detailed("test game").await.unwrap();
+        assert!(outcome.errors[0].1.contains("连接超时"));
+        // 一次连接超时即断开，不必等待 5 次失败
+        assert!(middleware.circuit_breaker.as_ref().unwrap().is_open("IGDB"));
+    }
+
+    #[tokio::test]
+    async fn test_search_with_type_skips_unsupported_providers() {
+        let dlsite = Arc::new(
+            MockProvider::new("DLsite")
+                .with_game_types(&["visual_novel", "doujin", "all"])
+                .with_results(vec![metadata("魔女の夜宴", "Circle")]),
+        );
+        let igdb = Arc::new(
+            MockProvider::new("IGDB")
+                .with_game_types(&["western_game", "all"])
+                .with_results(vec![metadata("Witch Night", "Studio")]),
+        );
+        let middleware = GameDatabaseMiddleware::new();
+        middleware.register_provider(dlsite.clone()).await;
+        middleware.register_provider(igdb.clone()).await;
+
+        let results = middleware.search_with_type("魔女の夜宴", "visual_novel").await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].source, "DLsite");
+        assert_eq!((dlsite.calls(), igdb.calls()), (1, 0));
+
+        // 普通搜索仍查询所有提供者，且不复用按类型搜索的缓存
+        assert_eq!(middleware.search("魔女の夜宴").await.unwrap().len(), 2);
+        assert_eq!((dlsite.calls(), igdb.calls()), (2, 1));
+    }
+
+    #[tokio::test]
+    async fn test_crossref_excludes_low_confidence_candidates() {
+        let middleware = GameDatabaseMiddleware::new();
+        middleware
+            .register_provider(Arc::new(
+                MockProvider::new("DLsite").with_item("RJ01", metadata("Hollow Knight", "Team Cherry")),
+            ))
+            .await;
+        middleware
+            .register_provider(Arc::new(MockProvider::new("IGDB").with_results(vec![
+                metadata("Hollow Knight", "Team Cherry"),
+                metadata("Hollow Earth Tales", "Other Studio"),
+            ])))
+            .await;
+
+        let result = middleware.get_by_id_with_crossref("RJ01", 0.6).await.unwrap();
+        assert_eq!(result.primary.source, "DLsite");
+        assert_eq!(result.cross_references.len(), 1);
+        assert_eq!(result.cross_references[0].source, "IGDB");
+        assert_eq!(result.cross_references[0].info.title.as_deref(), Some("Hollow Knight"));
+
+        // 阈值为 0 时弱匹配也会被附加
+        let result = middleware.get_by_id_with_crossref("RJ01", 0.0).await.unwrap();
+        assert_eq!(result.cross_references.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_search_merged_combines_same_game_across_providers() {
+        let mut dlsite_game = metadata("Hollow Knight", "Team Cherry");
+        dlsite_game.genres = Some(vec!["Action".to_string()]);
+        dlsite_game.cover_url = Some("https://img.dlsite.jp/hollow.jpg".to_string());
+        let mut igdb_game = metadata("Hollow Knight.", "Team Cherry");
+        igdb_game.genres = Some(vec!["Metroidvania".to_string(), "action".to_string()]);
+        igdb_game.description = Some("A challenging adventure".to_string());
+
+        let middleware = GameDatabaseMiddleware::new();
+        middleware
+            .register_provider(Arc::new(MockProvider::new("DLsite").with_results(vec![dlsite_game])))
+            .await;
+        middleware
+            .register_provider(Arc::new(
+                MockProvider::new("IGDB").with_results(vec![igdb_game, metadata("Hollow Earth Tales", "Other")]),
+            ))
+            .await;
+
+        assert_eq!(middleware.search("Hollow Knight").await.unwrap().len(), 3);
+
+        let merged = middleware.search_merged("Hollow Knight").await.unwrap();
+        assert_eq!(merged.len(), 2);
+        let game = &merged[0];
+        assert_eq!(game.source, "DLsite+IGDB");
+        assert_eq!(game.info.title.as_deref(), Some("Hollow Knight"));
+        assert_eq!(game.info.cover_url.as_deref(), Some("https://img.dlsite.jp/hollow.jpg"));
+        assert_eq!(game.info.description.as_deref(), Some("A challenging adventure"));
+        assert_eq!(game.info.genres, Some(vec!["Action".to_string(), "Metroidvania".to_string()]));
+        assert_eq!(merged[1].source, "IGDB");
+    }
+
+    #[test]
+    fn test_merge_results_keeps_sequels_and_same_source_apart() {
+        let result = |title: &str, source: &str, cover: &str| {
+            let mut info = metadata(title, "Studio");
+            info.cover_url = Some(cover.to_string());
+            GameQueryResult { info, source: source.to_string(), confidence: 0.9 }
+        };
+
+        // 续作编号不同的标题不合并
+        let merged = merge_results(
+            vec![
+                result("Portal", "IGDB", "https://a/portal.jpg"),
+                result("Portal 2", "DLsite", "https://b/portal2.jpg"),
+                result("Hollow Knight", "IGDB", "https://a/hk.jpg"),
+                result("Hollow Knight II", "DLsite", "https://b/hk2.jpg"),
+            ],
+            DEFAULT_MERGE_THRESHOLD,
+        );
+        assert_eq!(merged.len(), 4);
+
+        // 同一提供者返回的相似标题是不同的游戏；不同提供者的封面都保留
+        let merged = merge_results(
+            vec![
+                result("Dark Tales", "IGDB", "https://a/dark.jpg"),
+                result("Dark Tale", "IGDB", "https://a/other.jpg"),
+                result("Dark Tales", "DLsite", "https://b/dark.jpg"),
+            ],
+            DEFAULT_MERGE_THRESHOLD,
+        );
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].source, "IGDB+DLsite");
+        assert_eq!(merged[0].info.cover_url.as_deref(), Some("https://a/dark.jpg"));
+        assert_eq!(merged[0].info.extra_cover_urls, vec!["https://b/dark.jpg"]);
+        assert_eq!(merged[1].info.title.as_deref(), Some("Dark Tale"));
+
+        assert_eq!(sequel_number("Final Fantasy VII"), Some(7));
+        assert_eq!(sequel_number("ゲーム2"), Some(2));
+        assert_eq!(sequel_number("Portal"), None);
+    }
+
+    #[tokio::test]
+    async fn test_save_and_load_cache_round_trip() {
+        let provider = Arc::new(MockProvider::new("Mock").with_results(vec![metadata("Celeste", "Maddy Makes Games")]));
+        let middleware = GameDatabaseMiddleware::new();
+        middleware.register_provider(provider.clone()).await;
+        middleware.search("Celeste").await.unwrap();
+        middleware.search("Hades").await.unwrap();
+
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        let path = dir.path().join("cache.json");
+        assert_eq!(middleware.save_cache(&path).await.unwrap(), 2);
+
+        let restored = GameDatabaseMiddleware::new();
+        restored.register_provider(provider.clone()).await;
+        assert_eq!(restored.load_cache(&path).await.unwrap(), 2);
+        let results = restored.search("Celeste").await.unwrap();
+        assert_eq!(results[0].info.title.as_deref(), Some("Celeste"));
+        assert_eq!(provider.calls(), 2);
+
+        // 写入时间早于有效期的条目在加载时被跳过
+        let mut pack = CachePack::read_from(&path).unwrap();
+        pack.entries[0].cached_at -= chrono::Duration::hours(2);
+        pack.write_to(&path).unwrap();
+        let expired = GameDatabaseMiddleware::new();
+        assert_eq!(expired.load_cache(&path).await.unwrap(), 1);
+        assert_eq!(expired.cache_size().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_cache_stats_counts_hits_and_misses() {
+        let middleware = GameDatabaseMiddleware::new();
+        middleware
+            .register_provider(Arc::new(MockProvider::new("Mock").with_results(vec![metadata("Celeste", "Maddy Makes Games")])))
+            .await;
+
+        assert_eq!(middleware.cache_stats().await, CacheStats::default());
+        middleware.search("Celeste").await.unwrap();
+        middleware.search("Celeste").await.unwrap();
+
+        let stats = middleware.cache_stats().await;
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.insertions, 1);
+        assert_eq!(stats.entries, 1);
+    }
+
+    #[tokio::test]
+    async fn test_cache_key_ignores_case_and_whitespace() {
+        let provider = Arc::new(MockProvider::new("Mock").with_results(vec![metadata("Elden Ring", "FromSoftware")]));
+        let middleware = GameDatabaseMiddleware::new();
+        middleware.register_provider(provider.clone()).await;
+
+        let first = middleware.search("Elden Ring").await.unwrap();
+        let second = middleware.search("  elden   RING ").await.unwrap();
+        assert_eq!(provider.calls(), 1);
+        assert_eq!(middleware.cache_size().await, 1);
+        assert_eq!(first[0].info.title, second[0].info.title);
+
+        // 中日韩文字没有大小写，不同的标题仍然分开缓存
+        middleware.search("東方紅魔郷").await.unwrap();
+        middleware.search("東方妖々夢").await.unwrap();
+        assert_eq!(provider.calls(), 3);
+        assert_eq!(middleware.cache_size().await, 3);
+    }
+
+    #[tokio::test]
+    async fn test_status_report_covers_all_providers() {
+        let middleware = GameDatabaseMiddleware::new()
+            .with_circuit_breaker(CircuitConfig::default().with_failure_threshold(1))
+            .with_total_retry_budget(3);
+        middleware
+            .register_provider(Arc::new(
+                MockProvider::new("Up")
+                    .with_results(vec![metadata("Game", "Studio")])
+                    .with_delay(std::time::Duration::from_millis(20)),
+            ))
+            .await;
+        middleware.register_provider(Arc::new(MockProvider::new("Down").failing())).await;
+        middleware.register_provider(Arc::new(MockProvider::new("Idle").with_tier(Tier::Fallback))).await;
+
+        middleware.search("Game").await.unwrap();
+        let report = middleware.status_report().await;
+        let names: Vec<&str> = report.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names.len(), 3);
+        assert!(["Up", "Down", "Idle"].iter().all(|name| names.contains(name)));
+
+        let status = |name: &str| report.iter().find(|s| s.name == name).unwrap();
+        let up = status("Up");
+        assert_eq!(up.reachable, Some(true));
+        assert!(up.last_latency_ms.unwrap() >= 20);
+        assert_eq!((up.queries, up.errors), (1, 0));
+        assert_eq!(up.circuit, CircuitStatus::Closed);
+        assert_eq!(up.rate_limit_remaining, DEFAULT_MAX_CONCURRENCY);
+
+        let down = status("Down");
+        assert_eq!(down.reachable, Some(false));
+        assert_eq!(down.errors, 1);
+        assert!(down.last_error.is_some());
+        assert_eq!(down.circuit, CircuitStatus::Open);
+
+        let idle = status("Idle");
+        assert_eq!(idle.reachable, None);
+        assert_eq!(idle.queries, 0);
+        assert_eq!(idle.tier, Tier::Fallback);
+        assert!(idle.retry_budget_remaining.is_some());
+
+        let table = ProviderStatus::format_table(&report);
+        assert_eq!(table.lines().count(), 4);
+        assert!(table.contains("open"));
+        let json = serde_json::to_value(&report).unwrap();
+        assert!(json[0]["circuit"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_custom_scorer_replaces_confidence() {
+        struct AlwaysCertain;
+        impl ConfidenceScorer for AlwaysCertain {
+            fn score(&self, _query: &str, _meta: &GameMetadata) -> f32 {
+                1.0
+            }
+        }
+
+        let provider = Arc::new(MockProvider::new("Mock").with_results(vec![metadata("Unrelated Title", "Studio")]));
+        let middleware = GameDatabaseMiddleware::new();
+        middleware.register_provider(provider.clone()).await;
+        let results = middleware.search("Query").await.unwrap();
+        let info = &results[0].info;
+        assert_eq!(results[0].confidence, calculate_confidence("Query", info));
+        assert_eq!(results[0].confidence, DefaultScorer.score("Query", info));
+        assert!(results[0].confidence < 1.0);
+
+        let middleware = GameDatabaseMiddleware::new().with_scorer(Arc::new(AlwaysCertain));
+        middleware.register_provider(provider).await;
+        let results = middleware.search("Query").await.unwrap();
+        assert_eq!(results[0].confidence, 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_company_match_outranks_equal_candidate() {
+        let provider = Arc::new(MockProvider::new("Mock").with_results(vec![
+            metadata("Dark Tales", "Other Works"),
+            metadata("Dark Tales", "Moonlight Games Inc."),
+        ]));
+        let middleware = GameDatabaseMiddleware::new();
+        middleware.register_provider(provider).await;
+        let results = middleware.search("Moonlight Dark Tales").await.unwrap();
+        assert_eq!(results[0].info.developer.as_deref(), Some("Moonlight Games Inc."));
+        assert!(results[0].confidence > results[1].confidence);
+        assert!(results[0].confidence <= 1.0);
+
+        let exact = calculate_confidence("Dark Tales", &results[0].info);
+        assert_eq!(exact, calculate_confidence("Dark Tales", &results[1].info));
+    }
+
+    #[tokio::test]
+    async fn test_full_version_outranks_trial() {
+        let provider = Arc::new(MockProvider::new("Mock").with_results(vec![
+            metadata("Dark Tales Demo", "Studio"),
+            metadata("Dark Tales: Complete Edition", "Studio"),
+        ]));
+        let middleware = GameDatabaseMiddleware::new();
+        middleware.register_provider(provider).await;
+        let results = middleware.search("Dark Tales").await.unwrap();
+        assert_eq!(results[0].info.title.as_deref(), Some("Dark Tales: Complete Edition"));
+        assert!(!results[0].info.is_trial);
+        assert!(results[1].info.is_trial);
+
+        // 明确搜索体验版时不扣分
+        let trial = &results[1].info;
+        assert!(calculate_confidence("Dark Tales Demo", trial) > calculate_confidence("Dark Tales", trial));
+    }
+
+    #[tokio::test]
+    async fn test_get_by_id_from_is_cached_and_guarded() {
+        let provider = MockProvider::new("Mock").with_item("RJ01", metadata("Game 体験版", "Circle"));
+        let middleware = GameDatabaseMiddleware::new();
+        middleware.register_provider(Arc::new(provider)).await;
+        let first = middleware.get_by_id_from("Mock", "RJ01").await.unwrap();
+        assert!(first.info.is_trial);
+        assert_eq!(first.confidence, 1.0);
+        middleware.get_by_id_from("Mock", "RJ01").await.unwrap();
+        assert_eq!(middleware.id_cache.read().await.len(), 1);
+
+        // ID 缓存不计入搜索缓存的统计，也不会写入缓存包
+        let stats = middleware.cache_stats().await;
+        assert_eq!((stats.hits, stats.misses, stats.insertions, stats.entries), (0, 0, 0, 0));
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(middleware.export_cache_pack(dir.path().join("pack.json")).await.unwrap(), 0);
+        assert_eq!(middleware.save_cache(dir.path().join("cache.json")).await.unwrap(), 0);
+
+        // 单个提供者超时和熔断器同样适用于按 ID 获取
+        let slow = MockProvider::new("Slow")
+            .with_item("RJ02", metadata("Slow Game", "Circle"))
+            .with_delay(std::time::Duration::from_secs(5));
+        let middleware = GameDatabaseMiddleware::new()
+            .with_per_provider_timeout(std::time::Duration::from_millis(50))
+            .with_circuit_breaker(CircuitConfig { failure_threshold: 1, ..CircuitConfig::default() });
+        middleware.register_provider(Arc::new(slow)).await;
+        let error = middleware.get_by_id_from("Slow", "RJ02").await.unwrap_err();
+        assert!(error.to_string().contains("超时"));
+        let error = middleware.get_by_id_from("Slow", "RJ02").await.unwrap_err();
+        assert!(error.to_string().contains("熔断"));
+    }
+
+    #[tokio::test]
+    async fn test_trial_flag_set_on_every_result_path() {
+        let provider = MockProvider::new("Mock")
+            .with_results(vec![metadata("Game Demo", "Studio")])
+            .with_item("RJ01", metadata("Game 体験版", "Studio"));
+        let middleware = GameDatabaseMiddleware::new();
+        middleware.register_provider(Arc::new(provider)).await;
+
+        assert!(middleware.get_by_id("RJ01").await.unwrap().info.is_trial);
+        assert!(middleware.get_by_id_from("Mock", "RJ01").await.unwrap().info.is_trial);
+
+        // 旧版本缓存中的结果没有标记，命中缓存时补上
+        let stale = GameQueryResult { info: metadata("Game Demo", "Studio"), source: "Mock".to_string(), confidence: 0.5 };
+        middleware.cache.write().await.insert(SearchParams::new().cache_key("Game"), CacheEntry::new(vec![stale]));
+        assert!(middleware.search("Game").await.unwrap()[0].info.is_trial);
+    }
+
+    #[tokio::test]
+    async fn test_cache_pack_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let pack_path = dir.path().join("pack.json");
+
+        let middleware = GameDatabaseMiddleware::new();
+        middleware
+            .register_provider(Arc::new(MockProvider::new("Mock").with_results(vec![metadata("Game", "Studio")])))
+            .await;
+        middleware.search("Game").await.unwrap();
+        assert_eq!(middleware.export_cache_pack(&pack_path).await.unwrap(), 1);
+
+        let other = GameDatabaseMiddleware::new();
+        assert_eq!(other.import_cache_pack(&pack_path, false).await.unwrap(), 1);
+        let results = other.search("Game").await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].source, "Mock");
+    }
+
+    #[tokio::test]
+    async fn test_cache_pack_rejects_schema_version_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let pack_path = dir.path().join("pack.json");
+        std::fs::write(
+            &pack_path,
+            format!(
+                r#"{{"magic":"{}","schema_version":999,"entries":"future format"}}"#,
+                cache::CACHE_PACK_MAGIC
+            ),
+        )
+        .unwrap();
+
+        let middleware = GameDatabaseMiddleware::new();
+        let err = middleware.import_cache_pack(&pack_path, true).await.unwrap_err();
+        assert!(err.to_string().contains("999"));
+        assert_eq!(middleware.cache_size().await, 0);
+
+        // 魔数错误的文件同样被拒绝
+        std::fs::write(&pack_path, r#"{"results":[]}"#).unwrap();
+        assert!(middleware.import_cache_pack(&pack_path, true).await.is_err());
+    }
+}
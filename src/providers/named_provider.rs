@@ -0,0 +1,91 @@
+//! 带实例名称的提供者
+//!
+//! 同一类型的提供者注册多个实例时（如指向不同数据库的两个 SQLite 提供者），
+//! 它们的 `name()` 相同，会在结果来源、注销、熔断和查询合并中互相冲突。
+//! 这里用一个只覆盖名称、其余全部委托给内部提供者的包装解决。
+
+use std::path::Path;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::models::game_meta_data::{FieldSet, GameMetadata};
+use crate::providers::{GameDatabaseProvider, SearchParams, Tier};
+
+/// 使用指定实例名称的提供者
+pub(crate) struct NamedProvider {
+    name: String,
+    inner: Arc<dyn GameDatabaseProvider>,
+}
+
+impl NamedProvider {
+    pub(crate) fn new(name: impl Into<String>, inner: Arc<dyn GameDatabaseProvider>) -> Self {
+        NamedProvider {
+            name: name.into(),
+            inner,
+        }
+    }
+}
+
+#[async_trait]
+impl GameDatabaseProvider for NamedProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn search(&self, title: &str) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.search(title).await
+    }
+
+    async fn search_for_platform(
+        &self,
+        title: &str,
+        platform: &str,
+    ) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.search_for_platform(title, platform).await
+    }
+
+    async fn search_with_params(
+        &self,
+        title: &str,
+        params: &SearchParams,
+    ) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.search_with_params(title, params).await
+    }
+
+    async fn search_in_dir(
+        &self,
+        dir: &Path,
+        title: &str,
+    ) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.search_in_dir(dir, title).await
+    }
+
+    fn preprocess_query(&self, title: &str) -> String {
+        self.inner.preprocess_query(title)
+    }
+
+    async fn get_by_id(&self, id: &str) -> Result<GameMetadata, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_by_id(id).await
+    }
+
+    fn priority(&self) -> u32 {
+        self.inner.priority()
+    }
+
+    fn supports_game_type(&self, game_type: &str) -> bool {
+        self.inner.supports_game_type(game_type)
+    }
+
+    fn is_local(&self) -> bool {
+        self.inner.is_local()
+    }
+
+    fn tier(&self) -> Tier {
+        self.inner.tier()
+    }
+
+    fn provided_fields(&self) -> FieldSet {
+        self.inner.provided_fields()
+    }
+}
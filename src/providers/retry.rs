@@ -0,0 +1,108 @@
+//! 提供者查询重试策略
+//!
+//! 数据源偶尔会返回 5xx 或断开连接，这类临时错误值得重试；认证失败、凭据未配置等
+//! 错误重试也不会成功，直接按失败处理。重试之间按指数退避等待，并加入随机抖动，
+//! 避免多个查询同时重试。
+
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// 错误消息中的 4xx 状态码匹配正则
+///
+/// 只匹配固定位置的状态码：`IGDB API error: 401 Unauthorized`、`status 404`、`HTTP 403`。
+/// 不匹配 `api.igdb.com:443` 这类端口号或 ID 中的数字
+static CLIENT_ERROR_STATUS_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:\b(?:status(?:\s+code)?|http)\s*:?|:)\s+4\d{2}\b").unwrap()
+});
+
+/// 重试策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// 单次查询最多重试的次数
+    pub max_retries: u32,
+    /// 第一次重试前的等待时间，之后每次翻倍
+    pub base_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// 创建重试策略
+    ///
+    /// # 参数
+    /// - `max_retries`: 单次查询最多重试的次数
+    /// - `base_delay`: 第一次重试前的等待时间
+    pub fn new(max_retries: u32, base_delay: Duration) -> Self {
+        RetryPolicy { max_retries, base_delay }
+    }
+
+    /// 第 `attempt` 次重试（从 1 开始）前的等待时间
+    ///
+    /// 为 `base_delay * 2^(attempt - 1)` 加上不超过其一半的随机抖动
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let delay = self.base_delay.saturating_mul(1u32 << attempt.saturating_sub(1).min(16));
+        let jitter_range = delay.as_nanos() as u64 / 2;
+        if jitter_range == 0 {
+            return delay;
+        }
+        delay + Duration::from_nanos(jitter_seed() % jitter_range)
+    }
+}
+
+/// 抖动使用的随机数（取当前时间的纳秒部分，不需要密码学强度）
+fn jitter_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or(0)
+}
+
+/// 判断错误是否值得重试
+///
+/// 网络错误和 5xx 视为临时错误；4xx（认证失败、请求错误等）和凭据未配置不重试。
+/// 提供者的错误多为字符串，无法识别的错误按临时错误处理。
+pub fn is_retryable_error(error: &(dyn std::error::Error + 'static)) -> bool {
+    if let Some(error) = error.downcast_ref::<reqwest::Error>() {
+        return match error.status() {
+            Some(status) => status.is_server_error(),
+            None => true,
+        };
+    }
+
+    let message = error.to_string();
+    let lower = message.to_lowercase();
+    if lower.contains("not configured") || lower.contains("unauthorized") || lower.contains("forbidden") {
+        return false;
+    }
+
+    // 消息中的 HTTP 状态码（如 "IGDB API error: 401 Unauthorized"）
+    !CLIENT_ERROR_STATUS_PATTERN.is_match(&message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(message: &str) -> Box<dyn std::error::Error + Send + Sync> {
+        message.into()
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        assert!(is_retryable_error(error("IGDB API error: 503 Service Unavailable").as_ref()));
+        assert!(is_retryable_error(error("connection reset by peer").as_ref()));
+        assert!(!is_retryable_error(error("IGDB credentials not configured").as_ref()));
+        assert!(!is_retryable_error(error("Failed to get access token: 401 Unauthorized").as_ref()));
+        assert!(!is_retryable_error(error("request failed with status 404").as_ref()));
+
+        // 端口号、ID 中的数字不是状态码
+        assert!(is_retryable_error(
+            error("error sending request for url (https://api.igdb.com:443/v4/games): connection closed").as_ref()
+        ));
+        assert!(is_retryable_error(error("connection refused: 10.0.0.4:4433, game 4512").as_ref()));
+
+        let policy = RetryPolicy::new(3, Duration::from_millis(100));
+        let third = policy.backoff(3);
+        assert!(third >= Duration::from_millis(400) && third < Duration::from_millis(600));
+    }
+}
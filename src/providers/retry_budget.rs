@@ -0,0 +1,55 @@
+//! 全局重试预算
+//!
+//! 网络不稳定时，每次查询各自重试会在大规模扫描中累积成成千上万次请求。
+//! 重试预算在整个扫描（所有分组、所有提供者）之间共享，每次重试消耗一次，
+//! 耗尽后不再重试，失败的查询直接按失败处理，从而限制最坏情况下的 API 用量。
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use crate::logger::{get_logger, LogEvent, LogLevel};
+
+/// 共享的重试预算
+#[derive(Debug)]
+pub struct RetryBudget {
+    /// 剩余可用的重试次数
+    remaining: AtomicU32,
+    /// 是否已记录过耗尽日志（只记录一次）
+    depleted_logged: AtomicBool,
+}
+
+impl RetryBudget {
+    /// 创建重试预算
+    ///
+    /// # 参数
+    /// - `total`: 允许的重试总次数
+    pub fn new(total: u32) -> Self {
+        RetryBudget {
+            remaining: AtomicU32::new(total),
+            depleted_logged: AtomicBool::new(false),
+        }
+    }
+
+    /// 尝试消耗一次重试
+    ///
+    /// # 返回
+    /// 预算未耗尽时返回 `true` 并扣减一次；已耗尽时返回 `false`，首次耗尽时记录警告
+    pub fn try_consume(&self) -> bool {
+        let consumed = self
+            .remaining
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |remaining| remaining.checked_sub(1))
+            .is_ok();
+
+        if !consumed && !self.depleted_logged.swap(true, Ordering::SeqCst) {
+            get_logger().log(&LogEvent::new(
+                LogLevel::Warning,
+                "重试预算已耗尽，后续失败的查询将不再重试",
+            ));
+        }
+        consumed
+    }
+
+    /// 剩余可用的重试次数
+    pub fn remaining(&self) -> u32 {
+        self.remaining.load(Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,51 @@
+//! 搜索结果置信度评分
+//!
+//! 中间件使用 [`ConfidenceScorer`] 为每条提供者结果计算置信度。默认的 [`DefaultScorer`]
+//! 综合标题匹配度（最高 0.7）、搜索词命中开发商或发行商的加成（0.1）和数据完整度（最高 0.2），
+//! 体验版结果扣 0.2（搜索词本身指明体验版时不扣），最终限制在 0.0 ~ 1.0。需要其他权重时，
+//! 可以实现该 trait 并通过 [`GameDatabaseMiddleware::with_scorer`](crate::providers::GameDatabaseMiddleware::with_scorer) 替换。
+
+use crate::models::game_meta_data::GameMetadata;
+
+/// 置信度评分 trait
+///
+/// # 示例
+///
+/// ```
+/// use std::sync::Arc;
+/// use gamebox::models::game_meta_data::GameMetadata;
+/// use gamebox::providers::GameDatabaseMiddleware;
+/// use gamebox::providers::scoring::ConfidenceScorer;
+///
+/// /// 只看开发商是否已知
+/// struct DeveloperScorer;
+///
+/// impl ConfidenceScorer for DeveloperScorer {
+///     fn score(&self, _query: &str, meta: &GameMetadata) -> f32 {
+///         if meta.developer.is_some() { 0.9 } else { 0.1 }
+///     }
+/// }
+///
+/// let middleware = GameDatabaseMiddleware::new().with_scorer(Arc::new(DeveloperScorer));
+/// ```
+pub trait ConfidenceScorer: Send + Sync {
+    /// 计算一条结果的置信度
+    ///
+    /// # 参数
+    /// - `query`: 搜索关键词（提供者预处理之前的原始关键词）
+    /// - `meta`: 提供者返回的元数据
+    ///
+    /// # 返回
+    /// 置信度（0.0 ~ 1.0，超出范围的值由调用方按原样使用）
+    fn score(&self, query: &str, meta: &GameMetadata) -> f32;
+}
+
+/// 默认评分：基于标题匹配度、开发商/发行商匹配和数据完整度，体验版结果扣分
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultScorer;
+
+impl ConfidenceScorer for DefaultScorer {
+    fn score(&self, query: &str, meta: &GameMetadata) -> f32 {
+        super::calculate_confidence(query, meta)
+    }
+}
@@ -0,0 +1,133 @@
+//! 本地附属文件提供者
+//!
+//! 用户已经整理过的游戏，可以在游戏目录中放置 `gamebox.json` 或 `metadata.json`，
+//! 内容直接按 [`GameMetadata`] 的 JSON 结构反序列化。这类数据由用户维护，
+//! 因此优先级最高，命中时置信度为 1.0，覆盖所有刮削结果。
+//!
+//! 该提供者只能按目录查找（[`GameDatabaseProvider::search_in_dir`]），按标题搜索总是返回空结果。
+
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+
+use crate::models::game_meta_data::GameMetadata;
+use crate::providers::GameDatabaseProvider;
+
+/// 按顺序查找的附属文件名
+pub const SIDECAR_FILE_NAMES: [&str; 2] = ["gamebox.json", "metadata.json"];
+
+/// 本地附属文件提供者
+#[derive(Debug, Default)]
+pub struct SidecarProvider;
+
+impl SidecarProvider {
+    /// 创建附属文件提供者
+    pub fn new() -> Self {
+        SidecarProvider
+    }
+
+    /// 查找目录中的附属文件
+    fn find_file(dir: &Path) -> Option<PathBuf> {
+        SIDECAR_FILE_NAMES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+    }
+}
+
+#[async_trait]
+impl GameDatabaseProvider for SidecarProvider {
+    fn name(&self) -> &str {
+        "Sidecar"
+    }
+
+    /// 附属文件与目录绑定，无法按标题搜索
+    async fn search(&self, _title: &str) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+
+    /// 读取目录中的 `gamebox.json` / `metadata.json`
+    ///
+    /// 没有附属文件时返回空结果；文件无法解析时返回错误
+    async fn search_in_dir(
+        &self,
+        dir: &Path,
+        _title: &str,
+    ) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+        let Some(path) = Self::find_file(dir) else {
+            return Ok(Vec::new());
+        };
+
+        let content = tokio::fs::read_to_string(&path).await?;
+        let metadata: GameMetadata = serde_json::from_str(&content)
+            .map_err(|e| format!("无法解析附属文件 {}: {}", path.display(), e))?;
+        Ok(vec![metadata])
+    }
+
+    fn priority(&self) -> u32 {
+        100 // 用户整理的数据覆盖所有刮削结果
+    }
+
+    fn is_local(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::GameDatabaseMiddleware;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_sidecar_valid_and_malformed() {
+        let root = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        let valid = root.path().join("Catalogued");
+        let malformed = root.path().join("Broken");
+        let empty = root.path().join("Plain");
+        for dir in [&valid, &malformed, &empty] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        std::fs::write(
+            valid.join("gamebox.json"),
+            r#"{"title": "Catalogued Game", "developer": "Me", "genres": ["RPG"]}"#,
+        )
+        .unwrap();
+        std::fs::write(malformed.join("metadata.json"), "{ not json").unwrap();
+
+        let provider = SidecarProvider::new();
+        let results = provider.search_in_dir(&valid, "ignored").await.unwrap();
+        assert_eq!(results[0].title.as_deref(), Some("Catalogued Game"));
+        assert_eq!(results[0].genres, Some(vec!["RPG".to_string()]));
+        assert!(provider.search_in_dir(&malformed, "Broken").await.is_err());
+        assert!(provider.search_in_dir(&empty, "Plain").await.unwrap().is_empty());
+        assert!(provider.search("Catalogued Game").await.unwrap().is_empty());
+
+        // 通过中间件查找时置信度为 1.0，解析失败的附属文件视为没有结果
+        let middleware = GameDatabaseMiddleware::new();
+        middleware.register_provider(Arc::new(provider)).await;
+        let results = middleware.search_in_dir(&valid, "Catalogued").await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].source, "Sidecar");
+        assert_eq!(results[0].confidence, 1.0);
+        assert!(middleware.search_in_dir(&malformed, "Broken").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sidecar_trial_flag_taken_as_written() {
+        let root = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        let demo = root.path().join("Demo");
+        let flagged = root.path().join("Flagged");
+        for dir in [&demo, &flagged] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        std::fs::write(demo.join("gamebox.json"), r#"{"title": "Catalogued Game [Demo]"}"#).unwrap();
+        std::fs::write(flagged.join("gamebox.json"), r#"{"title": "Catalogued Game", "is_trial": true}"#).unwrap();
+
+        // 用户整理的数据按原样使用，不根据标题标记体验版
+        let middleware = GameDatabaseMiddleware::new();
+        middleware.register_provider(Arc::new(SidecarProvider::new())).await;
+        assert!(!middleware.search_in_dir(&demo, "Catalogued").await[0].info.is_trial);
+        assert!(middleware.search_in_dir(&flagged, "Catalogued").await[0].info.is_trial);
+    }
+}
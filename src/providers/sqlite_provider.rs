@@ -0,0 +1,302 @@
+//! SQLite 元数据库提供者
+//!
+//! 从用户自行维护的 SQLite 游戏数据库中读取元数据。数据库是本地的、由用户整理的，
+//! 因此优先级高于所有在线数据源。表名和列名可以通过 [`SqliteColumns`] 配置，
+//! 未配置的可选列视为不提供该字段。
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use rusqlite::{Connection, OptionalExtension, Row};
+
+use crate::models::game_meta_data::{FieldSet, GameMetadata};
+use crate::providers::{GameDatabaseProvider, Tier};
+
+/// 单次搜索最多返回的结果数量
+const SEARCH_LIMIT: usize = 20;
+
+/// 数据库表名和列名配置
+///
+/// 默认配置对应如下表结构：
+///
+/// ```sql
+/// CREATE TABLE games (
+///     id TEXT PRIMARY KEY, title TEXT, cover_url TEXT, description TEXT,
+///     release_date TEXT, developer TEXT, publisher TEXT, genres TEXT
+/// );
+/// ```
+///
+/// `genres` 列为逗号分隔的类型列表
+#[derive(Debug, Clone)]
+pub struct SqliteColumns {
+    /// 表名
+    pub table: String,
+    /// 主键列（`get_by_id` 使用）
+    pub id: String,
+    /// 标题列（`search` 使用 `LIKE` 匹配）
+    pub title: String,
+    /// 封面 URL 列
+    pub cover_url: Option<String>,
+    /// 简介列
+    pub description: Option<String>,
+    /// 发行日期列
+    pub release_date: Option<String>,
+    /// 开发商列
+    pub developer: Option<String>,
+    /// 发行商列
+    pub publisher: Option<String>,
+    /// 类型列（逗号分隔）
+    pub genres: Option<String>,
+}
+
+impl Default for SqliteColumns {
+    fn default() -> Self {
+        SqliteColumns {
+            table: "games".to_string(),
+            id: "id".to_string(),
+            title: "title".to_string(),
+            cover_url: Some("cover_url".to_string()),
+            description: Some("description".to_string()),
+            release_date: Some("release_date".to_string()),
+            developer: Some("developer".to_string()),
+            publisher: Some("publisher".to_string()),
+            genres: Some("genres".to_string()),
+        }
+    }
+}
+
+/// 为 SQL 标识符加引号
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// 转义 `LIKE` 模式中的通配符
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+impl SqliteColumns {
+    /// 查询列表：未配置的可选列以 `NULL` 占位，保证列的位置固定
+    fn select_list(&self) -> String {
+        let optional = [
+            &self.cover_url,
+            &self.description,
+            &self.release_date,
+            &self.developer,
+            &self.publisher,
+            &self.genres,
+        ];
+
+        let mut columns = vec![quote_identifier(&self.id), quote_identifier(&self.title)];
+        columns.extend(optional.iter().map(|column| match column {
+            Some(name) => quote_identifier(name),
+            None => "NULL".to_string(),
+        }));
+        columns.join(", ")
+    }
+
+    /// 已配置的列对应的字段
+    fn fields(&self) -> FieldSet {
+        let optional = [
+            (&self.cover_url, FieldSet::COVER),
+            (&self.description, FieldSet::DESCRIPTION),
+            (&self.release_date, FieldSet::RELEASE_DATE),
+            (&self.developer, FieldSet::DEVELOPER),
+            (&self.publisher, FieldSet::PUBLISHER),
+            (&self.genres, FieldSet::GENRES),
+        ];
+        optional
+            .iter()
+            .filter(|(column, _)| column.is_some())
+            .fold(FieldSet::TITLE, |fields, (_, field)| fields | *field)
+    }
+}
+
+/// 将查询结果行转换为元数据（列顺序与 [`SqliteColumns::select_list`] 一致）
+fn row_to_metadata(row: &Row) -> rusqlite::Result<GameMetadata> {
+    let genres: Option<String> = row.get(7)?;
+    Ok(GameMetadata {
+        provider_id: row.get::<_, rusqlite::types::Value>(0).map(|value| match value {
+            rusqlite::types::Value::Integer(id) => Some(id.to_string()),
+            rusqlite::types::Value::Text(id) => Some(id),
+            _ => None,
+        })?,
+        title: row.get(1)?,
+        cover_url: row.get(2)?,
+        description: row.get(3)?,
+        release_date: row.get(4)?,
+        developer: row.get(5)?,
+        publisher: row.get(6)?,
+        genres: genres.map(|genres| {
+            genres
+                .split(',')
+                .map(|genre| genre.trim().to_string())
+                .filter(|genre| !genre.is_empty())
+                .collect()
+        }),
+        ..Default::default()
+    })
+}
+
+/// SQLite 元数据库提供者
+pub struct SqliteProvider {
+    connection: Mutex<Connection>,
+    columns: SqliteColumns,
+    /// 提供者层级
+    tier: Tier,
+}
+
+impl SqliteProvider {
+    /// 打开 SQLite 数据库文件
+    ///
+    /// # 参数
+    /// - `path`: 数据库文件路径
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self::from_connection(Connection::open(path)?))
+    }
+
+    /// 使用已打开的数据库连接创建提供者
+    pub fn from_connection(connection: Connection) -> Self {
+        SqliteProvider {
+            connection: Mutex::new(connection),
+            columns: SqliteColumns::default(),
+            tier: Tier::Primary,
+        }
+    }
+
+    /// 设置表名和列名（链式调用）
+    pub fn with_columns(mut self, columns: SqliteColumns) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// 设置提供者层级（链式调用）
+    pub fn with_tier(mut self, tier: Tier) -> Self {
+        self.tier = tier;
+        self
+    }
+
+    fn connection(&self) -> Result<std::sync::MutexGuard<'_, Connection>, Box<dyn std::error::Error + Send + Sync>> {
+        self.connection
+            .lock()
+            .map_err(|_| "SQLite 连接不可用".into())
+    }
+}
+
+#[async_trait]
+impl GameDatabaseProvider for SqliteProvider {
+    fn name(&self) -> &str {
+        "SQLite"
+    }
+
+    /// 按标题模糊查找（`LIKE '%title%'`）
+    async fn search(&self, title: &str) -> Result<Vec<GameMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+        let sql = format!(
+            "SELECT {} FROM {} WHERE {} LIKE ?1 ESCAPE '\\' LIMIT {}",
+            self.columns.select_list(),
+            quote_identifier(&self.columns.table),
+            quote_identifier(&self.columns.title),
+            SEARCH_LIMIT
+        );
+        let pattern = format!("%{}%", escape_like(title.trim()));
+
+        let connection = self.connection()?;
+        let mut statement = connection.prepare(&sql)?;
+        let results = statement
+            .query_map([pattern], row_to_metadata)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(results)
+    }
+
+    /// 按主键查找
+    async fn get_by_id(&self, id: &str) -> Result<GameMetadata, Box<dyn std::error::Error + Send + Sync>> {
+        let sql = format!(
+            "SELECT {} FROM {} WHERE {} = ?1",
+            self.columns.select_list(),
+            quote_identifier(&self.columns.table),
+            quote_identifier(&self.columns.id)
+        );
+
+        let connection = self.connection()?;
+        connection
+            .query_row(&sql, [id], row_to_metadata)
+            .optional()?
+            .ok_or_else(|| format!("Game with ID {} not found", id).into())
+    }
+
+    fn priority(&self) -> u32 {
+        95 // 本地的、用户整理的数据，优先级最高
+    }
+
+    fn is_local(&self) -> bool {
+        true
+    }
+
+    fn tier(&self) -> Tier {
+        self.tier
+    }
+
+    fn provided_fields(&self) -> FieldSet {
+        self.columns.fields()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeded_provider() -> SqliteProvider {
+        let connection = Connection::open_in_memory().unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE library (game_id INTEGER PRIMARY KEY, name TEXT, studio TEXT, tags TEXT);
+                 INSERT INTO library VALUES (1, 'Hollow Knight', 'Team Cherry', 'Metroidvania, Action');
+                 INSERT INTO library VALUES (2, 'Hollow_Knight Silksong', 'Team Cherry', NULL);
+                 INSERT INTO library VALUES (3, 'Celeste', 'Maddy Makes Games', 'Platformer');",
+            )
+            .unwrap();
+
+        SqliteProvider::from_connection(connection).with_columns(SqliteColumns {
+            table: "library".to_string(),
+            id: "game_id".to_string(),
+            title: "name".to_string(),
+            cover_url: None,
+            description: None,
+            release_date: None,
+            developer: Some("studio".to_string()),
+            publisher: None,
+            genres: Some("tags".to_string()),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_search_and_get_by_id() {
+        let provider = seeded_provider();
+
+        let results = provider.search("hollow").await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title.as_deref(), Some("Hollow Knight"));
+        assert_eq!(results[0].developer.as_deref(), Some("Team Cherry"));
+        assert_eq!(
+            results[0].genres,
+            Some(vec!["Metroidvania".to_string(), "Action".to_string()])
+        );
+        assert_eq!(results[0].provider_id.as_deref(), Some("1"));
+
+        // `_` 按字面匹配而不是通配符
+        let results = provider.search("Hollow_Knight").await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title.as_deref(), Some("Hollow_Knight Silksong"));
+
+        let game = provider.get_by_id("3").await.unwrap();
+        assert_eq!(game.title.as_deref(), Some("Celeste"));
+        assert!(provider.get_by_id("42").await.is_err());
+
+        assert!(provider.provided_fields().contains(FieldSet::GENRES));
+        assert!(!provider.provided_fields().contains(FieldSet::COVER));
+    }
+}
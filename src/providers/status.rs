@@ -0,0 +1,136 @@
+//! 提供者状态报告
+//!
+//! 中间件记录每个提供者最近一次查询的结果和耗时，并结合熔断器和速率限制器的状态
+//! 生成状态报告，供运维一次性查看所有数据源的健康状况。报告可以格式化为紧凑的文本表格，
+//! 也可以序列化为 JSON 供 Web 接口使用。
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::providers::circuit_breaker::CircuitStatus;
+use crate::providers::Tier;
+
+/// 单个提供者的查询统计
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ProviderMetrics {
+    /// 查询次数
+    pub(crate) queries: u64,
+    /// 出错次数
+    pub(crate) errors: u64,
+    /// 最近一次查询的耗时
+    pub(crate) last_latency: Option<Duration>,
+    /// 最近一次查询是否成功
+    pub(crate) last_ok: Option<bool>,
+    /// 最近一次错误信息
+    pub(crate) last_error: Option<String>,
+}
+
+/// 按提供者名称记录查询统计
+#[derive(Debug, Default)]
+pub(crate) struct ProviderMetricsRegistry {
+    metrics: Mutex<HashMap<String, ProviderMetrics>>,
+}
+
+impl ProviderMetricsRegistry {
+    /// 记录一次查询
+    ///
+    /// # 参数
+    /// - `provider`: 提供者名称
+    /// - `latency`: 查询耗时（包括重试）
+    /// - `error`: 查询失败时的错误信息
+    pub(crate) fn record(&self, provider: &str, latency: Duration, error: Option<&str>) {
+        let mut metrics = self.metrics.lock().unwrap();
+        let entry = metrics.entry(provider.to_string()).or_default();
+        entry.queries += 1;
+        entry.last_latency = Some(latency);
+        entry.last_ok = Some(error.is_none());
+        if let Some(error) = error {
+            entry.errors += 1;
+            entry.last_error = Some(error.to_string());
+        }
+    }
+
+    /// 获取提供者的查询统计，从未查询过时返回空统计
+    pub(crate) fn get(&self, provider: &str) -> ProviderMetrics {
+        self.metrics.lock().unwrap().get(provider).cloned().unwrap_or_default()
+    }
+}
+
+/// 提供者状态
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderStatus {
+    /// 提供者名称
+    pub name: String,
+    /// 提供者层级
+    pub tier: Tier,
+    /// 提供者优先级
+    pub priority: u32,
+    /// 最近一次查询是否成功（`None` 表示尚未查询过）
+    pub reachable: Option<bool>,
+    /// 最近一次查询的耗时（毫秒）
+    pub last_latency_ms: Option<u64>,
+    /// 查询次数
+    pub queries: u64,
+    /// 出错次数
+    pub errors: u64,
+    /// 最近一次错误信息
+    pub last_error: Option<String>,
+    /// 熔断状态
+    pub circuit: CircuitStatus,
+    /// 当前可用的 API 请求许可数（所有提供者共享）
+    pub rate_limit_remaining: usize,
+    /// 剩余的全局重试次数（`None` 表示未设置重试预算）
+    pub retry_budget_remaining: Option<u32>,
+}
+
+impl ProviderStatus {
+    /// 格式化为表格中的一行
+    pub fn format(&self) -> String {
+        let reachable = match self.reachable {
+            Some(true) => "ok",
+            Some(false) => "down",
+            None => "-",
+        };
+        let latency = self
+            .last_latency_ms
+            .map(|ms| format!("{}ms", ms))
+            .unwrap_or_else(|| "-".to_string());
+        let retries = self
+            .retry_budget_remaining
+            .map(|remaining| remaining.to_string())
+            .unwrap_or_else(|| "-".to_string());
+
+        format!(
+            "{:<16} {:<6} {:>9} {:>5}/{:<5} {:<9} {:>5} {:>7}",
+            self.name, reachable, latency, self.errors, self.queries, self.circuit, self.rate_limit_remaining, retries
+        )
+    }
+
+    /// 将一组提供者状态格式化为带表头的紧凑表格
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use gamebox::providers::GameDatabaseMiddleware;
+    /// use gamebox::providers::status::ProviderStatus;
+    ///
+    /// # async fn example(middleware: GameDatabaseMiddleware) {
+    /// let report = middleware.status_report().await;
+    /// println!("{}", ProviderStatus::format_table(&report));
+    /// # }
+    /// ```
+    pub fn format_table(statuses: &[ProviderStatus]) -> String {
+        let mut table = format!(
+            "{:<16} {:<6} {:>9} {:>11} {:<9} {:>5} {:>7}\n",
+            "PROVIDER", "STATUS", "LATENCY", "ERRORS", "CIRCUIT", "RATE", "RETRIES"
+        );
+        for status in statuses {
+            let _ = writeln!(table, "{}", status.format());
+        }
+        table
+    }
+}
@@ -1,15 +1,24 @@
 use async_trait::async_trait;
-use crate::models::game_meta_data::GameMetadata;
-use crate::providers::GameDatabaseProvider;
+use crate::models::game_meta_data::{FieldSet, GameMetadata};
+use crate::providers::{GameDatabaseProvider, Tier};
 
 /// TheGamesDB 数据库提供者
 pub struct TheGamesDBProvider {
-    // 可以添加配置
+    /// 提供者层级
+    tier: Tier,
 }
 
 impl TheGamesDBProvider {
     pub fn new() -> Self {
-        TheGamesDBProvider {}
+        TheGamesDBProvider {
+            tier: Tier::Primary,
+        }
+    }
+
+    /// 设置提供者层级（链式调用）
+    pub fn with_tier(mut self, tier: Tier) -> Self {
+        self.tier = tier;
+        self
     }
 }
 
@@ -37,6 +46,15 @@ impl GameDatabaseProvider for TheGamesDBProvider {
             cover_url: None,
             genres: Some(vec!["Adventure".to_string()]),
             tags: None,
+            platforms: None,
+            price: None,
+            currency: None,
+            on_sale: None,
+            provider_id: None,
+            rating: None,
+            release_dates: None,
+            extra_cover_urls: Vec::new(),
+            is_trial: false,
         }])
     }
 
@@ -51,6 +69,15 @@ impl GameDatabaseProvider for TheGamesDBProvider {
             cover_url: None,
             genres: Some(vec!["Adventure".to_string()]),
             tags: None,
+            platforms: None,
+            price: None,
+            currency: None,
+            on_sale: None,
+            provider_id: Some(id.to_string()),
+            rating: None,
+            release_dates: None,
+            extra_cover_urls: Vec::new(),
+            is_trial: false,
         })
     }
 
@@ -61,6 +88,15 @@ impl GameDatabaseProvider for TheGamesDBProvider {
     fn supports_game_type(&self, game_type: &str) -> bool {
         matches!(game_type, "classic_game" | "retro_game" | "multi_platform" | "all")
     }
+
+    fn tier(&self) -> Tier {
+        self.tier
+    }
+
+    /// 目前只返回占位数据，除标题外的字段都不可靠
+    fn provided_fields(&self) -> FieldSet {
+        FieldSet::TITLE
+    }
 }
 
 #[cfg(test)]
@@ -88,6 +124,12 @@ mod tests {
         assert_eq!(provider.priority(), 70);
     }
 
+    #[tokio::test]
+    async fn test_thegamesdb_provider_tier() {
+        assert_eq!(TheGamesDBProvider::new().tier(), Tier::Primary);
+        assert_eq!(TheGamesDBProvider::new().with_tier(Tier::Fallback).tier(), Tier::Fallback);
+    }
+
     #[tokio::test]
     async fn test_thegamesdb_provider_supports_game_type() {
         let provider = TheGamesDBProvider::new();
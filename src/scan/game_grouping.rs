@@ -3,9 +3,13 @@
 //! 该模块负责将扫描到的游戏文件路径按照游戏根目录进行分组，
 //! 并提取游戏的版本号和搜索关键词。
 
+use std::path::PathBuf;
+
 use ignore::DirEntry;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use crate::scan::utils::{extract_search_key, extract_version, find_common_parent_dir};
+use crate::scan::patterns::EPISODE_SUFFIX_PATTERNS;
+use crate::scan::utils::{extract_circle_and_title, extract_version, find_common_parent_dir};
 
 /// 路径分组结果
 ///
@@ -22,6 +26,19 @@ pub struct PathGroupResult {
     pub search_key: String,
     /// 从目录名中提取的版本号
     pub version: Option<String>,
+    /// 合并的分集目录名称（相对于游戏根目录），普通游戏为空
+    #[serde(default)]
+    pub episodes: Vec<String>,
+    /// 是否是直接放在扫描根目录中的单文件游戏（平铺的游戏库）
+    ///
+    /// 这类游戏的根目录是扫描根目录，只有 `child_path` 中的文件属于该游戏
+    #[serde(default)]
+    pub standalone: bool,
+    /// 外部来源（如安装清单）记录的游戏名称
+    ///
+    /// 没有从提供者获取到标题时，优先于可执行文件的产品名称和目录名作为标题
+    #[serde(default)]
+    pub fallback_title: Option<String>,
 }
 
 /// 目录条目过滤器 trait
@@ -46,13 +63,74 @@ impl DirEntryFilter for Vec<DirEntry> {
     }
 }
 
+/// 分组选项
+///
+/// 控制 [`paths_group_with_options`] 的启发式规则
+#[derive(Debug, Clone)]
+pub struct GroupingOptions {
+    /// 单个第一级目录最多拆分出的游戏数量
+    ///
+    /// 默认为 1，即每个第一级目录视为一个游戏（原有行为）。
+    /// 大于 1 时，如果某个第一级目录本身没有可执行文件，而其下有 2 到该数量个
+    /// 各自包含可执行文件的第二级目录（如 `发行商/游戏1`、`发行商/游戏2`），
+    /// 则按第二级目录拆分为多个游戏。
+    pub max_games_per_first_level: usize,
+    /// 确定性输出
+    ///
+    /// 默认为 `false`。启用后第一级分组按目录名有序遍历，结果排序时以游戏根目录打破平局，
+    /// 保证相同的输入总是得到相同顺序的结果
+    pub deterministic: bool,
+    /// 并行处理第一级分组
+    ///
+    /// 默认为 `false`。各第一级分组相互独立，可执行文件数以万计时可以并行计算公共父目录；
+    /// 结果顺序与串行处理完全相同。需要启用 `rayon` feature，否则该选项被忽略
+    pub parallel: bool,
+    /// 合并分集目录
+    ///
+    /// 默认为 `false`。启用后同一目录下标题相同、仅分集后缀不同的游戏（如 `GameName_Ep1`、
+    /// `GameName_Ep2`）合并为一个游戏，游戏根目录为它们的上级目录，各分集的可执行文件都作为启动项
+    pub episode_grouping: bool,
+    /// 分集后缀正则，匹配目录名末尾的分集/章节标识（默认为 `EPISODE_SUFFIX_PATTERNS`）
+    pub episode_patterns: Vec<Regex>,
+    /// 将直接放在扫描根目录中的可执行文件视为独立的游戏
+    ///
+    /// 默认为 `true`。便携游戏合集常把所有 exe 平铺在同一个目录中，没有第一级目录可以分组；
+    /// 启用后每个这样的文件各自成为一个游戏，以文件名（不含扩展名）作为游戏名称
+    pub standalone_root_files: bool,
+}
+
+impl Default for GroupingOptions {
+    fn default() -> Self {
+        GroupingOptions {
+            max_games_per_first_level: 1,
+            deterministic: false,
+            parallel: false,
+            episode_grouping: false,
+            episode_patterns: EPISODE_SUFFIX_PATTERNS.clone(),
+            standalone_root_files: true,
+        }
+    }
+}
+
+/// 通用的平台目录名称，这类目录不会被当作独立的游戏
+const COMMON_PLATFORM_NAMES: [&str; 6] = ["Windows", "Linux", "Mac", "MacOS", "Android", "iOS"];
+
+/// 基于最近公共父目录分组（使用默认选项）
+///
+/// 参见 [`paths_group_with_options`]
+pub fn paths_group(paths: Vec<PathBuf>) -> Vec<PathGroupResult> {
+    paths_group_with_options(paths, &GroupingOptions::default())
+}
+
 /// 基于最近公共父目录分组
 ///
 /// 将多个 exe 文件路径按照它们的最近公共父目录分组。
 /// 每组的游戏根目录是该组所有 exe 文件的最近公共父目录。
 ///
+/// 分组只使用路径的各个组件，不访问文件系统，因此直接接收扫描时收集到的路径。
+///
 /// # 参数
-/// - `paths`: 扫描到的目录条目列表（通常是可执行文件）
+/// - `paths`: 扫描到的文件路径列表（通常是可执行文件）
 ///
 /// # 返回
 /// 分组后的路径结果列表
@@ -64,8 +142,11 @@ impl DirEntryFilter for Vec<DirEntry> {
 /// 4. 使用启发式规则决定游戏根目录：
 ///    - 默认使用第一级目录
 ///    - 如果第一级包含前缀标签（如【RPG】），且第二级不是平台名称，则使用第二级
+///    - 如果启用了拆分（`max_games_per_first_level > 1`），且第一级目录只是包装了多个游戏的
+///      发行商目录，则按第二级目录拆分
+///    - 直接放在扫描根目录中的可执行文件（平铺的游戏库）各自作为一个游戏（`standalone_root_files`）
 /// 5. 提取版本号和搜索关键词
-pub fn paths_group(paths: Vec<DirEntry>) -> Vec<PathGroupResult> {
+pub fn paths_group_with_options(paths: Vec<PathBuf>, options: &GroupingOptions) -> Vec<PathGroupResult> {
     if paths.is_empty() {
         return Vec::new();
     }
@@ -74,8 +155,8 @@ pub fn paths_group(paths: Vec<DirEntry>) -> Vec<PathGroupResult> {
     // 将路径分割为组件，只在需要时进行字符串分配
     let path_components: Vec<Vec<String>> = paths
         .iter()
-        .map(|entry| {
-            let path_str = entry.path().to_string_lossy();
+        .map(|path| {
+            let path_str = path.to_string_lossy();
 
             // 只在包含反斜杠时才进行替换（Windows 路径）
             if path_str.contains('\\') {
@@ -110,9 +191,13 @@ pub fn paths_group(paths: Vec<DirEntry>) -> Vec<PathGroupResult> {
     // 按照扫描根目录后的第一级目录进行初步分组
     let mut first_level_groups: std::collections::HashMap<String, Vec<usize>> =
         std::collections::HashMap::new();
+    // 直接放在扫描根目录中的文件，没有第一级目录可以分组
+    let mut root_files: Vec<usize> = Vec::new();
 
     for (idx, path) in path_components.iter().enumerate() {
-        if scan_root_len < path.len() {
+        if options.standalone_root_files && path.len() == scan_root_len + 1 {
+            root_files.push(idx);
+        } else if scan_root_len < path.len() {
             let first_level_dir = path[scan_root_len].clone();
             first_level_groups
                 .entry(first_level_dir)
@@ -121,91 +206,295 @@ pub fn paths_group(paths: Vec<DirEntry>) -> Vec<PathGroupResult> {
         }
     }
 
-    // 对每个第一级分组，找到最近公共父目录
-    let mut results: Vec<PathGroupResult> = Vec::new();
+    // 确定性输出时按目录名有序遍历第一级分组
+    let first_level_groups: Vec<(String, Vec<usize>)> = if options.deterministic {
+        first_level_groups
+            .into_iter()
+            .collect::<std::collections::BTreeMap<_, _>>()
+            .into_iter()
+            .collect()
+    } else {
+        first_level_groups.into_iter().collect()
+    };
+
+    // 对每个第一级分组，找到最近公共父目录（各分组相互独立，可以并行处理）
+    #[cfg(feature = "rayon")]
+    let mut results: Vec<PathGroupResult> = if options.parallel {
+        use rayon::prelude::*;
+        first_level_groups
+            .par_iter()
+            .flat_map_iter(|(_, indices)| group_first_level(&path_components, indices, scan_root_len, options))
+            .collect()
+    } else {
+        first_level_groups
+            .iter()
+            .flat_map(|(_, indices)| group_first_level(&path_components, indices, scan_root_len, options))
+            .collect()
+    };
+    #[cfg(not(feature = "rayon"))]
+    let mut results: Vec<PathGroupResult> = first_level_groups
+        .iter()
+        .flat_map(|(_, indices)| group_first_level(&path_components, indices, scan_root_len, options))
+        .collect();
 
-    for (_first_level_dir, indices) in first_level_groups {
-        // 获取这个组的所有路径
-        let group_paths: Vec<Vec<String>> = indices
+    results.extend(
+        root_files
             .iter()
-            .map(|&idx| path_components[idx].clone())
+            .map(|&idx| build_standalone_result(&path_components[idx], scan_root_len)),
+    );
+
+    if options.episode_grouping {
+        results = merge_episode_groups(results, &options.episode_patterns);
+    }
+
+    // 按照 child_path 的第一个元素排序，保证结果的一致性
+    if options.deterministic {
+        results.sort_by(|a, b| {
+            a.child_path
+                .first()
+                .cmp(&b.child_path.first())
+                .then_with(|| a.root_path.cmp(&b.root_path))
+        });
+    } else {
+        results.sort_by(|a, b| a.child_path.first().cmp(&b.child_path.first()));
+    }
+
+    results
+}
+
+/// 处理单个第一级分组：决定游戏根目录，必要时拆分发行商目录
+fn group_first_level(
+    path_components: &[Vec<String>],
+    indices: &[usize],
+    scan_root_len: usize,
+    options: &GroupingOptions,
+) -> Vec<PathGroupResult> {
+    // 获取这个组的所有路径
+    let group_paths: Vec<Vec<String>> = indices
+        .iter()
+        .map(|&idx| path_components[idx].clone())
+        .collect();
+
+    // 找到这组路径的最近公共父目录
+    let common_parent_len = find_common_parent_dir(&group_paths);
+
+    // 发行商目录包装多个游戏时，按第二级目录拆分
+    if let Some(splits) =
+        split_publisher_group(path_components, indices, scan_root_len, common_parent_len, options)
+    {
+        return splits
+            .iter()
+            .map(|split_indices| build_group_result(path_components, split_indices, scan_root_len + 2))
             .collect();
+    }
 
-        // 找到这组路径的最近公共父目录
-        let common_parent_len = find_common_parent_dir(&group_paths);
-
-        // 决定游戏根目录：
-        // 默认使用第一级目录（scan_root_len + 1）
-        let mut game_root_len = scan_root_len + 1;
-
-        // 如果公共父目录是第二级（scan_root_len + 2），需要判断是否使用第二级
-        if common_parent_len == scan_root_len + 2
-            && common_parent_len <= path_components[indices[0]].len()
-        {
-            let first_level_name = &path_components[indices[0]][scan_root_len];
-            let second_level_name = &path_components[indices[0]][scan_root_len + 1];
-
-            // 启发式规则：
-            // 1. 如果第二级目录名是通用的平台名称（Windows, Linux, Mac等），使用第一级
-            // 2. 否则，如果第一级包含前缀标签，使用第二级
-            let common_platform_names = ["Windows", "Linux", "Mac", "MacOS", "Android", "iOS"];
-            let is_platform_dir = common_platform_names
-                .iter()
-                .any(|&name| second_level_name == name);
-
-            if !is_platform_dir {
-                let first_has_prefix =
-                    first_level_name.contains('【') || first_level_name.contains('[');
-
-                if first_has_prefix {
-                    // 使用第二级作为游戏根目录
-                    game_root_len = scan_root_len + 2;
-                }
+    // 决定游戏根目录：
+    // 默认使用第一级目录（scan_root_len + 1）
+    let mut game_root_len = scan_root_len + 1;
+
+    // 如果公共父目录是第二级（scan_root_len + 2），需要判断是否使用第二级
+    if common_parent_len == scan_root_len + 2
+        && common_parent_len <= path_components[indices[0]].len()
+    {
+        let first_level_name = &path_components[indices[0]][scan_root_len];
+        let second_level_name = &path_components[indices[0]][scan_root_len + 1];
+
+        // 启发式规则：
+        // 1. 如果第二级目录名是通用的平台名称（Windows, Linux, Mac等），使用第一级
+        // 2. 否则，如果第一级包含前缀标签，使用第二级
+        let is_platform_dir = COMMON_PLATFORM_NAMES
+            .iter()
+            .any(|&name| second_level_name == name);
+
+        if !is_platform_dir {
+            let first_has_prefix =
+                first_level_name.contains('【') || first_level_name.contains('[');
+
+            if first_has_prefix {
+                // 使用第二级作为游戏根目录
+                game_root_len = scan_root_len + 2;
             }
         }
+    }
 
-        // 构建游戏根目录路径
-        let game_root_path =
-            if game_root_len > 0 && game_root_len <= path_components[indices[0]].len() {
-                path_components[indices[0]][0..game_root_len].join("/")
-            } else {
-                String::new()
-            };
+    vec![build_group_result(path_components, indices, game_root_len)]
+}
 
-        // 提取游戏根目录名称（最后一个组件）
-        let game_root_name =
-            if game_root_len > 0 && game_root_len <= path_components[indices[0]].len() {
-                path_components[indices[0]][game_root_len - 1].clone()
-            } else {
-                "Unknown".to_string()
-            };
-
-        // 构建相对路径列表（相对于游戏根目录）
-        let mut child_paths: Vec<String> = Vec::new();
-        for &idx in &indices {
-            if game_root_len < path_components[idx].len() {
-                let relative_path = path_components[idx][game_root_len..].join("/");
-                child_paths.push(relative_path);
+/// 判断第一级分组是否是包装了多个游戏的发行商目录，如果是则返回按第二级目录拆分后的索引
+///
+/// 满足以下全部条件时拆分：
+/// - 启用了拆分（`max_games_per_first_level > 1`）
+/// - 分组的最近公共父目录就是第一级目录本身
+/// - 第一级目录下没有直接放置的可执行文件
+/// - 第二级目录数量在 2 到 `max_games_per_first_level` 之间，且都不是平台目录
+fn split_publisher_group(
+    path_components: &[Vec<String>],
+    indices: &[usize],
+    scan_root_len: usize,
+    common_parent_len: usize,
+    options: &GroupingOptions,
+) -> Option<Vec<Vec<usize>>> {
+    if options.max_games_per_first_level <= 1 || common_parent_len != scan_root_len + 1 {
+        return None;
+    }
+
+    // 第一级目录下直接有可执行文件，说明第一级目录就是游戏本体
+    if indices.iter().any(|&idx| path_components[idx].len() <= scan_root_len + 2) {
+        return None;
+    }
+
+    // 按第二级目录分组，保持首次出现的顺序
+    let mut second_level_groups: Vec<(&String, Vec<usize>)> = Vec::new();
+    for &idx in indices {
+        let second_level_name = &path_components[idx][scan_root_len + 1];
+        match second_level_groups.iter_mut().find(|(name, _)| *name == second_level_name) {
+            Some((_, group)) => group.push(idx),
+            None => second_level_groups.push((second_level_name, vec![idx])),
+        }
+    }
+
+    let count = second_level_groups.len();
+    if count < 2 || count > options.max_games_per_first_level {
+        return None;
+    }
+    if second_level_groups
+        .iter()
+        .any(|(name, _)| COMMON_PLATFORM_NAMES.contains(&name.as_str()))
+    {
+        return None;
+    }
+
+    Some(second_level_groups.into_iter().map(|(_, group)| group).collect())
+}
+
+/// 去除分集后缀后的共同标题，目录名不是分集时返回 `None`
+fn episode_title<'a>(name: &'a str, patterns: &[Regex]) -> Option<&'a str> {
+    patterns.iter().find_map(|re| {
+        let suffix = re.find(name)?;
+        let title = name[..suffix.start()].trim_end_matches([' ', '_', '-', '.']);
+        (!title.is_empty()).then_some(title)
+    })
+}
+
+/// 合并同一目录下的分集游戏
+///
+/// 上级目录相同、去除分集后缀后标题相同（忽略大小写）的两个及以上分组合并为一个，
+/// 游戏根目录为上级目录，启动项路径加上分集目录名前缀
+fn merge_episode_groups(results: Vec<PathGroupResult>, patterns: &[Regex]) -> Vec<PathGroupResult> {
+    // 按 (上级目录, 标题) 归类，保持首次出现的顺序
+    let mut buckets: Vec<((String, String), Vec<PathGroupResult>)> = Vec::new();
+    let mut merged: Vec<PathGroupResult> = Vec::new();
+    for result in results {
+        let key = match (result.root_path.rsplit_once('/'), episode_title(&result.child_root_name, patterns)) {
+            (Some((parent, _)), Some(title)) if result.episodes.is_empty() && !result.standalone => {
+                (parent.to_string(), title.to_lowercase())
             }
+            _ => {
+                merged.push(result);
+                continue;
+            }
+        };
+        match buckets.iter_mut().find(|(bucket_key, _)| *bucket_key == key) {
+            Some((_, members)) => members.push(result),
+            None => buckets.push((key, vec![result])),
+        }
+    }
+
+    for ((parent, _), mut members) in buckets {
+        if members.len() < 2 {
+            merged.extend(members);
+            continue;
         }
 
-        // 提取版本号和搜索关键词
-        let version = extract_version(&game_root_name);
-        let search_key = extract_search_key(&game_root_name);
+        members.sort_by(|a, b| a.child_root_name.cmp(&b.child_root_name));
+        let title = episode_title(&members[0].child_root_name, patterns)
+            .unwrap_or_default()
+            .to_string();
+        let child_path = members
+            .iter()
+            .flat_map(|m| m.child_path.iter().map(move |p| format!("{}/{}", m.child_root_name, p)))
+            .collect();
+        let (_, search_key) = extract_circle_and_title(&title);
 
-        results.push(PathGroupResult {
-            root_path: game_root_path,
-            child_root_name: game_root_name,
-            child_path: child_paths,
+        merged.push(PathGroupResult {
+            root_path: parent,
+            version: extract_version(&title),
+            child_path,
             search_key,
-            version,
+            episodes: members.into_iter().map(|m| m.child_root_name).collect(),
+            child_root_name: title,
+            standalone: false,
+            fallback_title: None,
         });
     }
+    merged
+}
 
-    // 按照 child_path 的第一个元素排序，保证结果的一致性
-    results.sort_by(|a, b| a.child_path.first().cmp(&b.child_path.first()));
+/// 根据游戏根目录深度构建分组结果
+fn build_group_result(path_components: &[Vec<String>], indices: &[usize], game_root_len: usize) -> PathGroupResult {
+    // 构建游戏根目录路径
+    let game_root_path =
+        if game_root_len > 0 && game_root_len <= path_components[indices[0]].len() {
+            path_components[indices[0]][0..game_root_len].join("/")
+        } else {
+            String::new()
+        };
 
-    results
+    // 提取游戏根目录名称（最后一个组件）
+    let game_root_name =
+        if game_root_len > 0 && game_root_len <= path_components[indices[0]].len() {
+            path_components[indices[0]][game_root_len - 1].clone()
+        } else {
+            "Unknown".to_string()
+        };
+
+    // 构建相对路径列表（相对于游戏根目录）
+    let mut child_paths: Vec<String> = Vec::new();
+    for &idx in indices {
+        if game_root_len < path_components[idx].len() {
+            let relative_path = path_components[idx][game_root_len..].join("/");
+            child_paths.push(relative_path);
+        }
+    }
+
+    // 提取版本号和搜索关键词
+    let version = extract_version(&game_root_name);
+    // 同人作品目录名中的社团名称不参与搜索
+    let (_, search_key) = extract_circle_and_title(&game_root_name);
+
+    PathGroupResult {
+        root_path: game_root_path,
+        child_root_name: game_root_name,
+        child_path: child_paths,
+        search_key,
+        version,
+        episodes: Vec::new(),
+        standalone: false,
+        fallback_title: None,
+    }
+}
+
+/// 构建直接放在扫描根目录中的单文件游戏：游戏根目录为扫描根目录，以文件名（不含扩展名）作为游戏名称
+fn build_standalone_result(path: &[String], scan_root_len: usize) -> PathGroupResult {
+    let file_name = &path[scan_root_len];
+    let name = std::path::Path::new(file_name)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| file_name.clone());
+
+    let version = extract_version(&name);
+    let (_, search_key) = extract_circle_and_title(&name);
+
+    PathGroupResult {
+        root_path: path[..scan_root_len].join("/"),
+        child_root_name: name,
+        child_path: vec![file_name.clone()],
+        search_key,
+        version,
+        episodes: Vec::new(),
+        standalone: true,
+        fallback_title: None,
+    }
 }
 
 #[cfg(test)]
@@ -220,6 +509,9 @@ mod tests {
             child_path: vec!["game.exe".to_string()],
             search_key: "Game1".to_string(),
             version: Some("1.0".to_string()),
+            episodes: Vec::new(),
+            standalone: false,
+            fallback_title: None,
         };
 
         let json = serde_json::to_string(&result).unwrap();
@@ -230,5 +522,145 @@ mod tests {
         assert_eq!(result.search_key, deserialized.search_key);
         assert_eq!(result.version, deserialized.version);
     }
+
+    /// 在临时目录中创建文件，并返回其中所有 .exe 文件的路径
+    fn exe_paths(root: &std::path::Path, files: &[&str]) -> Vec<PathBuf> {
+        for file in files {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, b"MZ").unwrap();
+        }
+
+        let mut paths: Vec<PathBuf> = ignore::Walk::new(root)
+            .filter_map(Result::ok)
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "exe"))
+            .map(|e| e.into_path())
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn test_publisher_wrapped_games_are_split() {
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        let files = [
+            "Publisher/Game1/game1.exe",
+            "Publisher/Game2/bin/game2.exe",
+            "Solo/solo.exe",
+            "Solo/Engine/engine.exe",
+        ];
+
+        // 默认选项：发行商目录被当作一个游戏
+        let groups = paths_group(exe_paths(dir.path(), &files));
+        let names: Vec<&str> = groups.iter().map(|g| g.child_root_name.as_str()).collect();
+        assert_eq!(names.len(), 2);
+        assert!(names.contains(&"Publisher"));
+
+        // 启用拆分：发行商目录按第二级目录拆成两个游戏，Solo 保持不变
+        let options = GroupingOptions {
+            max_games_per_first_level: 8,
+            ..GroupingOptions::default()
+        };
+        let groups = paths_group_with_options(exe_paths(dir.path(), &files), &options);
+        let mut names: Vec<&str> = groups.iter().map(|g| g.child_root_name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["Game1", "Game2", "Solo"]);
+
+        let game2 = groups.iter().find(|g| g.child_root_name == "Game2").unwrap();
+        assert_eq!(game2.child_path, vec!["bin/game2.exe".to_string()]);
+        assert!(game2.root_path.ends_with("Publisher/Game2"));
+
+        let solo = groups.iter().find(|g| g.child_root_name == "Solo").unwrap();
+        assert_eq!(solo.child_path.len(), 2);
+    }
+
+    #[test]
+    fn test_flat_library_root_files_are_separate_games() {
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        let files = ["Cave Story.exe", "Spelunky v1.1.exe", "VVVVVV.exe"];
+
+        let groups = paths_group_with_options(
+            exe_paths(dir.path(), &files),
+            &GroupingOptions { deterministic: true, ..GroupingOptions::default() },
+        );
+        let names: Vec<&str> = groups.iter().map(|g| g.child_root_name.as_str()).collect();
+        assert_eq!(names, vec!["Cave Story", "Spelunky v1.1", "VVVVVV"]);
+
+        let spelunky = &groups[1];
+        assert_eq!(spelunky.child_path, vec!["Spelunky v1.1.exe".to_string()]);
+        assert_eq!(spelunky.version.as_deref(), Some("1.1"));
+        assert_eq!(spelunky.search_key, "Spelunky");
+        assert_eq!(std::path::Path::new(&spelunky.root_path), dir.path());
+        assert!(groups.iter().all(|g| g.standalone));
+
+        // 与普通游戏目录混合时，根目录中的文件同样各自成为一个游戏
+        let groups = paths_group(exe_paths(dir.path(), &["Celeste/Celeste.exe"]));
+        assert_eq!(groups.len(), 4);
+        assert!(!groups.iter().find(|g| g.child_root_name == "Celeste").unwrap().standalone);
+    }
+
+    #[test]
+    fn test_parallel_grouping_matches_serial() {
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        let files: Vec<String> = (0..200)
+            .flat_map(|i| {
+                [
+                    format!("Game {:03}/game.exe", i),
+                    format!("Game {:03}/tools/config.exe", i),
+                    format!("Publisher {:02}/Title {:03}/bin/title.exe", i % 20, i),
+                ]
+            })
+            .collect();
+        let files: Vec<&str> = files.iter().map(String::as_str).collect();
+        let entries = exe_paths(dir.path(), &files);
+
+        let serial = GroupingOptions {
+            max_games_per_first_level: 16,
+            deterministic: true,
+            parallel: false,
+            ..GroupingOptions::default()
+        };
+        let parallel = GroupingOptions { parallel: true, ..serial.clone() };
+
+        let serial_groups = paths_group_with_options(entries.clone(), &serial);
+        let parallel_groups = paths_group_with_options(entries, &parallel);
+
+        assert_eq!(serial_groups.len(), 200 + 200);
+        assert_eq!(
+            serde_json::to_string(&serial_groups).unwrap(),
+            serde_json::to_string(&parallel_groups).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_episode_folders_collapse_into_one_game() {
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        let files = [
+            "GameName_Ep1/game.exe",
+            "GameName_Ep2/game.exe",
+            "GameName_Ep3/bin/game.exe",
+            "Other Game/other.exe",
+        ];
+
+        let groups = paths_group(exe_paths(dir.path(), &files));
+        assert_eq!(groups.len(), 4);
+
+        let options = GroupingOptions {
+            episode_grouping: true,
+            ..GroupingOptions::default()
+        };
+        let groups = paths_group_with_options(exe_paths(dir.path(), &files), &options);
+        assert_eq!(groups.len(), 2);
+
+        let game = groups.iter().find(|g| g.child_root_name == "GameName").unwrap();
+        assert_eq!(game.search_key, "GameName");
+        assert_eq!(game.episodes, vec!["GameName_Ep1", "GameName_Ep2", "GameName_Ep3"]);
+        assert_eq!(
+            game.child_path,
+            vec!["GameName_Ep1/game.exe", "GameName_Ep2/game.exe", "GameName_Ep3/bin/game.exe"]
+        );
+        assert!(std::path::Path::new(&game.root_path).join(&game.child_path[2]).exists());
+    }
+
 }
 
@@ -0,0 +1,143 @@
+//! 外部启动器安装清单
+//!
+//! 包管理器和部分商店会维护一份已安装游戏的清单。从清单扫描时直接使用其中记录的
+//! 游戏名称、安装目录和启动程序构建分组结果，不再遍历磁盘。
+//!
+//! 清单支持 JSON 和 TOML 两种格式（按扩展名 `.toml` 区分，其余按 JSON 解析），
+//! 两种格式的结构相同：
+//!
+//! ```toml
+//! [[games]]
+//! name = "Elden Ring"
+//! install_dir = "D:/Games/ELDEN RING"
+//! launcher = "Game/eldenring.exe"
+//! ```
+//!
+//! `launcher` 可以是相对于安装目录的路径，也可以是安装目录下的绝对路径，可省略。
+
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::scan::game_grouping::PathGroupResult;
+use crate::scan::utils::extract_version;
+
+/// 清单中的单个游戏
+#[derive(Debug, Clone, Deserialize)]
+pub struct ManifestEntry {
+    /// 游戏名称，直接作为搜索关键词；没有匹配结果时作为标题
+    pub name: String,
+    /// 安装目录
+    pub install_dir: PathBuf,
+    /// 启动程序路径
+    #[serde(default)]
+    pub launcher: Option<PathBuf>,
+}
+
+/// 安装清单
+#[derive(Debug, Clone, Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    games: Vec<ManifestEntry>,
+}
+
+impl ManifestEntry {
+    /// 转换为路径分组结果
+    pub fn to_group(&self) -> PathGroupResult {
+        let child_root_name = self
+            .install_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.name.clone());
+
+        let child_path = self
+            .launcher
+            .as_ref()
+            .map(|launcher| {
+                let relative = launcher.strip_prefix(&self.install_dir).unwrap_or(launcher);
+                relative.to_string_lossy().replace('\\', "/")
+            })
+            .into_iter()
+            .collect();
+
+        PathGroupResult {
+            root_path: self.install_dir.to_string_lossy().to_string(),
+            version: extract_version(&child_root_name),
+            child_root_name,
+            child_path,
+            search_key: self.name.trim().to_string(),
+            episodes: Vec::new(),
+            standalone: false,
+            fallback_title: Some(self.name.trim().to_string()).filter(|name| !name.is_empty()),
+        }
+    }
+}
+
+/// 读取安装清单
+///
+/// # 参数
+/// - `path`: 清单文件路径，`.toml` 按 TOML 解析，其余按 JSON 解析
+///
+/// # 返回
+/// 清单中的游戏列表
+pub fn read_manifest<P: AsRef<Path>>(path: P) -> Result<Vec<ManifestEntry>, Box<dyn std::error::Error + Send + Sync>> {
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path)?;
+
+    let is_toml = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+    let manifest: Manifest = if is_toml {
+        toml::from_str(&content).map_err(|e| format!("无法解析清单 {}: {}", path.display(), e))?
+    } else {
+        serde_json::from_str(&content).map_err(|e| format!("无法解析清单 {}: {}", path.display(), e))?
+    };
+
+    Ok(manifest.games)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_toml_and_json_manifest() {
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        let toml_path = dir.path().join("installed.toml");
+        std::fs::write(
+            &toml_path,
+            r#"
+[[games]]
+name = "Elden Ring"
+install_dir = "/games/ELDEN RING v1.10"
+launcher = "/games/ELDEN RING v1.10/Game/eldenring.exe"
+
+[[games]]
+name = "Celeste"
+install_dir = "/games/Celeste"
+"#,
+        )
+        .unwrap();
+        let json_path = dir.path().join("installed.json");
+        std::fs::write(
+            &json_path,
+            r#"{"games": [{"name": "Hades", "install_dir": "/games/Hades", "launcher": "x64/Hades.exe"}]}"#,
+        )
+        .unwrap();
+
+        let groups: Vec<_> = read_manifest(&toml_path).unwrap().iter().map(ManifestEntry::to_group).collect();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].search_key, "Elden Ring");
+        assert_eq!(groups[0].child_root_name, "ELDEN RING v1.10");
+        assert_eq!(groups[0].child_path, vec!["Game/eldenring.exe"]);
+        assert_eq!(groups[0].version.as_deref(), Some("1.10"));
+        assert!(groups[1].child_path.is_empty());
+
+        let groups: Vec<_> = read_manifest(&json_path).unwrap().iter().map(ManifestEntry::to_group).collect();
+        assert_eq!(groups[0].root_path, "/games/Hades");
+        assert_eq!(groups[0].child_path, vec!["x64/Hades.exe"]);
+
+        std::fs::write(&json_path, "not a manifest").unwrap();
+        assert!(read_manifest(&json_path).is_err());
+    }
+}
@@ -29,8 +29,13 @@ mod patterns;
 mod utils;
 mod game_grouping;
 mod scanner;
+mod shortcut;
+pub mod pe_version;
+pub mod sidecar;
+pub mod manifest;
 
 // 公共导出
 pub use scanner::{GameScanner, walk_path};
-pub use game_grouping::{PathGroupResult, DirEntryFilter, paths_group};
-pub use utils::{extract_version, extract_search_key, find_common_parent_dir, calculate_directory_size_async};
\ No newline at end of file
+pub use game_grouping::{PathGroupResult, DirEntryFilter, GroupingOptions, paths_group, paths_group_with_options};
+pub use shortcut::{parse_shortcut_target, read_shortcut_target, resolve_shortcut_launcher};
+pub use utils::{dedup_cover_urls, detect_engine, detect_platform, extract_circle_and_title, extract_dlsite_code, find_local_covers, fold_case, glob_to_regex, is_trial_title, DEFAULT_EXCLUDE_PATTERNS, DEFAULT_LOCAL_COVER_NAMES, normalize_company_field, parse_release_date, extract_version, extract_search_key, find_common_parent_dir, calculate_directory_size_async, rank_launchers, rank_launchers_in_dir, score_launcher, select_release_date, sort_by_locale, truncate_chars, validate_cover_urls};
\ No newline at end of file
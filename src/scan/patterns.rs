@@ -76,3 +76,108 @@ pub static SUFFIX_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
     ]
 });
 
+// ============================================================================
+// 同人社团名称提取正则
+// ============================================================================
+
+/// 同人作品分类前缀匹配正则（需要移除）
+///
+/// 匹配：`(同人ゲーム)`, `（同人ソフト）`, `(18禁ゲーム)`
+pub static CIRCLE_CATEGORY_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*[(（](?:同人ゲーム|同人ソフト|同人誌|18禁ゲーム)[)）]\s*").unwrap()
+});
+
+/// 前置社团名称匹配正则
+///
+/// 匹配：`[サークル名] 作品名`, `［サークル名 (作者名)］作品名`。捕获组依次为社团、剩余部分
+pub static CIRCLE_PREFIX_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[\[［]([^\]］]+)[\]］]\s*(.+)$").unwrap()
+});
+
+/// 后置社团名称匹配正则
+///
+/// 匹配：`作品名 (サークル名)`, `作品名（サークル名）`。捕获组依次为剩余部分、社团
+pub static CIRCLE_SUFFIX_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(.+?)\s*[(（]([^()（）]+)[)）]\s*$").unwrap()
+});
+
+/// 不是社团名称的括号内容匹配正则
+///
+/// 匹配作品编号（`RJ01014447`）、汉化/语言标签、版本号、年份、体验版和平台标识
+pub static NON_CIRCLE_TAG_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(?:RJ|VJ|BJ|RE)\d+$|汉化|漢化|官中|中文|日本語|english|体験版|trial|^v(?:er)?\.?\s*\d|^\d|版$").unwrap()
+});
+
+
+// ============================================================================
+// 分集目录识别正则
+// ============================================================================
+
+/// 分集/章节后缀匹配正则（默认规则）
+///
+/// 匹配：`GameName_Ep1`, `GameName Chapter 2`, `GameName - Part3`, `游戏名 第2章`。
+/// 匹配部分从分隔符开始，去掉后即为各分集共同的标题
+pub static EPISODE_SUFFIX_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
+    vec![
+        Regex::new(r"(?i)(?:[\s_.\-]+|\b)(?:ep|episode|ch|chapter|part|vol|act)[\s_.\-]*\d+\s*$").unwrap(),
+        Regex::new(r"[\s_.\-]*第\s*[0-9０-９一二三四五六七八九十]+\s*[话話章部卷幕]\s*$").unwrap(),
+    ]
+});
+
+
+// ============================================================================
+// NFO 附属文件解析正则
+// ============================================================================
+
+/// NFO/XML 叶子元素匹配正则
+///
+/// 匹配不包含子元素的标签：`<title>名称</title>`、`<thumb aspect="poster">URL</thumb>`、
+/// `<plot><![CDATA[简介]]></plot>`。捕获组依次为开始标签名、内容、结束标签名
+pub static NFO_TAG_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?s)<([A-Za-z_][\w.-]*)(?:\s[^>]*)?>((?:<!\[CDATA\[.*?\]\]>|[^<])*)</([A-Za-z_][\w.-]*)\s*>").unwrap()
+});
+
+
+// ============================================================================
+// 封面图片 URL 识别正则
+// ============================================================================
+
+/// IGDB 图片 URL 匹配正则
+///
+/// 匹配：`https://images.igdb.com/igdb/image/upload/t_cover_big/co1abc.jpg`、`.../t_1080p_2x/co1abc.png`。
+/// 捕获组依次为尺寸标记、图片 ID
+pub static IGDB_IMAGE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)images\.igdb\.com/igdb/image/upload/t_([a-z0-9_]+)/([a-z0-9]+)\.\w+$").unwrap()
+});
+
+/// 体验版/试玩版标记匹配正则
+///
+/// 匹配：`体験版`、`体验版`、`試遊版`（任意位置）、`[Demo]`、`(Trial Version)`（括号内）、
+/// `GameName Demo`、`GameName - Trial`、`GameName Trial ver.`（结尾）。
+/// 不匹配 `Demon's Souls`、`Demolition`、`Trials of Mana` 等只是包含这些字母的标题
+pub static TRIAL_MARKER_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(concat!(
+        r"(?i)体験版|体验版|試遊版",
+        r"|[\[(【（［]\s*(?:free\s+)?(?:demo|trial)(?:\s+ver(?:sion|\.)?)?\s*[\])】）］]",
+        r"|\bdemo(?:\s+ver(?:sion|\.)?)?\s*$",
+        r"|\btrial\s+ver(?:sion|\.)?\s*$|[-:~～]\s*trial\s*$",
+    ))
+    .unwrap()
+});
+
+/// DLsite 作品编号匹配正则
+///
+/// 匹配：`[RJ01014447] 游戏名称`、`VJ123456_游戏名称`、`游戏名称 (BJ01234567)`。
+/// 捕获组为作品编号
+pub static DLSITE_PRODUCT_CODE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:^|[^a-z0-9])([RVB]J\d{6,8})(?:$|[^a-z0-9])").unwrap()
+});
+
+/// DLsite 作品图片 URL 匹配正则
+///
+/// 匹配：`RJ01014447_img_main.jpg`（原图）、`RJ01014447_img_main_240x240.jpg`（缩略图）、
+/// `RJ01014447_img_sam.jpg`（小缩略图）、`RJ01014447_img_smp1.jpg`（样图）。
+/// 捕获组依次为作品编号、图片类型、缩略图宽度
+pub static DLSITE_IMAGE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)/([A-Z]{2}\d+)_img_(main|sam|smp\d+)(?:_(\d+)x\d+)?\.\w+$").unwrap()
+});
@@ -0,0 +1,416 @@
+//! 可执行文件（PE）版本资源解析
+//!
+//! 按照 PE/COFF 格式定位资源节中的 `RT_VERSION` 资源，读取 `VS_VERSIONINFO` 中的
+//! 产品名称和版本号。解析本身不依赖平台，只读取文件头和资源节，不会读取整个可执行文件。
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// 读取文件头时最多读取的字节数（DOS 头、PE 头和节表都在其中）
+const HEADER_READ_SIZE: u64 = 4096;
+
+/// 资源节最大读取大小，超过时放弃解析
+const MAX_RESOURCE_SECTION_SIZE: u32 = 64 * 1024 * 1024;
+
+/// 资源类型 RT_VERSION
+const RT_VERSION: u32 = 16;
+
+/// 资源目录项指向子目录的标记位
+const SUBDIRECTORY_FLAG: u32 = 0x8000_0000;
+
+/// VS_FIXEDFILEINFO 的签名
+const FIXED_FILE_INFO_SIGNATURE: u32 = 0xFEEF_04BD;
+
+/// 可执行文件的版本信息
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PeVersionInfo {
+    /// 产品名称（`ProductName`）
+    pub product_name: Option<String>,
+    /// 产品版本（`ProductVersion`）
+    pub product_version: Option<String>,
+    /// 文件版本（`FileVersion`）
+    pub file_version: Option<String>,
+}
+
+impl PeVersionInfo {
+    /// 游戏版本：优先使用产品版本，没有时使用文件版本
+    pub fn version(&self) -> Option<String> {
+        self.product_version.clone().or_else(|| self.file_version.clone())
+    }
+}
+
+/// 资源节的位置
+struct ResourceSection {
+    /// 资源目录的 RVA
+    directory_rva: u32,
+    /// 资源节的 RVA
+    virtual_address: u32,
+    /// 资源节在文件中的偏移
+    raw_offset: u32,
+    /// 资源节在文件中的大小
+    raw_size: u32,
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// 读取以 NUL 结尾（或到切片末尾）的 UTF-16LE 字符串，返回字符串和占用的字节数（含结尾的 NUL）
+fn read_unicode_z(data: &[u8], offset: usize) -> Option<(String, usize)> {
+    let bytes = data.get(offset..)?;
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|&u| u != 0)
+        .collect();
+    Some((String::from_utf16_lossy(&units), (units.len() + 1) * 2))
+}
+
+/// 向上对齐到 4 字节
+fn align4(offset: usize) -> usize {
+    (offset + 3) & !3
+}
+
+/// 从文件头中定位资源节
+fn locate_resources(headers: &[u8]) -> Option<ResourceSection> {
+    if headers.get(0..2)? != b"MZ" {
+        return None;
+    }
+    let pe = read_u32(headers, 0x3C)? as usize;
+    if headers.get(pe..pe + 4)? != b"PE\0\0" {
+        return None;
+    }
+
+    let coff = pe + 4;
+    let section_count = read_u16(headers, coff + 2)? as usize;
+    let optional_size = read_u16(headers, coff + 16)? as usize;
+    let optional = coff + 20;
+
+    // PE32 与 PE32+ 的数据目录位置不同
+    let (count_offset, directories) = match read_u16(headers, optional)? {
+        0x10B => (optional + 92, optional + 96),
+        0x20B => (optional + 108, optional + 112),
+        _ => return None,
+    };
+    if read_u32(headers, count_offset)? <= 2 {
+        return None;
+    }
+    let directory_rva = read_u32(headers, directories + 2 * 8)?;
+    if directory_rva == 0 {
+        return None;
+    }
+
+    let sections = optional + optional_size;
+    (0..section_count).find_map(|i| {
+        let section = sections + i * 40;
+        let virtual_size = read_u32(headers, section + 8)?;
+        let virtual_address = read_u32(headers, section + 12)?;
+        let raw_size = read_u32(headers, section + 16)?;
+        let raw_offset = read_u32(headers, section + 20)?;
+        let end = virtual_address.checked_add(virtual_size.max(raw_size))?;
+        (virtual_address..end).contains(&directory_rva).then_some(ResourceSection {
+            directory_rva,
+            virtual_address,
+            raw_offset,
+            raw_size,
+        })
+    })
+}
+
+/// 在资源目录中查找指定 ID 的目录项（`None` 表示取第一个目录项），返回其偏移字段
+fn find_directory_entry(section: &[u8], directory: usize, id: Option<u32>) -> Option<u32> {
+    let named = read_u16(section, directory + 12)? as usize;
+    let ids = read_u16(section, directory + 14)? as usize;
+    (0..named + ids).find_map(|i| {
+        let entry = directory + 16 + i * 8;
+        let name = read_u32(section, entry)?;
+        let matches = match id {
+            Some(id) => name & SUBDIRECTORY_FLAG == 0 && name == id,
+            None => true,
+        };
+        matches.then(|| read_u32(section, entry + 4)).flatten()
+    })
+}
+
+/// 从资源节中取出 RT_VERSION 资源的数据
+fn version_resource<'a>(section: &'a [u8], resources: &ResourceSection) -> Option<&'a [u8]> {
+    let root = resources.directory_rva.checked_sub(resources.virtual_address)? as usize;
+
+    // 三级目录：资源类型 -> 资源名称 -> 语言
+    let mut offset = find_directory_entry(section, root, Some(RT_VERSION))?;
+    for _ in 0..2 {
+        if offset & SUBDIRECTORY_FLAG == 0 {
+            return None;
+        }
+        offset = find_directory_entry(section, root + (offset & !SUBDIRECTORY_FLAG) as usize, None)?;
+    }
+    if offset & SUBDIRECTORY_FLAG != 0 {
+        return None;
+    }
+
+    let data_entry = root + offset as usize;
+    let data_rva = read_u32(section, data_entry)?;
+    let data_size = read_u32(section, data_entry + 4)? as usize;
+    let start = data_rva.checked_sub(resources.virtual_address)? as usize;
+    section.get(start..start + data_size)
+}
+
+/// 版本资源中的一个块（`VS_VERSIONINFO`、`StringFileInfo`、`StringTable`、`String`）
+struct VersionBlock {
+    key: String,
+    /// 值的起始偏移
+    value: usize,
+    /// 值的长度（文本值为字符数，二进制值为字节数）
+    value_length: usize,
+    /// 子块的起始偏移
+    children: usize,
+    /// 块的结束偏移
+    end: usize,
+}
+
+/// 解析指定偏移处的版本资源块
+fn parse_block(data: &[u8], offset: usize) -> Option<VersionBlock> {
+    let length = read_u16(data, offset)? as usize;
+    let value_length = read_u16(data, offset + 2)? as usize;
+    let is_text = read_u16(data, offset + 4)? == 1;
+    let end = (offset + length).min(data.len());
+    if length < 6 {
+        return None;
+    }
+
+    let (key, key_size) = read_unicode_z(data.get(..end)?, offset + 6)?;
+    let value = align4(offset + 6 + key_size);
+    let value_size = if is_text { value_length * 2 } else { value_length };
+
+    Some(VersionBlock {
+        key,
+        value,
+        value_length,
+        children: align4(value + value_size),
+        end,
+    })
+}
+
+/// 依次解析一个块的所有子块
+fn child_blocks(data: &[u8], parent: &VersionBlock) -> Vec<VersionBlock> {
+    let mut blocks = Vec::new();
+    let mut offset = parent.children;
+    while offset < parent.end {
+        let Some(block) = parse_block(data, offset) else {
+            break;
+        };
+        offset = align4(block.end);
+        blocks.push(block);
+    }
+    blocks
+}
+
+/// 将版本号中的逗号分隔（`1, 0, 2, 0`）统一为点分隔
+fn normalize_version(version: &str) -> Option<String> {
+    let version = version
+        .split([',', '.'])
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(".");
+    (!version.is_empty()).then_some(version)
+}
+
+/// 解析 `VS_VERSIONINFO` 资源
+fn parse_version_info(data: &[u8]) -> Option<PeVersionInfo> {
+    let root = parse_block(data, 0)?;
+    if root.key != "VS_VERSION_INFO" {
+        return None;
+    }
+
+    let mut info = PeVersionInfo::default();
+    for string_file_info in child_blocks(data, &root).iter().filter(|b| b.key == "StringFileInfo") {
+        for table in child_blocks(data, string_file_info) {
+            for string in child_blocks(data, &table) {
+                let value = data
+                    .get(string.value..(string.value + string.value_length * 2).min(string.end))
+                    .and_then(|bytes| read_unicode_z(bytes, 0))
+                    .map(|(value, _)| value.trim().to_string())
+                    .filter(|value| !value.is_empty());
+                let field = match string.key.as_str() {
+                    "ProductName" => &mut info.product_name,
+                    "ProductVersion" => &mut info.product_version,
+                    "FileVersion" => &mut info.file_version,
+                    _ => continue,
+                };
+                if field.is_none() {
+                    *field = value;
+                }
+            }
+        }
+    }
+    info.product_version = info.product_version.as_deref().and_then(normalize_version);
+    info.file_version = info.file_version.as_deref().and_then(normalize_version);
+
+    // 没有字符串表时使用 VS_FIXEDFILEINFO 中的数字版本
+    if info.product_version.is_none() && info.file_version.is_none() && root.value_length >= 24 {
+        let fixed = root.value;
+        if read_u32(data, fixed)? == FIXED_FILE_INFO_SIGNATURE {
+            let ms = read_u32(data, fixed + 16)?;
+            let ls = read_u32(data, fixed + 20)?;
+            if ms != 0 || ls != 0 {
+                info.product_version = Some(format!("{}.{}.{}.{}", ms >> 16, ms & 0xFFFF, ls >> 16, ls & 0xFFFF));
+            }
+        }
+    }
+
+    Some(info)
+}
+
+/// 解析可执行文件数据中的版本信息
+///
+/// # 参数
+/// - `data`: 可执行文件的完整内容
+///
+/// # 返回
+/// 版本信息；不是有效的 PE 文件或没有版本资源时返回 `None`
+pub fn parse_pe_version(data: &[u8]) -> Option<PeVersionInfo> {
+    let resources = locate_resources(data)?;
+    let start = resources.raw_offset as usize;
+    let section = data.get(start..start + resources.raw_size as usize)?;
+    parse_version_info(version_resource(section, &resources)?)
+}
+
+/// 读取可执行文件的版本信息
+///
+/// 只读取文件头和资源节，适用于体积很大的游戏可执行文件
+///
+/// # 参数
+/// - `path`: 可执行文件路径
+///
+/// # 返回
+/// 版本信息；文件无法读取、不是有效的 PE 文件或没有版本资源时返回 `None`
+pub fn read_pe_version<P: AsRef<Path>>(path: P) -> Option<PeVersionInfo> {
+    let mut file = File::open(path).ok()?;
+    let mut headers = Vec::new();
+    (&mut file).take(HEADER_READ_SIZE).read_to_end(&mut headers).ok()?;
+    let resources = locate_resources(&headers)?;
+    if resources.raw_size > MAX_RESOURCE_SECTION_SIZE {
+        return None;
+    }
+
+    let mut section = vec![0u8; resources.raw_size as usize];
+    file.seek(SeekFrom::Start(resources.raw_offset as u64)).ok()?;
+    file.read_exact(&mut section).ok()?;
+    parse_version_info(version_resource(&section, &resources)?)
+}
+
+/// 构造只包含版本资源的最小 PE 文件，供测试使用
+#[cfg(test)]
+pub(crate) fn version_fixture(strings: &[(&str, &str)], fixed_version: [u16; 4]) -> Vec<u8> {
+    fn utf16z(s: &str) -> Vec<u8> {
+        s.encode_utf16().chain([0]).flat_map(u16::to_le_bytes).collect()
+    }
+
+    fn pad4(data: &mut Vec<u8>) {
+        data.resize(align4(data.len()), 0);
+    }
+
+    fn block(key: &str, value: &[u8], value_length: u16, is_text: bool, children: &[Vec<u8>]) -> Vec<u8> {
+        let mut data = vec![0u8; 6];
+        data.extend(utf16z(key));
+        pad4(&mut data);
+        data.extend_from_slice(value);
+        for child in children {
+            pad4(&mut data);
+            data.extend_from_slice(child);
+        }
+        let length = data.len() as u16;
+        data[0..2].copy_from_slice(&length.to_le_bytes());
+        data[2..4].copy_from_slice(&value_length.to_le_bytes());
+        data[4..6].copy_from_slice(&(is_text as u16).to_le_bytes());
+        data
+    }
+
+    let strings: Vec<Vec<u8>> = strings
+        .iter()
+        .map(|(key, value)| block(key, &utf16z(value), value.encode_utf16().count() as u16 + 1, true, &[]))
+        .collect();
+    let table = block("040904b0", &[], 0, true, &strings);
+    let string_file_info = block("StringFileInfo", &[], 0, true, &[table]);
+
+    let [a, b, c, d] = fixed_version.map(u32::from);
+    let mut fixed = Vec::new();
+    for value in [FIXED_FILE_INFO_SIGNATURE, 0x0001_0000, a << 16 | b, c << 16 | d, a << 16 | b, c << 16 | d] {
+        fixed.extend_from_slice(&value.to_le_bytes());
+    }
+    fixed.resize(52, 0);
+    let version = block("VS_VERSION_INFO", &fixed, 52, false, &[string_file_info]);
+
+    // 资源节：类型目录 -> 名称目录 -> 语言目录 -> 数据项 -> 版本数据
+    const SECTION_RVA: u32 = 0x1000;
+    const SECTION_OFFSET: usize = 0x200;
+    let mut section = Vec::new();
+    for (id, offset) in [(RT_VERSION, SUBDIRECTORY_FLAG | 0x18), (1, SUBDIRECTORY_FLAG | 0x30), (0x409, 0x48)] {
+        section.extend_from_slice(&[0u8; 14]);
+        section.extend_from_slice(&1u16.to_le_bytes());
+        section.extend_from_slice(&id.to_le_bytes());
+        section.extend_from_slice(&offset.to_le_bytes());
+    }
+    for value in [SECTION_RVA + 0x58, version.len() as u32, 0, 0] {
+        section.extend_from_slice(&value.to_le_bytes());
+    }
+    section.extend(version);
+
+    let mut data = vec![0u8; SECTION_OFFSET];
+    data[0..2].copy_from_slice(b"MZ");
+    data[0x3C..0x40].copy_from_slice(&0x40u32.to_le_bytes());
+    data[0x40..0x44].copy_from_slice(b"PE\0\0");
+    // COFF 头：1 个节，PE32 可选头 224 字节
+    data[0x44..0x46].copy_from_slice(&0x14Cu16.to_le_bytes());
+    data[0x46..0x48].copy_from_slice(&1u16.to_le_bytes());
+    data[0x54..0x56].copy_from_slice(&224u16.to_le_bytes());
+    let optional = 0x58;
+    data[optional..optional + 2].copy_from_slice(&0x10Bu16.to_le_bytes());
+    data[optional + 92..optional + 96].copy_from_slice(&16u32.to_le_bytes());
+    data[optional + 112..optional + 116].copy_from_slice(&SECTION_RVA.to_le_bytes());
+    data[optional + 116..optional + 120].copy_from_slice(&(section.len() as u32).to_le_bytes());
+    // 节表
+    let header = optional + 224;
+    data[header..header + 5].copy_from_slice(b".rsrc");
+    for (offset, value) in [(8, section.len() as u32), (12, SECTION_RVA), (16, section.len() as u32), (20, SECTION_OFFSET as u32)] {
+        data[header + offset..header + offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+    data.extend(section);
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pe_version_strings_and_fixed_info() {
+        let data = version_fixture(
+            &[("ProductName", "Sample Quest"), ("FileVersion", "1, 2, 3, 0"), ("ProductVersion", "1.2.3")],
+            [1, 2, 3, 0],
+        );
+        let info = parse_pe_version(&data).unwrap();
+        assert_eq!(info.product_name.as_deref(), Some("Sample Quest"));
+        assert_eq!(info.file_version.as_deref(), Some("1.2.3.0"));
+        assert_eq!(info.version().as_deref(), Some("1.2.3"));
+
+        // 没有字符串表时使用数字版本
+        let info = parse_pe_version(&version_fixture(&[], [2, 0, 1, 7])).unwrap();
+        assert_eq!(info.product_name, None);
+        assert_eq!(info.version().as_deref(), Some("2.0.1.7"));
+
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        let path = dir.path().join("game.exe");
+        std::fs::write(&path, &data).unwrap();
+        assert_eq!(read_pe_version(&path), Some(parse_pe_version(&data).unwrap()));
+
+        assert_eq!(parse_pe_version(b"MZ not a real executable"), None);
+        assert_eq!(parse_pe_version(&data[..0x100]), None);
+    }
+}
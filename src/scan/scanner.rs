@@ -2,18 +2,34 @@
 //!
 //! 该模块提供了 `GameScanner` 结构体，用于扫描本地游戏文件并通过游戏数据库提供者获取元数据。
 
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
 use chrono::Utc;
-use ignore::{DirEntry, Walk, WalkBuilder};
+use ignore::WalkBuilder;
 
 use crate::logger::{get_logger, LogEvent, LogLevel, ScanProgress};
 use crate::models::game_info::GameInfo;
-use crate::providers::GameDatabaseMiddleware;
-use crate::scan::game_grouping::{paths_group, PathGroupResult};
-use crate::scan::utils::calculate_directory_size_async;
+use crate::providers::cache::PersistentCache;
+use crate::models::game_meta_data::{FieldSet, GameMetadata};
+use crate::providers::{GameDatabaseMiddleware, GameQueryResult, SearchParams};
+use crate::scan::manifest::{read_manifest, ManifestEntry};
+use crate::scan::pe_version::{read_pe_version, PeVersionInfo};
+use crate::scan::sidecar::{read_sidecar, SIDECAR_CONFIDENCE, SIDECAR_SOURCE};
+use crate::scan::game_grouping::{paths_group_with_options, GroupingOptions, PathGroupResult};
+use crate::scan::utils::{
+    calculate_directory_size_async, contains_cjk, dedup_cover_urls, detect_engine, detect_platform, extract_circle_and_title, extract_dlsite_code,
+    find_local_covers, glob_to_regex, has_executable_bit, normalize_company_field, parse_release_date, rank_launchers_in_dir, search_key_fallbacks, select_release_date, sort_by_locale, truncate_chars, validate_cover_urls,
+};
+use crate::traits::QueryResultFilter;
+
+/// 弱匹配阈值：最佳结果的置信度低于该值时，尝试使用别名再次搜索
+const WEAK_MATCH_THRESHOLD: f32 = 0.7;
+
+/// DLsite 提供者名称（与 `DLsiteProvider::name` 一致），目录名包含作品编号时直接按编号获取
+const DLSITE_PROVIDER: &str = "DLsite";
 
 /// 游戏扫描器
 ///
@@ -37,86 +53,885 @@ use crate::scan::utils::calculate_directory_size_async;
 pub struct GameScanner {
     /// 游戏数据库中间件
     middleware: GameDatabaseMiddleware,
+    /// 单次搜索使用的搜索参数
+    search_params: SearchParams,
+    /// 别名表：目录名 -> 原语言标题（如汉化游戏对应的日文原名）
+    alias_map: HashMap<String, String>,
+    /// 路径分组选项
+    grouping_options: GroupingOptions,
+    /// 发行年份范围（最早, 最晚），`None` 表示不过滤
+    year_range: Option<(Option<i32>, Option<i32>)>,
+    /// 按年份过滤时是否保留没有日期的结果
+    keep_undated: bool,
+    /// 视为启动程序的文件扩展名（小写，不含点），为空表示任意文件
+    extensions: Vec<String>,
+    /// 排除的文件名模式，匹配的文件不作为启动程序收集
+    exclude_patterns: Vec<regex::Regex>,
+    /// 启动程序的最小文件大小（字节），小于该值的文件不收集，0 表示不限制
+    min_exe_size: u64,
+    /// 是否解析 .lnk 快捷方式（仅 Windows 生效）
+    resolve_shortcuts: bool,
+    /// 是否将设置了可执行权限位的文件视为启动程序（仅 Unix 生效）
+    detect_executable_bit: bool,
+    /// 是否读取默认启动程序的 PE 版本资源补充版本号和标题
+    pe_metadata: bool,
+    /// 固定匹配表：目录名 -> (提供者名称, 提供者中的游戏 ID)
+    pins: HashMap<String, (String, String)>,
+    /// 是否在构建 GameInfo 前检查封面 URL 是否可访问
+    cover_validation: bool,
+    /// 离线模式：只使用本地数据源，不访问网络
+    offline: bool,
+    /// 封面检查使用的 HTTP 客户端
+    http_client: reqwest::Client,
+    /// 严格模式：提供者出错时中止扫描
+    strict_mode: bool,
+    /// 扫描结果按标题排序使用的语言区域（`None` 表示保持分组顺序）
+    sort_locale: Option<String>,
+    /// 首选的发布地区（`None` 表示使用最早的地区发布日期）
+    preferred_region: Option<String>,
+    /// 是否读取游戏目录中的 NFO/XML 附属元数据文件
+    sidecar_metadata: bool,
+    /// 是否按原始目录名、去除前缀、完全清理的顺序尝试多个搜索关键词
+    search_key_fallbacks: bool,
+    /// 最多处理的游戏数量（`None` 表示不限制）
+    max_games: Option<usize>,
+    /// 识别为本地封面的文件名（`None` 表示不查找本地封面）
+    local_cover_names: Option<Vec<String>>,
+    /// 持久化缓存（`None` 表示不将缓存保存到磁盘），扫描器释放时写回缓存文件
+    persistent_cache: Option<PersistentCache>,
 }
 
-impl GameScanner {
-    /// 创建新的游戏扫描器
+impl GameScanner {
+    /// 创建新的游戏扫描器
+    ///
+    /// # 返回
+    /// 新的 `GameScanner` 实例
+    pub fn new() -> Self {
+        GameScanner {
+            middleware: GameDatabaseMiddleware::new(),
+            search_params: SearchParams::new(),
+            alias_map: HashMap::new(),
+            grouping_options: GroupingOptions::default(),
+            year_range: None,
+            keep_undated: true,
+            extensions: vec!["exe".to_string()],
+            exclude_patterns: crate::scan::utils::DEFAULT_EXCLUDE_PATTERNS
+                .iter()
+                .map(|pattern| glob_to_regex(pattern))
+                .collect(),
+            min_exe_size: 0,
+            resolve_shortcuts: false,
+            detect_executable_bit: false,
+            pe_metadata: false,
+            pins: HashMap::new(),
+            cover_validation: false,
+            offline: false,
+            http_client: reqwest::Client::new(),
+            strict_mode: false,
+            sort_locale: None,
+            preferred_region: None,
+            sidecar_metadata: true,
+            search_key_fallbacks: false,
+            max_games: None,
+            local_cover_names: None,
+            persistent_cache: None,
+        }
+    }
+
+    /// 创建离线模式的游戏扫描器
+    ///
+    /// 等同于 `GameScanner::new().with_offline(true)`
+    pub fn offline() -> Self {
+        Self::new().with_offline(true)
+    }
+
+    /// 注册 DLsite 提供者（链式调用）
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub async fn with_dlsite_provider(self) -> Self {
+        use crate::providers::dlsite_provider::DLsiteProvider;
+        self.middleware
+            .register_provider(Arc::new(DLsiteProvider::new()))
+            .await;
+        self
+    }
+
+    /// 注册 IGDB 提供者（链式调用）
+    ///
+    /// # 参数
+    /// - `client_id`: IGDB API 客户端 ID
+    /// - `client_secret`: IGDB API 客户端密钥
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub async fn with_igdb_provider(self, client_id: String, client_secret: String) -> Self {
+        use crate::providers::igdb_provider::IGDBProvider;
+        self.middleware
+            .register_provider(Arc::new(IGDBProvider::with_client(
+                client_id,
+                client_secret,
+                self.middleware.http_client(),
+            )))
+            .await;
+        self
+    }
+
+    /// 注册 TheGamesDB 提供者（链式调用）
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub async fn with_thegamesdb_provider(self) -> Self {
+        use crate::providers::thegamesdb_provider::TheGamesDBProvider;
+        self.middleware
+            .register_provider(Arc::new(TheGamesDBProvider::new()))
+            .await;
+        self
+    }
+
+    /// 注册本地附属文件提供者（链式调用）
+    ///
+    /// 读取游戏目录中的 `gamebox.json` / `metadata.json`，命中时覆盖所有刮削结果
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub async fn with_sidecar_provider(self) -> Self {
+        use crate::providers::sidecar_provider::SidecarProvider;
+        self.middleware
+            .register_provider(Arc::new(SidecarProvider::new()))
+            .await;
+        self
+    }
+
+    /// 注册自定义提供者（链式调用）
+    ///
+    /// # 参数
+    /// - `provider`: 实现了 `GameDatabaseProvider` trait 的提供者
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub async fn with_provider(
+        self,
+        provider: Arc<dyn crate::providers::GameDatabaseProvider>,
+    ) -> Self {
+        self.middleware.register_provider(provider).await;
+        self
+    }
+
+    /// 设置 HTTP 客户端（链式调用）
+    ///
+    /// 用于配置代理、TLS 根证书和超时。之后通过 `with_igdb_provider` 注册的提供者
+    /// 和封面检查都使用该客户端，共用连接池
+    ///
+    /// # 参数
+    /// - `client`: HTTP 客户端
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = client.clone();
+        self.middleware = self.middleware.with_http_client(client);
+        self
+    }
+
+    /// 设置 HTTP 连接超时和总超时（链式调用）
+    ///
+    /// 使用指定的超时时间创建新的 HTTP 客户端，替换扫描器和中间件的共享客户端。
+    /// 无法在连接超时内建立连接的提供者以 [`GameboxError::ConnectTimeout`](crate::error::GameboxError::ConnectTimeout)
+    /// 报告错误。需要在 `with_igdb_provider` 之前调用才会应用到该提供者
+    ///
+    /// # 参数
+    /// - `connect_timeout`: 连接超时时间
+    /// - `timeout`: 单个请求的总超时时间
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_http_timeouts(mut self, connect_timeout: std::time::Duration, timeout: std::time::Duration) -> Self {
+        self.middleware = self.middleware.with_http_timeouts(connect_timeout, timeout);
+        self.http_client = self.middleware.http_client();
+        self
+    }
+
+    /// 以指定的实例名称注册自定义提供者（链式调用）
+    ///
+    /// 用于注册同一类型提供者的多个实例（如指向不同数据库的两个 SQLite 提供者）
+    ///
+    /// # 参数
+    /// - `name`: 实例名称
+    /// - `provider`: 实现了 `GameDatabaseProvider` trait 的提供者
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub async fn with_provider_as(
+        self,
+        name: impl Into<String>,
+        provider: Arc<dyn crate::providers::GameDatabaseProvider>,
+    ) -> Self {
+        self.middleware.register_provider_as(name, provider).await;
+        self
+    }
+
+    /// 设置开发商/发行商提示（链式调用）
+    ///
+    /// 仅作用于 [`search`](Self::search)：开发商或发行商与提示模糊匹配的结果会获得置信度加成，
+    /// 用于区分同名的不同游戏。
+    ///
+    /// # 参数
+    /// - `hint`: 开发商或发行商名称
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_developer_hint(mut self, hint: String) -> Self {
+        self.search_params = self.search_params.with_developer_hint(hint);
+        self
+    }
+
+    /// 设置别名表（链式调用）
+    ///
+    /// 汉化游戏的中文标题在 DLsite 等站点上往往搜不到，而原语言（日文）标题可以精确匹配。
+    /// 扫描时如果某个中文标题的搜索结果较弱，且别名表中存在该目录名，
+    /// 会额外使用别名搜索一次并合并两次的结果。
+    ///
+    /// # 参数
+    /// - `aliases`: 目录名（`PathGroupResult.child_root_name`）到原语言标题的映射
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_alias_map(mut self, aliases: HashMap<String, String>) -> Self {
+        self.alias_map = aliases;
+        self
+    }
+
+    /// 设置固定匹配表（链式调用）
+    ///
+    /// 表中的游戏不再进行模糊搜索，而是通过指定提供者的 `get_by_id` 直接获取固定的结果，
+    /// 保证重新扫描时用户选定的匹配不会改变。获取失败时回退到正常搜索。
+    ///
+    /// # 参数
+    /// - `pins`: 目录名（`PathGroupResult.child_root_name`）到 (提供者名称, 游戏 ID) 的映射
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_pins(mut self, pins: HashMap<String, (String, String)>) -> Self {
+        self.pins.extend(pins);
+        self
+    }
+
+    /// 沿用上次扫描结果中的固定匹配（链式调用）
+    ///
+    /// 收集 `pinned_match` 不为空的游戏，以其本地目录名（`sub_title`）作为键加入固定匹配表
+    ///
+    /// # 参数
+    /// - `previous`: 上次扫描的结果
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_pins_from(mut self, previous: &[GameInfo]) -> Self {
+        for game in previous {
+            if let Some(pin) = &game.pinned_match {
+                self.pins.insert(game.sub_title.clone(), pin.clone());
+            }
+        }
+        self
+    }
+
+    /// 设置单个第一级目录最多拆分出的游戏数量（链式调用）
+    ///
+    /// 当扫描目录中存在 `发行商/游戏1`、`发行商/游戏2` 这类发行商目录包装多个游戏的结构时，
+    /// 默认会把整个发行商目录识别为一个游戏。设置为大于 1 的值后，
+    /// 没有直接放置可执行文件、且其下有多个各自包含可执行文件的子目录的第一级目录会被拆分。
+    ///
+    /// # 参数
+    /// - `max_games`: 拆分上限，默认 1（不拆分）
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_max_games_per_first_level(mut self, max_games: usize) -> Self {
+        self.grouping_options.max_games_per_first_level = max_games;
+        self
+    }
+
+    /// 设置是否合并分集目录（链式调用）
+    ///
+    /// 启用后 `GameName_Ep1`、`GameName_Ep2` 这类位于同一目录下、仅分集后缀不同的目录
+    /// 合并为一个游戏，各分集的可执行文件都作为启动项
+    ///
+    /// # 参数
+    /// - `enabled`: 是否启用，默认 `false`
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_episode_grouping(mut self, enabled: bool) -> Self {
+        self.grouping_options.episode_grouping = enabled;
+        self
+    }
+
+    /// 设置识别分集目录使用的后缀正则（链式调用）
+    ///
+    /// # 参数
+    /// - `patterns`: 匹配目录名末尾分集/章节标识的正则，匹配部分去掉后即为共同标题
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_episode_patterns(mut self, patterns: Vec<regex::Regex>) -> Self {
+        self.grouping_options.episode_patterns = patterns;
+        self
+    }
+
+    /// 设置是否确定性输出（链式调用）
+    ///
+    /// 并行遍历和分组使用的哈希表会使同一个未改变的游戏库在两次扫描中得到不同的顺序。
+    /// 启用后扫描到的路径在分组前排序，分组按目录名有序进行，结果排序以游戏根目录打破平局，
+    /// 使输出在多次运行之间可以直接比较（适用于测试和版本控制）。
+    ///
+    /// # 参数
+    /// - `deterministic`: 是否确定性输出，默认 `false`
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_deterministic_output(mut self, deterministic: bool) -> Self {
+        self.grouping_options.deterministic = deterministic;
+        self
+    }
+
+    /// 设置最多处理的游戏数量（链式调用）
+    ///
+    /// 达到上限后停止处理剩余的分组，返回已构建完成的游戏并记录警告。
+    /// 适用于在大型游戏库上快速预览扫描配置的效果
+    ///
+    /// # 参数
+    /// - `max_games`: 游戏数量上限
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_max_games(mut self, max_games: usize) -> Self {
+        self.max_games = Some(max_games);
+        self
+    }
+
+    /// 设置是否尝试多个逐步清理的搜索关键词（链式调用）
+    ///
+    /// 完全清理后的关键词有时会丢失关键信息（如续作编号被去除）。启用后依次尝试
+    /// 原始目录名、去除前缀标签后的名称和完全清理后的关键词，使用第一个得到可信匹配的结果；
+    /// 都没有可信匹配时使用完全清理后的关键词的结果。会增加 API 请求数量
+    ///
+    /// # 参数
+    /// - `enabled`: 是否启用，默认 `false`
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_search_key_fallbacks(mut self, enabled: bool) -> Self {
+        self.search_key_fallbacks = enabled;
+        self
+    }
+
+    /// 设置是否并行处理路径分组（链式调用）
+    ///
+    /// 可执行文件数以万计的游戏库中，分组阶段计算公共父目录会占用大量 CPU。
+    /// 启用后各第一级目录的分组并行处理，结果与串行处理相同。需要启用 `rayon` feature，否则该设置被忽略。
+    ///
+    /// # 参数
+    /// - `parallel`: 是否并行分组，默认 `false`
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_parallel_grouping(mut self, parallel: bool) -> Self {
+        self.grouping_options.parallel = parallel;
+        self
+    }
+
+    /// 设置层级置信度下限（链式调用）
+    ///
+    /// 主要层级提供者的结果置信度达到该值时，不再查询次要和兜底层级的提供者
+    ///
+    /// # 参数
+    /// - `floor`: 置信度下限（0.0 ~ 1.0），默认 0.7
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_tier_confidence_floor(mut self, floor: f32) -> Self {
+        self.middleware = self.middleware.with_tier_confidence_floor(floor);
+        self
+    }
+
+    /// 设置单次搜索同时查询的提供者数量上限（链式调用）
+    ///
+    /// 与全局的 API 速率限制相互独立，用于避免同一时间访问过多不同的数据源
+    ///
+    /// # 参数
+    /// - `fanout`: 同时查询的提供者数量，默认不限制
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_provider_fanout(mut self, fanout: usize) -> Self {
+        self.middleware = self.middleware.with_provider_fanout(fanout);
+        self
+    }
+
+    /// 启用提供者熔断器（链式调用）
+    ///
+    /// 某个提供者连续失败后在冷却期内直接跳过，避免数据源不可用时每个游戏都等待超时
+    ///
+    /// # 参数
+    /// - `config`: 熔断器配置（失败阈值、统计窗口、冷却时间）
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_circuit_breaker(mut self, config: crate::providers::circuit_breaker::CircuitConfig) -> Self {
+        self.middleware = self.middleware.with_circuit_breaker(config);
+        self
+    }
+
+    /// 设置查询缓存有效期（链式调用）
+    ///
+    /// # 参数
+    /// - `ttl`: 有效期，默认 1 小时，设置为 0 时不使用缓存
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_cache_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.middleware = self.middleware.with_cache_ttl(ttl);
+        // 已启用持久化缓存时，保存时同样按新的有效期过滤
+        if let Some(persistent_cache) = &mut self.persistent_cache {
+            persistent_cache.ttl = ttl;
+        }
+        self
+    }
+
+    /// 在多次运行之间持久化查询缓存（链式调用）
+    ///
+    /// 立即从缓存文件加载未过期的条目（文件不存在时从空缓存开始），
+    /// 扫描器释放时将缓存写回该文件，也可以通过 [`flush_cache`](Self::flush_cache) 随时写入。
+    /// 加载时按当前的缓存有效期跳过过期条目（因此应在 `with_cache_ttl` 之后调用），
+    /// 保存时按扫描器最终的有效期跳过过期条目
+    ///
+    /// # 参数
+    /// - `path`: 缓存文件路径
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub async fn with_persistent_cache(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        if path.exists() {
+            if let Err(e) = self.middleware.load_cache(&path).await {
+                get_logger().log(
+                    &LogEvent::new(LogLevel::Warning, "无法加载缓存")
+                        .with_details(format!("{}: {}", path.display(), e)),
+                );
+            }
+        }
+        self.persistent_cache = Some(self.middleware.persistent_cache(path));
+        self
+    }
+
+    /// 立即将查询缓存写入持久化缓存文件
+    ///
+    /// # 返回
+    /// 写入的条目数量；未通过 `with_persistent_cache` 设置缓存文件时返回 0
+    pub async fn flush_cache(&self) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        match &self.persistent_cache {
+            Some(persistent) => self.middleware.save_cache(&persistent.path).await,
+            None => Ok(0),
+        }
+    }
+
+    /// 设置最多同时进行的 API 请求数量（链式调用）
+    ///
+    /// # 参数
+    /// - `max_concurrency`: 并发请求上限，默认 5，传入 0 时按 1 处理
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_rate_limit(mut self, max_concurrency: usize) -> Self {
+        self.middleware = self.middleware.with_max_concurrency(max_concurrency);
+        self
+    }
+
+    /// 设置封面偏好权重（链式调用）
+    ///
+    /// 没有封面的搜索结果置信度扣减该值，置信度相近时优先选用带封面的结果
+    ///
+    /// # 参数
+    /// - `weight`: 扣减的置信度，默认 0（不生效）
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_cover_preference(mut self, weight: f32) -> Self {
+        self.middleware = self.middleware.with_cover_preference(weight);
+        self
+    }
+
+    /// 设置整个扫描共享的重试预算（链式调用）
+    ///
+    /// 提供者查询失败时最多重试 2 次，所有分组和提供者的重试共同消耗该预算，
+    /// 耗尽后不再重试并记录日志，避免网络不稳定时大规模扫描产生失控的 API 请求。
+    ///
+    /// # 参数
+    /// - `budget`: 允许的重试总次数
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_total_retry_budget(mut self, budget: u32) -> Self {
+        self.middleware = self.middleware.with_total_retry_budget(budget);
+        self
+    }
+
+    /// 设置发行年份范围（链式调用）
+    ///
+    /// 扫描和搜索时会丢弃发行年份不在范围内的结果，用于建立特定年代的游戏合集
+    ///
+    /// # 参数
+    /// - `min`: 最早年份（包含），`None` 表示不限制
+    /// - `max`: 最晚年份（包含），`None` 表示不限制
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_year_range(mut self, min: Option<i32>, max: Option<i32>) -> Self {
+        self.year_range = Some((min, max));
+        self
+    }
+
+    /// 设置按年份过滤时是否保留没有日期的结果（链式调用）
+    ///
+    /// # 参数
+    /// - `keep`: 是否保留缺少日期或日期无法解析的结果，默认 `true`
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_keep_undated(mut self, keep: bool) -> Self {
+        self.keep_undated = keep;
+        self
+    }
+
+    /// 设置是否解析 .lnk 快捷方式（链式调用）
+    ///
+    /// 启用后，只包含指向其他位置可执行文件的快捷方式的目录也会被识别为游戏，
+    /// 快捷方式会被替换为其目标路径加入启动项。仅在 Windows 上生效，其他平台忽略该设置。
+    ///
+    /// # 参数
+    /// - `resolve`: 是否解析快捷方式，默认 `false`
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_resolve_shortcuts(mut self, resolve: bool) -> Self {
+        self.resolve_shortcuts = resolve;
+        self
+    }
+
+    /// 设置视为启动程序的文件扩展名（链式调用）
+    ///
+    /// 扩展名不区分大小写，可以带或不带前导点（`"sh"` 与 `".sh"` 等价）。
+    /// 传入空列表表示收集任意文件，适用于模拟器 ROM 目录等没有可执行文件的库。
+    ///
+    /// # 参数
+    /// - `extensions`: 扩展名列表，默认 `["exe"]`
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_extensions(mut self, extensions: Vec<String>) -> Self {
+        self.extensions = extensions
+            .iter()
+            .map(|ext| ext.trim().trim_start_matches('.').to_lowercase())
+            .collect();
+        self
+    }
+
+    /// 设置排除的文件名模式（链式调用）
+    ///
+    /// 模式使用通配符（`*` 匹配任意多个字符，`?` 匹配单个字符），匹配整个文件名且不区分大小写，
+    /// 匹配的文件在遍历时直接跳过，不会产生卸载程序、运行库安装程序等无效分组。
+    /// 该列表会替换默认列表，需要保留默认规则时可在 [`DEFAULT_EXCLUDE_PATTERNS`](crate::scan::DEFAULT_EXCLUDE_PATTERNS) 的基础上追加。
+    ///
+    /// # 参数
+    /// - `patterns`: 文件名通配模式，默认为 [`DEFAULT_EXCLUDE_PATTERNS`](crate::scan::DEFAULT_EXCLUDE_PATTERNS)
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_exclude_patterns(mut self, patterns: Vec<String>) -> Self {
+        self.exclude_patterns = patterns.iter().map(|pattern| glob_to_regex(pattern)).collect();
+        self
+    }
+
+    /// 设置启动程序的最小文件大小（链式调用）
+    ///
+    /// 崩溃处理程序、配置工具等辅助程序通常只有几十 KB，设置阈值后遍历时直接跳过
+    /// 小于该大小的文件。无法读取文件大小时保留该文件；.lnk 快捷方式不受限制。
+    ///
+    /// # 参数
+    /// - `min_size`: 最小文件大小（字节），默认 `0`（不限制）
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_min_exe_size(mut self, min_size: u64) -> Self {
+        self.min_exe_size = min_size;
+        self
+    }
+
+    /// 设置是否将设置了可执行权限位的文件视为启动程序（链式调用）
+    ///
+    /// Linux 上的启动程序常常是没有扩展名的 ELF 文件或脚本（如 `start`、`run`），
+    /// 启用后无论扩展名如何，设置了可执行权限位的文件都会作为启动程序候选。
+    /// 仅在 Unix 上生效，其他平台忽略该设置。注意以 NTFS/exFAT 挂载的目录中
+    /// 所有文件通常都带有可执行权限位，不适合启用该选项。
+    ///
+    /// # 参数
+    /// - `detect`: 是否检测可执行权限位，默认 `false`
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_detect_executable_bit(mut self, detect: bool) -> Self {
+        self.detect_executable_bit = detect;
+        self
+    }
+
+    /// 设置是否读取可执行文件的版本资源（链式调用）
+    ///
+    /// 启用后，目录名中没有版本号时读取默认启动程序 PE 版本资源中的 `ProductVersion`
+    /// （没有时使用 `FileVersion`）作为游戏版本；没有找到数据库结果时使用 `ProductName` 作为标题。
+    /// 只读取文件头和资源节；非 PE 文件（如 Linux 启动脚本）会被忽略。
+    ///
+    /// # 参数
+    /// - `enabled`: 是否启用，默认 `false`
     ///
     /// # 返回
-    /// 新的 `GameScanner` 实例
-    pub fn new() -> Self {
-        GameScanner {
-            middleware: GameDatabaseMiddleware::new(),
-        }
+    /// 返回 `self` 以支持链式调用
+    pub fn with_pe_metadata(mut self, enabled: bool) -> Self {
+        self.pe_metadata = enabled;
+        self
     }
 
-    /// 注册 DLsite 提供者（链式调用）
+    /// 设置是否使用游戏目录中的本地封面（链式调用）
+    ///
+    /// 启用后在游戏根目录中查找 `cover.png`、`folder.jpg`、`icon.ico` 等图片，
+    /// 以 `file://` URL 的形式放在 `cover_urls` 的最前面，界面可以优先使用本地图片
+    ///
+    /// # 参数
+    /// - `enabled`: 是否启用，默认 `false`
     ///
     /// # 返回
     /// 返回 `self` 以支持链式调用
-    pub async fn with_dlsite_provider(self) -> Self {
-        use crate::providers::dlsite_provider::DLsiteProvider;
-        self.middleware
-            .register_provider(Arc::new(DLsiteProvider::new()))
-            .await;
+    pub fn with_local_covers(mut self, enabled: bool) -> Self {
+        self.local_cover_names = enabled.then(|| {
+            crate::scan::utils::DEFAULT_LOCAL_COVER_NAMES
+                .iter()
+                .map(|name| name.to_string())
+                .collect()
+        });
         self
     }
 
-    /// 注册 IGDB 提供者（链式调用）
+    /// 设置识别为本地封面的文件名并启用本地封面（链式调用）
     ///
     /// # 参数
-    /// - `client_id`: IGDB API 客户端 ID
-    /// - `client_secret`: IGDB API 客户端密钥
+    /// - `names`: 文件名列表（不区分大小写），按优先级排列
     ///
     /// # 返回
     /// 返回 `self` 以支持链式调用
-    pub async fn with_igdb_provider(self, client_id: String, client_secret: String) -> Self {
-        use crate::providers::igdb_provider::IGDBProvider;
-        self.middleware
-            .register_provider(Arc::new(IGDBProvider::with_credentials(
-                client_id,
-                client_secret,
-            )))
-            .await;
+    pub fn with_local_cover_names(mut self, names: Vec<String>) -> Self {
+        self.local_cover_names = Some(names);
         self
     }
 
-    /// 注册 TheGamesDB 提供者（链式调用）
+    /// 设置是否检查封面 URL 可访问性（链式调用）
+    ///
+    /// 启用后构建 GameInfo 时会并发发送 HEAD 请求检查每个候选封面，
+    /// 只保留可访问的封面，避免出现失效图片。默认关闭以免拖慢扫描。
+    ///
+    /// # 参数
+    /// - `validate`: 是否检查封面，默认 `false`
     ///
     /// # 返回
     /// 返回 `self` 以支持链式调用
-    pub async fn with_thegamesdb_provider(self) -> Self {
-        use crate::providers::thegamesdb_provider::TheGamesDBProvider;
-        self.middleware
-            .register_provider(Arc::new(TheGamesDBProvider::new()))
-            .await;
+    pub fn with_cover_validation(mut self, validate: bool) -> Self {
+        self.cover_validation = validate;
         self
     }
 
-    /// 注册自定义提供者（链式调用）
+    /// 设置离线模式（链式调用）
+    ///
+    /// 启用后扫描不访问网络：跳过所有远程提供者，只使用 NFO/XML 附属文件、
+    /// 本地提供者（如 [`with_sidecar_provider`](Self::with_sidecar_provider)、SQLite 数据库）和目录名，
+    /// 并且不检查封面 URL。没有本地数据的游戏以目录名作为标题
     ///
     /// # 参数
-    /// - `provider`: 实现了 `GameDatabaseProvider` trait 的提供者
+    /// - `offline`: 是否启用，默认 `false`
     ///
     /// # 返回
     /// 返回 `self` 以支持链式调用
-    pub async fn with_provider(
-        self,
-        provider: Arc<dyn crate::providers::GameDatabaseProvider>,
-    ) -> Self {
-        self.middleware.register_provider(provider).await;
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self.middleware = self.middleware.with_local_only(offline);
+        self
+    }
+
+    /// 设置严格模式（链式调用）
+    ///
+    /// 启用后扫描过程中任意提供者出错（认证失败、超时等）都会使 [`try_scan`](Self::try_scan)
+    /// 立即返回错误，而不是为该游戏构建回退信息。适用于需要让配置错误明确暴露的自动化流程。
+    ///
+    /// # 参数
+    /// - `strict`: 是否启用严格模式，默认 `false`
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_strict_mode(mut self, strict: bool) -> Self {
+        self.strict_mode = strict;
+        self.middleware = self.middleware.with_strict_errors(strict);
+        self
+    }
+
+    /// 设置扫描结果的排序语言区域（链式调用）
+    ///
+    /// 设置后扫描结果按标题以该语言区域的排序规则排列（如 `zh` 按拼音、`ja` 按读音），
+    /// 而不是按路径的码点顺序。默认不排序，保持原有行为。
+    ///
+    /// # 参数
+    /// - `locale`: BCP 47 语言标签（如 `zh`、`ja`、`en-US`）
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_sort_locale(mut self, locale: impl Into<String>) -> Self {
+        self.sort_locale = Some(locale.into());
+        self
+    }
+
+    /// 设置首选的发布地区（链式调用）
+    ///
+    /// 提供者返回了各地区的发布日期时，`GameInfo.release_date` 使用该地区的日期；
+    /// 未设置或没有该地区的日期时使用最早的地区发布日期
+    ///
+    /// # 参数
+    /// - `region`: 地区名称（如 `japan`、`north_america`、`europe`，忽略大小写）
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_preferred_region(mut self, region: impl Into<String>) -> Self {
+        self.preferred_region = Some(region.into());
+        self
+    }
+
+    /// 设置是否读取 NFO/XML 附属元数据文件（链式调用）
+    ///
+    /// 启用时，如果游戏根目录中存在 `game.xml` 或 `.nfo` 文件，直接使用其中的元数据
+    /// （置信度 0.95），不再查询远程数据库。支持的标签见 [`sidecar`](crate::scan::sidecar) 模块。
+    ///
+    /// # 参数
+    /// - `enabled`: 是否读取附属文件，默认 `true`
+    ///
+    /// # 返回
+    /// 返回 `self` 以支持链式调用
+    pub fn with_sidecar_metadata(mut self, enabled: bool) -> Self {
+        self.sidecar_metadata = enabled;
         self
     }
 
     /// 执行扫描
     ///
+    /// 严格模式下扫描中止时记录错误并返回空列表，需要获取错误信息时请使用 [`try_scan`](Self::try_scan)
+    ///
     /// # 参数
     /// - `scan_path`: 要扫描的目录路径
     ///
     /// # 返回
     /// 扫描到的游戏信息列表
     pub async fn scan(self, scan_path: String) -> Vec<GameInfo> {
-        self.scan_internal(scan_path).await
+        self.scan_ref(&scan_path).await
+    }
+
+    /// 执行扫描（借用版本）
+    ///
+    /// 与 [`scan`](Self::scan) 相同，但不消耗扫描器，同一个扫描器（及其已注册的提供者、
+    /// 已认证的令牌和查询缓存）可以重复用于多次扫描或搜索。
+    ///
+    /// # 参数
+    /// - `scan_path`: 要扫描的目录路径
+    ///
+    /// # 返回
+    /// 扫描到的游戏信息列表
+    pub async fn scan_ref(&self, scan_path: &str) -> Vec<GameInfo> {
+        let (progress, _) = tokio::sync::mpsc::channel(1);
+        self.scan_with_progress(scan_path, progress).await
+    }
+
+    /// 执行扫描，并通过异步通道报告进度
+    ///
+    /// 处理每个分组之前发送一个 [`ScanProgress`]（`current` 从 1 到 `total`），
+    /// 全部处理完成后发送一个 `finished` 为 `true` 的完成事件，供图形界面驱动进度条。
+    /// 接收端已关闭时忽略进度事件，扫描照常进行
+    ///
+    /// # 参数
+    /// - `scan_path`: 要扫描的目录路径
+    /// - `progress`: 进度事件的发送端
+    ///
+    /// # 返回
+    /// 扫描到的游戏信息列表
+    pub async fn scan_with_progress(
+        &self,
+        scan_path: &str,
+        progress: tokio::sync::mpsc::Sender<ScanProgress>,
+    ) -> Vec<GameInfo> {
+        match self.scan_internal(scan_path.to_string(), Some(&progress)).await {
+            Ok(game_infos) => game_infos,
+            Err(e) => {
+                get_logger().log(
+                    &LogEvent::new(LogLevel::Error, "扫描中止").with_details(e.to_string()),
+                );
+                Vec::new()
+            }
+        }
+    }
+
+    /// 一次扫描多个根目录
+    ///
+    /// 各根目录分别遍历和分组（分组时的公共前缀按单个根目录计算），合并后统一查询提供者，
+    /// 因此不同根目录中的相同游戏共享同一份查询缓存。无法访问的根目录会被跳过并记录错误
+    ///
+    /// # 参数
+    /// - `scan_paths`: 要扫描的目录路径列表
+    ///
+    /// # 返回
+    /// 所有根目录中扫描到的游戏信息列表
+    pub async fn scan_many(&self, scan_paths: Vec<String>) -> Vec<GameInfo> {
+        let mut groups = Vec::new();
+        for scan_path in &scan_paths {
+            match self.collect_groups(scan_path) {
+                Ok(root_groups) => groups.extend(root_groups),
+                Err(e) => {
+                    get_logger().log(
+                        &LogEvent::new(LogLevel::Error, "跳过扫描目录")
+                            .with_details(format!("{}: {}", scan_path, e)),
+                    );
+                }
+            }
+        }
+
+        match self.process_groups(&groups, None).await {
+            Ok(game_infos) => game_infos,
+            Err(e) => {
+                get_logger().log(
+                    &LogEvent::new(LogLevel::Error, "扫描中止").with_details(e.to_string()),
+                );
+                Vec::new()
+            }
+        }
+    }
+
+    /// 执行扫描，并返回扫描过程中的错误
+    ///
+    /// 非严格模式下查询失败的游戏会使用本地信息构建，不会返回错误；
+    /// 严格模式下第一个查询失败的游戏会使扫描立即中止
+    ///
+    /// # 参数
+    /// - `scan_path`: 要扫描的目录路径
+    ///
+    /// # 返回
+    /// - `Ok(Vec<GameInfo>)`: 扫描到的游戏信息列表
+    /// - `Err`: 严格模式下的查询错误
+    pub async fn try_scan(
+        self,
+        scan_path: String,
+    ) -> Result<Vec<GameInfo>, Box<dyn std::error::Error + Send + Sync>> {
+        self.scan_internal(scan_path, None).await
     }
 
     /// 直接搜索游戏数据库
@@ -154,14 +969,50 @@ impl GameScanner {
         self,
         search_key: String,
     ) -> Result<Vec<crate::providers::GameQueryResult>, Box<dyn std::error::Error + Send + Sync>> {
-        self.middleware
-            .search_with_timeout(&search_key, std::time::Duration::from_secs(30))
-            .await
+        self.search_ref(&search_key).await
+    }
+
+    /// 直接搜索游戏数据库（借用版本）
+    ///
+    /// 与 [`search`](Self::search) 相同，但不消耗扫描器，多次搜索共享同一个中间件和查询缓存。
+    ///
+    /// # 参数
+    /// - `search_key`: 搜索关键词（游戏名称）
+    ///
+    /// # 返回
+    /// 查询结果列表，按置信度从高到低排序
+    pub async fn search_ref(
+        &self,
+        search_key: &str,
+    ) -> Result<Vec<crate::providers::GameQueryResult>, Box<dyn std::error::Error + Send + Sync>> {
+        let results = self
+            .middleware
+            .search_with_params(search_key, &self.search_params)
+            .await?;
+        Ok(self.apply_year_range(results))
+    }
+
+    /// 按设置的年份范围过滤结果
+    fn apply_year_range(&self, results: Vec<GameQueryResult>) -> Vec<GameQueryResult> {
+        match self.year_range {
+            Some((min, max)) => results.filter_by_year(min, max, self.keep_undated),
+            None => results,
+        }
     }
 
     /// 内部扫描实现
-    async fn scan_internal(&self, scan_path: String) -> Vec<GameInfo> {
-        let mut game_infos: Vec<GameInfo> = Vec::new();
+    async fn scan_internal(
+        &self,
+        scan_path: String,
+        progress: Option<&tokio::sync::mpsc::Sender<ScanProgress>>,
+    ) -> Result<Vec<GameInfo>, Box<dyn std::error::Error + Send + Sync>> {
+        let groups = self.collect_groups(&scan_path)?;
+        self.process_groups(&groups, progress).await
+    }
+
+    /// 遍历扫描目录收集启动程序，并按游戏分组
+    fn collect_groups(&self, scan_path: &str) -> Result<Vec<PathGroupResult>, Box<dyn std::error::Error + Send + Sync>> {
+        Self::check_scan_path(scan_path)?;
 
         let logger = get_logger();
         logger.log(&LogEvent::new(
@@ -172,25 +1023,46 @@ impl GameScanner {
         // 使用并行遍历收集 .exe 文件路径
         let exe_paths = Arc::new(Mutex::new(Vec::new()));
 
+        // 快捷方式的目标是 Windows 路径，只在 Windows 上解析
+        let collect_shortcuts = self.resolve_shortcuts && cfg!(windows);
+        // 可执行权限位只在 Unix 上有意义
+        let detect_executable_bit = self.detect_executable_bit && cfg!(unix);
+        let extensions = Arc::new(self.extensions.clone());
+        let exclude_patterns = Arc::new(self.exclude_patterns.clone());
+        let min_exe_size = self.min_exe_size;
+
         {
             let exe_paths_clone = Arc::clone(&exe_paths);
-            WalkBuilder::new(&scan_path)
+            WalkBuilder::new(scan_path)
                 .threads(num_cpus::get()) // 使用所有 CPU 核心
                 .build_parallel()
                 .run(|| {
                     let exe_paths = Arc::clone(&exe_paths_clone);
+                    let extensions = Arc::clone(&extensions);
+                    let exclude_patterns = Arc::clone(&exclude_patterns);
                     Box::new(move |result| {
                         if let Ok(entry) = result {
                             // 只处理文件
                             if let Some(file_type) = entry.file_type() {
                                 if file_type.is_file() {
-                                    // 只处理 .exe 文件
-                                    if let Some(ext) = entry.path().extension() {
-                                        if ext == "exe" {
-                                            // 存储路径而不是 DirEntry（避免生命周期问题）
-                                            if let Ok(mut paths) = exe_paths.lock() {
-                                                paths.push(entry.path().to_path_buf());
-                                            }
+                                    // 只处理指定扩展名的文件（启用时包括 .lnk 快捷方式和带可执行权限位的文件）
+                                    let is_launcher = extensions.is_empty()
+                                        || entry.path().extension().is_some_and(|ext| {
+                                            extensions.iter().any(|wanted| ext.eq_ignore_ascii_case(wanted))
+                                                || (collect_shortcuts && ext.eq_ignore_ascii_case("lnk"))
+                                        })
+                                        || (detect_executable_bit && has_executable_bit(entry.path()));
+                                    let is_excluded = exclude_patterns.iter().any(|pattern| {
+                                        pattern.is_match(&entry.file_name().to_string_lossy())
+                                    });
+                                    // 读取文件大小失败时保留该文件
+                                    let is_too_small = min_exe_size > 0
+                                        && !entry.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("lnk"))
+                                        && entry.metadata().is_ok_and(|metadata| metadata.len() < min_exe_size);
+                                    if is_launcher && !is_excluded && !is_too_small {
+                                        // 存储路径而不是 DirEntry（避免生命周期问题）
+                                        if let Ok(mut paths) = exe_paths.lock() {
+                                            paths.push(entry.path().to_path_buf());
                                         }
                                     }
                                 }
@@ -202,7 +1074,7 @@ impl GameScanner {
         } // exe_paths_clone 在这里被 drop
 
         // 提取路径（现在只有一个 Arc 引用）
-        let exe_paths = Arc::try_unwrap(exe_paths)
+        let mut exe_paths = Arc::try_unwrap(exe_paths)
             .expect("Failed to unwrap Arc")
             .into_inner()
             .expect("Failed to unwrap Mutex");
@@ -212,29 +1084,85 @@ impl GameScanner {
             format!("扫描完成，找到 {} 个 .exe 文件", exe_paths.len()),
         ));
 
-        // 将路径转换回 DirEntry 格式（通过重新遍历）
-        let mut exe_dirs: Vec<DirEntry> = Vec::new();
-        for path in exe_paths {
-            // 使用 Walk 获取 DirEntry
-            for result in Walk::new(&path) {
-                if let Ok(entry) = result {
-                    if entry.path() == path {
-                        exe_dirs.push(entry);
-                        break;
-                    }
-                }
-            }
+        // 并行遍历的收集顺序不固定，确定性输出时先排序
+        if self.grouping_options.deterministic {
+            exe_paths.sort();
         }
 
         // 对扫描结果分组
-        let groups: Vec<PathGroupResult> = paths_group(exe_dirs);
+        let groups: Vec<PathGroupResult> = paths_group_with_options(exe_paths, &self.grouping_options);
+
+        // 将快捷方式替换为其目标路径
+        #[cfg(windows)]
+        let groups = if collect_shortcuts {
+            Self::resolve_group_shortcuts(groups)
+        } else {
+            groups
+        };
+
+        Ok(groups)
+    }
+
+    /// 从外部启动器的安装清单扫描
+    ///
+    /// 直接使用清单中记录的游戏名称、安装目录和启动程序构建分组，不遍历磁盘，
+    /// 然后与 [`scan`](Self::scan) 一样查询提供者并构建游戏信息。清单格式见 [`manifest`](crate::scan::manifest) 模块。
+    ///
+    /// # 参数
+    /// - `manifest_path`: JSON 或 TOML 格式的清单文件路径
+    ///
+    /// # 返回
+    /// - `Ok(Vec<GameInfo>)`: 清单中各游戏的信息，保持清单中的顺序
+    /// - `Err`: 清单无法读取或解析，或严格模式下的查询错误
+    pub async fn scan_from_manifest<P: AsRef<std::path::Path>>(
+        &self,
+        manifest_path: P,
+    ) -> Result<Vec<GameInfo>, Box<dyn std::error::Error + Send + Sync>> {
+        let groups: Vec<PathGroupResult> = read_manifest(manifest_path)?
+            .iter()
+            .map(ManifestEntry::to_group)
+            .collect();
+
+        get_logger().log(&LogEvent::new(
+            LogLevel::Success,
+            format!("读取清单完成，共 {} 个游戏", groups.len()),
+        ));
+
+        self.process_groups(&groups, None).await
+    }
 
+    /// 为每个分组查询元数据并构建游戏信息
+    ///
+    /// 设置了 `progress` 时，处理每个分组前以及全部完成后发送进度事件
+    async fn process_groups(
+        &self,
+        groups: &[PathGroupResult],
+        progress: Option<&tokio::sync::mpsc::Sender<ScanProgress>>,
+    ) -> Result<Vec<GameInfo>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut game_infos: Vec<GameInfo> = Vec::new();
         let logger = get_logger();
 
         for (idx, item) in groups.iter().enumerate() {
+            // 达到游戏数量上限时提前结束，已处理的游戏保持完整
+            if self.max_games.is_some_and(|max| game_infos.len() >= max) {
+                logger.log(&LogEvent::new(
+                    LogLevel::Warning,
+                    format!(
+                        "已达到游戏数量上限 {}，跳过剩余 {} 个分组",
+                        game_infos.len(),
+                        groups.len() - idx
+                    ),
+                ));
+                break;
+            }
+
             // 显示进度
-            let progress = ScanProgress::new(idx + 1, groups.len(), &item.child_root_name);
-            logger.section(&format!("{} - {}", progress.format(), item.child_root_name));
+            let current = ScanProgress::new(idx + 1, groups.len(), &item.child_root_name);
+            logger.section(&format!("{} - {}", current.format(), item.child_root_name));
+            if let Some(sender) = progress {
+                // 接收端关闭（如界面已退出）不影响扫描
+                let _ = sender.send(current).await;
+            }
 
             if item.search_key != item.child_root_name {
                 logger.log(&LogEvent::new(
@@ -244,7 +1172,18 @@ impl GameScanner {
             }
 
             let start_time = Instant::now();
-            match self.middleware.search(&item.search_key).await {
+            let pinned = self.fetch_pinned(item).await;
+            let query_result = match &pinned {
+                Some((_, result)) => Ok(vec![result.clone()]),
+                None => match self.read_sidecar_result(item) {
+                    Some(result) => Ok(vec![result]),
+                    None => match self.fetch_by_product_code(item).await {
+                        Some(result) => Ok(vec![result]),
+                        None => self.query_dir_or_group(item).await,
+                    },
+                },
+            };
+            match query_result {
                 Ok(game_query_results) => {
                     let duration_ms = start_time.elapsed().as_millis() as u64;
 
@@ -257,7 +1196,8 @@ impl GameScanner {
                     }
 
                     // 构建 GameInfo
-                    let game_info = self.build_game_info(item, game_query_results).await;
+                    let mut game_info = self.build_game_info(item, game_query_results).await;
+                    game_info.pinned_match = pinned.map(|(pin, _)| pin);
                     game_infos.push(game_info);
                 }
                 Err(e) => {
@@ -269,6 +1209,11 @@ impl GameScanner {
                         .with_details(e.to_string()),
                     );
 
+                    // 严格模式下直接中止扫描
+                    if self.strict_mode {
+                        return Err(format!("查询失败: {}: {}", item.child_root_name, e).into());
+                    }
+
                     // 即使查询失败，也创建基本的 GameInfo
                     let game_info = self.build_fallback_game_info(item).await;
                     game_infos.push(game_info);
@@ -276,13 +1221,211 @@ impl GameScanner {
             }
         }
 
-        logger.section(&format!("扫描完成！共找到 {} 个游戏", game_infos.len()));
-        logger.log(&LogEvent::new(
-            LogLevel::Success,
-            format!("成功扫描 {} 个游戏目录", game_infos.len()),
-        ));
+        if let Some(locale) = &self.sort_locale {
+            sort_by_locale(&mut game_infos, locale, |game| &game.title);
+        }
+
+        logger.section(&format!("扫描完成！共找到 {} 个游戏", game_infos.len()));
+        logger.log(&LogEvent::new(
+            LogLevel::Success,
+            format!("成功扫描 {} 个游戏目录", game_infos.len()),
+        ));
+        if let Some(sender) = progress {
+            let _ = sender.send(ScanProgress::completed(groups.len())).await;
+        }
+
+        Ok(game_infos)
+    }
+
+    /// 检查扫描路径：不能为空，且必须是已存在的目录
+    fn check_scan_path(scan_path: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if scan_path.trim().is_empty() {
+            return Err("扫描路径为空".into());
+        }
+
+        let path = std::path::Path::new(scan_path);
+        if !path.exists() {
+            return Err(format!("扫描路径不存在: {}", scan_path).into());
+        }
+        if !path.is_dir() {
+            return Err(format!("扫描路径不是目录: {}", scan_path).into());
+        }
+
+        Ok(())
+    }
+
+    /// 读取分组根目录中的附属元数据文件
+    fn read_sidecar_result(&self, item: &PathGroupResult) -> Option<GameQueryResult> {
+        if !self.sidecar_metadata {
+            return None;
+        }
+
+        let info = read_sidecar(std::path::Path::new(&item.root_path))?;
+        get_logger().log(&LogEvent::new(LogLevel::Debug, "使用本地附属文件中的元数据"));
+
+        Some(GameQueryResult {
+            info,
+            source: SIDECAR_SOURCE.to_string(),
+            confidence: SIDECAR_CONFIDENCE,
+        })
+    }
+
+    /// 获取分组的固定匹配结果
+    ///
+    /// # 返回
+    /// 分组在固定匹配表中且获取成功时返回 `(固定匹配, 查询结果)`，否则返回 `None`
+    async fn fetch_pinned(&self, item: &PathGroupResult) -> Option<((String, String), GameQueryResult)> {
+        let (provider, id) = self.pins.get(&item.child_root_name)?;
+
+        match self.middleware.get_by_id_from(provider, id).await {
+            Ok(result) => {
+                get_logger().log(&LogEvent::new(
+                    LogLevel::Debug,
+                    format!("使用固定匹配: {} {}", provider, id),
+                ));
+                Some(((provider.clone(), id.clone()), result))
+            }
+            Err(e) => {
+                get_logger().log(
+                    &LogEvent::new(
+                        LogLevel::Warning,
+                        format!("获取固定匹配失败，改用搜索: {} {}", provider, id),
+                    )
+                    .with_details(e.to_string()),
+                );
+                None
+            }
+        }
+    }
+
+    /// 按目录名中的 DLsite 作品编号获取元数据
+    ///
+    /// # 返回
+    /// 目录名包含作品编号且获取成功时返回置信度为 1.0 的结果；没有编号、未注册 DLsite 提供者
+    /// 或获取失败时返回 `None`，调用方回退到正常搜索
+    async fn fetch_by_product_code(&self, item: &PathGroupResult) -> Option<GameQueryResult> {
+        let code = extract_dlsite_code(&item.child_root_name)?;
+
+        match self.middleware.get_by_id_from(DLSITE_PROVIDER, &code).await {
+            Ok(result) => {
+                get_logger().log(&LogEvent::new(
+                    LogLevel::Debug,
+                    format!("按作品编号获取: {}", code),
+                ));
+                Some(result)
+            }
+            Err(e) => {
+                get_logger().log(
+                    &LogEvent::new(LogLevel::Debug, format!("按作品编号获取失败，改用搜索: {}", code))
+                        .with_details(e.to_string()),
+                );
+                None
+            }
+        }
+    }
+
+    /// 查询单个分组的元数据
+    ///
+    /// 先使用搜索关键词查询；如果结果较弱、标题包含汉字且别名表中有该目录，
+    /// 再使用别名查询并合并结果（按置信度重新排序）。
+    async fn query_group(
+        &self,
+        item: &PathGroupResult,
+    ) -> Result<Vec<GameQueryResult>, Box<dyn std::error::Error + Send + Sync>> {
+        // 检测到特定平台时，作为平台提示传给提供者
+        let platform = detect_platform(std::path::Path::new(&item.root_path));
+        // 同人作品目录名中的社团名称作为发行商提示（DLsite 按社团过滤）
+        let (circle, _) = extract_circle_and_title(&item.child_root_name);
+        let hints = Self::group_search_params(platform, circle);
+
+        // 按顺序尝试清理程度较低的关键词，得到可信匹配时直接使用
+        if self.search_key_fallbacks {
+            for key in search_key_fallbacks(&item.child_root_name) {
+                if key == item.search_key {
+                    continue;
+                }
+                let results = self.apply_year_range(self.search_for_group(&key, &hints).await?);
+                if results.first().is_some_and(|r| r.confidence >= WEAK_MATCH_THRESHOLD) {
+                    get_logger().log(&LogEvent::new(
+                        LogLevel::Debug,
+                        format!("使用备选关键词匹配: {}", key),
+                    ));
+                    return Ok(results);
+                }
+            }
+        }
+
+        let mut results = self.apply_year_range(self.search_for_group(&item.search_key, &hints).await?);
+
+        let best_confidence = results.first().map(|r| r.confidence).unwrap_or(0.0);
+        if best_confidence >= WEAK_MATCH_THRESHOLD || !contains_cjk(&item.search_key) {
+            return Ok(results);
+        }
+
+        if let Some(alias) = self.alias_map.get(&item.child_root_name) {
+            get_logger().log(&LogEvent::new(
+                LogLevel::Debug,
+                format!("匹配较弱，使用别名搜索: {}", alias),
+            ));
+
+            match self.search_for_group(alias, &hints).await {
+                Ok(alias_results) => {
+                    results.extend(self.apply_year_range(alias_results));
+                    results.sort_by(|a, b| {
+                        b.confidence
+                            .partial_cmp(&a.confidence)
+                            .unwrap_or(std::cmp::Ordering::Equal)
+                    });
+                }
+                Err(e) => {
+                    get_logger().log(
+                        &LogEvent::new(LogLevel::Warning, format!("别名搜索失败: {}", alias))
+                            .with_details(e.to_string()),
+                    );
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// 先按游戏目录查找（本地附属文件等），没有结果时再搜索
+    async fn query_dir_or_group(
+        &self,
+        item: &PathGroupResult,
+    ) -> Result<Vec<GameQueryResult>, Box<dyn std::error::Error + Send + Sync>> {
+        let results = self
+            .middleware
+            .search_in_dir(std::path::Path::new(&item.root_path), &item.search_key)
+            .await;
+        if !results.is_empty() {
+            return Ok(results);
+        }
+        self.query_group(item).await
+    }
+
+    /// 根据分组检测到的平台和社团构建搜索参数
+    fn group_search_params(platform: Option<&str>, circle: Option<String>) -> SearchParams {
+        let mut params = SearchParams::new();
+        if let Some(platform) = platform {
+            params = params.with_platform_hint(platform);
+        }
+        if let Some(circle) = circle {
+            params = params.with_developer_hint(circle);
+        }
+        params
+    }
 
-        game_infos
+    /// 使用分组的搜索参数搜索
+    async fn search_for_group(
+        &self,
+        search_key: &str,
+        hints: &SearchParams,
+    ) -> Result<Vec<GameQueryResult>, Box<dyn std::error::Error + Send + Sync>> {
+        if hints.platform_hint.is_none() && hints.developer_hint.is_none() {
+            return self.middleware.search(search_key).await;
+        }
+        self.middleware.search_with_params(search_key, hints).await
     }
 
     /// 处理查询结果并显示日志
@@ -346,6 +1489,9 @@ impl GameScanner {
                 if let Some(genres) = &result.info.genres {
                     println!("       类型: {}", genres.join(", "));
                 }
+                if let Some(description) = &result.info.description {
+                    println!("       简介: {}", truncate_chars(description, 100));
+                }
                 if let Some(cover_url) = &result.info.cover_url {
                     println!("       封面: {}", cover_url);
                 }
@@ -361,139 +1507,264 @@ impl GameScanner {
         item: &PathGroupResult,
         game_query_results: Vec<crate::providers::GameQueryResult>,
     ) -> GameInfo {
-        // 合并所有数据库的结果
-        let mut title = None; // 优先使用置信度最高的结果的标题
-        let mut cover_urls = Vec::new();
-        let mut description = None;
-        let mut release_date = None;
-        let mut developer = None;
-        let mut publisher = None;
-        let mut tabs = None;
-        let platform = None;
-
-        // 从所有查询结果中收集数据（优先使用置信度最高的）
-        for result in game_query_results.iter() {
-            // 如果还没有标题，使用第一个（置信度最高的）结果的标题
-            if title.is_none() && result.info.title.is_some() {
-                title = result.info.title.clone();
-            }
-            // 收集所有封面URL
-            if let Some(cover_url) = &result.info.cover_url {
-                if !cover_urls.contains(cover_url) {
-                    cover_urls.push(cover_url.clone());
-                }
-            }
-
-            // 如果还没有描述，使用第一个有描述的结果
-            if description.is_none() && result.info.description.is_some() {
-                description = result.info.description.clone();
-            }
+        // 各提供者声明可靠提供的字段；未注册的来源（附属文件、固定匹配）视为提供全部字段
+        let provided_fields = self.middleware.provided_fields().await;
+        let provides = |result: &GameQueryResult, field: FieldSet| {
+            provided_fields
+                .get(&result.source)
+                .is_none_or(|fields| fields.contains(field))
+        };
 
-            // 如果还没有发布日期，使用第一个有发布日期的结果
-            if release_date.is_none() && result.info.release_date.is_some() {
-                release_date = result.info.release_date.clone();
-            }
+        // 按字段合并所有数据库的结果（结果已按置信度从高到低排序）：
+        // 优先使用声明提供该字段的提供者中置信度最高的值，没有时退回任意有值的结果
+        let pick = |field: FieldSet, value: fn(&GameMetadata) -> Option<String>| {
+            game_query_results
+                .iter()
+                .filter(|result| provides(result, field))
+                .find_map(|result| value(&result.info))
+                .or_else(|| game_query_results.iter().find_map(|result| value(&result.info)))
+        };
+        let title = pick(FieldSet::TITLE, |info| info.title.clone());
+        let description = pick(FieldSet::DESCRIPTION, |info| info.description.clone());
+        // 与其他字段一样取排名最靠前的有日期的结果，只使用该结果的数据：
+        // 有地区发布日期时按首选地区（或最早的日期）选择，否则使用单一的发布日期
+        let has_date = |result: &&GameQueryResult| result.info.release_dates.is_some() || result.info.release_date.is_some();
+        let release_date = game_query_results
+            .iter()
+            .filter(|result| provides(result, FieldSet::RELEASE_DATE))
+            .find(has_date)
+            .or_else(|| game_query_results.iter().find(has_date))
+            .and_then(|result| {
+                result
+                    .info
+                    .release_dates
+                    .as_deref()
+                    .and_then(|dates| select_release_date(dates, self.preferred_region.as_deref()))
+                    .or_else(|| result.info.release_date.clone())
+            });
+        let developer = pick(FieldSet::DEVELOPER, |info| info.developer.clone());
+        let publisher = pick(FieldSet::PUBLISHER, |info| info.publisher.clone());
+        let platform = pick(FieldSet::PLATFORMS, |info| {
+            info.platforms
+                .as_ref()
+                .filter(|platforms| !platforms.is_empty())
+                .map(|platforms| platforms.join(", "))
+        });
 
-            // 如果还没有开发商，使用第一个有开发商的结果
-            if developer.is_none() && result.info.developer.is_some() {
-                developer = result.info.developer.clone();
-            }
+        // 来源和原生 ID 取自置信度最高的结果
+        let source = game_query_results.first().map(|result| result.source.clone());
+        let provider_id = game_query_results.first().and_then(|result| result.info.provider_id.clone());
 
-            // 如果还没有发行商，使用第一个有发行商的结果
-            if publisher.is_none() && result.info.publisher.is_some() {
-                publisher = result.info.publisher.clone();
-            }
+        // 收集所有封面URL（同一图片的不同尺寸只保留分辨率最高的一个）
+        let mut cover_urls = dedup_cover_urls(
+            game_query_results
+                .iter()
+                .flat_map(|result| result.info.cover_url.iter().chain(&result.info.extra_cover_urls).cloned())
+                .collect(),
+        );
 
-            // 收集所有标签
-            if let Some(genres) = &result.info.genres {
-                let genres_str = genres.join(", ");
-                if tabs.is_none() {
-                    tabs = Some(genres_str);
-                } else if let Some(existing_tabs) = &tabs {
-                    // 合并标签，避免重复
-                    let mut all_tabs: Vec<String> = existing_tabs
-                        .split(", ")
-                        .map(|s| s.to_string())
-                        .collect();
-                    for genre in genres {
-                        if !all_tabs.contains(genre) {
-                            all_tabs.push(genre.clone());
-                        }
-                    }
-                    tabs = Some(all_tabs.join(", "));
+        // 合并所有类型和标签（避免重复）：有提供者声明提供该字段时只使用这些提供者的数据
+        let genres_declared = game_query_results
+            .iter()
+            .any(|result| provides(result, FieldSet::GENRES) && result.info.genres.is_some());
+        let tags_declared = game_query_results
+            .iter()
+            .any(|result| provides(result, FieldSet::TAGS) && result.info.tags.is_some());
+        let mut all_tabs: Vec<String> = Vec::new();
+        let mut seen_tabs: HashSet<&str> = HashSet::new();
+        for result in game_query_results.iter() {
+            let genres = result
+                .info
+                .genres
+                .iter()
+                .filter(|_| !genres_declared || provides(result, FieldSet::GENRES));
+            let tags = result
+                .info
+                .tags
+                .iter()
+                .filter(|_| !tags_declared || provides(result, FieldSet::TAGS));
+            for tab in genres.chain(tags).flatten() {
+                if seen_tabs.insert(tab.as_str()) {
+                    all_tabs.push(tab.clone());
                 }
             }
+        }
+        let tabs = if all_tabs.is_empty() { None } else { Some(all_tabs.join(", ")) };
 
-            // 收集所有标签（从tags字段）
-            if let Some(tags) = &result.info.tags {
-                let tags_str = tags.join(", ");
-                if tabs.is_none() {
-                    tabs = Some(tags_str);
-                } else if let Some(existing_tabs) = &tabs {
-                    // 合并标签，避免重复
-                    let mut all_tabs: Vec<String> = existing_tabs
-                        .split(", ")
-                        .map(|s| s.to_string())
-                        .collect();
-                    for tag in tags {
-                        if !all_tabs.contains(tag) {
-                            all_tabs.push(tag.clone());
-                        }
-                    }
-                    tabs = Some(all_tabs.join(", "));
-                }
-            }
+        // 过滤无法访问的封面
+        if self.cover_validation && !self.offline && !cover_urls.is_empty() {
+            cover_urls = validate_cover_urls(&self.http_client, cover_urls).await;
+        }
+
+        // 本地封面优先
+        let mut local_covers = self.local_covers(item);
+        if !local_covers.is_empty() {
+            local_covers.append(&mut cover_urls);
+            cover_urls = local_covers;
         }
 
         // 游戏目录路径（root_path 已经是完整的游戏根目录路径）
         let dir_path = PathBuf::from(&item.root_path);
 
         // 异步计算目录大小
-        let byte_size = calculate_directory_size_async(dir_path.clone()).await;
-
-        // 解析发布日期，如果没有则使用当前时间
-        let parsed_release_date = if let Some(date_str) = release_date {
-            // 尝试解析日期字符串
-            chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")
-                .ok()
-                .and_then(|d| d.and_hms_opt(0, 0, 0))
-                .map(|dt| chrono::DateTime::<Utc>::from_naive_utc_and_offset(dt, Utc))
-                .or_else(|| {
-                    // 尝试只解析年份
-                    date_str.parse::<i32>().ok().and_then(|year| {
-                        chrono::NaiveDate::from_ymd_opt(year, 1, 1)
-                            .and_then(|d| d.and_hms_opt(0, 0, 0))
-                            .map(|dt| chrono::DateTime::<Utc>::from_naive_utc_and_offset(dt, Utc))
-                    })
-                })
-                .unwrap_or_else(Utc::now)
-        } else {
-            Utc::now()
-        };
+        let byte_size = Self::group_byte_size(item).await;
 
         // 创建 GameInfo
-        // 如果从数据库找到了标题，使用数据库的标题；否则使用本地扫描的目录名
-        let final_title = title.unwrap_or_else(|| item.child_root_name.clone());
+        // 如果从数据库找到了标题，使用数据库的标题；其次使用清单等外部来源记录的名称和可执行文件的产品名称，
+        // 否则使用本地扫描的目录名
+        let pe_info = self.launcher_version_info(item);
+        let final_title = title
+            .or_else(|| item.fallback_title.clone())
+            .or_else(|| pe_info.as_ref().and_then(|info| info.product_name.clone()))
+            .unwrap_or_else(|| item.child_root_name.clone());
 
-        // 设置默认启动项（使用第一个启动项）
-        let start_path_defualt = item.child_path.first().cloned().unwrap_or_default();
+        // 按可能性排序启动项，默认启动项使用排名第一的启动项
+        let start_path = Self::ranked_start_paths(item, &final_title);
+        let start_path_defualt = start_path.first().cloned().unwrap_or_default();
+        let platform = platform.or_else(|| Self::local_platform(item, &start_path_defualt));
 
         GameInfo {
             title: final_title,
             sub_title: item.child_root_name.clone(), // 副标题始终使用本地目录名
-            version: item.version.clone(),
+            version: item.version.clone().or_else(|| pe_info.and_then(|info| info.version())),
             cover_urls,
             dir_path,
-            start_path: item.child_path.clone(),
+            start_path,
             start_path_defualt,
             description,
-            release_date: parsed_release_date,
+            release_date: release_date.as_deref().and_then(parse_release_date),
+            developers: developer.as_deref().map(normalize_company_field).unwrap_or_default(),
+            publishers: publisher.as_deref().map(normalize_company_field).unwrap_or_default(),
             developer,
             publisher,
             tabs,
+            tab_list: all_tabs,
             platform,
+            engine: Self::group_engine(item),
             byte_size,
             scan_time: Utc::now(),
+            pinned_match: None,
+            source,
+            provider_id,
+            annotations: Default::default(),
+        }
+    }
+
+    /// 将分组中的 .lnk 快捷方式替换为其指向的可执行文件
+    ///
+    /// 目标位于游戏目录内时使用相对路径，并与已收集的启动项去重；
+    /// 无法解析、目标不存在或不是 .exe 的快捷方式会被移除，不再包含任何启动项的分组也会被移除
+    #[cfg(windows)]
+    fn resolve_group_shortcuts(mut groups: Vec<PathGroupResult>) -> Vec<PathGroupResult> {
+        use crate::scan::shortcut::resolve_shortcut_launcher;
+
+        for group in groups.iter_mut() {
+            let root = PathBuf::from(&group.root_path);
+            let mut child_path: Vec<String> = Vec::new();
+            for child in &group.child_path {
+                let is_shortcut = std::path::Path::new(child)
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("lnk"));
+                let launcher = if is_shortcut {
+                    resolve_shortcut_launcher(&root, child)
+                } else {
+                    Some(child.clone())
+                };
+                if let Some(launcher) = launcher {
+                    if !child_path.contains(&launcher) {
+                        child_path.push(launcher);
+                    }
+                }
+            }
+            group.child_path = child_path;
+        }
+
+        groups.retain(|group| !group.child_path.is_empty());
+        groups
+    }
+
+    /// 按启动项排名对分组中的可执行文件排序
+    fn ranked_start_paths(item: &PathGroupResult, title: &str) -> Vec<String> {
+        let root = std::path::Path::new(&item.root_path);
+        rank_launchers_in_dir(root, &item.child_path, &[title, &item.child_root_name, &item.search_key])
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect()
+    }
+
+    /// 读取分组默认启动程序的 PE 版本资源（未启用时返回 `None`）
+    fn launcher_version_info(&self, item: &PathGroupResult) -> Option<PeVersionInfo> {
+        if !self.pe_metadata {
+            return None;
+        }
+        let launcher = Self::ranked_start_paths(item, &item.child_root_name).into_iter().next()?;
+        read_pe_version(PathBuf::from(&item.root_path).join(launcher))
+    }
+
+    /// 根据本地文件推断游戏平台：先检测游戏目录（如 Android 安装包），再按默认启动项的类型判断
+    fn local_platform(item: &PathGroupResult, launcher: &str) -> Option<String> {
+        let detected = (!item.standalone)
+            .then(|| detect_platform(std::path::Path::new(&item.root_path)))
+            .flatten();
+        if let Some(platform) = detected {
+            return Some(platform.to_string());
+        }
+
+        let extension = std::path::Path::new(launcher)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase());
+        let platform = match extension.as_deref() {
+            Some("exe" | "bat" | "lnk") => "Windows",
+            Some("sh" | "x86_64" | "appimage") => "Linux",
+            Some("app") => "macOS",
+            Some("apk") => "Android",
+            _ => return None,
+        };
+        Some(platform.to_string())
+    }
+
+    /// 检测分组的游戏引擎；平铺的单文件游戏的根目录是扫描根目录，不做检测
+    fn group_engine(item: &PathGroupResult) -> Option<String> {
+        if item.standalone {
+            return None;
+        }
+        detect_engine(std::path::Path::new(&item.root_path)).map(str::to_string)
+    }
+
+    /// 计算分组的游戏大小：合并的分集游戏只累加各分集目录，不包含上级目录中的其他文件
+    async fn group_byte_size(item: &PathGroupResult) -> u64 {
+        let dir_path = PathBuf::from(&item.root_path);
+        // 平铺的单文件游戏只计算自身文件的大小，而不是整个扫描根目录
+        if item.standalone {
+            let mut byte_size = 0;
+            for child in &item.child_path {
+                if let Ok(metadata) = tokio::fs::metadata(dir_path.join(child)).await {
+                    byte_size += metadata.len();
+                }
+            }
+            return byte_size;
+        }
+        if item.episodes.is_empty() {
+            return calculate_directory_size_async(dir_path).await;
+        }
+
+        let mut byte_size = 0;
+        for episode in &item.episodes {
+            byte_size += calculate_directory_size_async(dir_path.join(episode)).await;
+        }
+        byte_size
+    }
+
+    /// 查找分组游戏根目录中的本地封面（未启用时为空）
+    ///
+    /// 平铺的单文件游戏的根目录是扫描根目录，其中的图片不属于某个游戏，不做查找
+    fn local_covers(&self, item: &PathGroupResult) -> Vec<String> {
+        if item.standalone {
+            return Vec::new();
+        }
+        match &self.local_cover_names {
+            Some(names) => find_local_covers(std::path::Path::new(&item.root_path), names),
+            None => Vec::new(),
         }
     }
 
@@ -501,27 +1772,42 @@ impl GameScanner {
     async fn build_fallback_game_info(&self, item: &PathGroupResult) -> GameInfo {
         // root_path 已经是完整的游戏根目录路径
         let dir_path = PathBuf::from(&item.root_path);
-        let byte_size = calculate_directory_size_async(dir_path.clone()).await;
+        let byte_size = Self::group_byte_size(item).await;
 
-        // 设置默认启动项（使用第一个启动项）
-        let start_path_defualt = item.child_path.first().cloned().unwrap_or_default();
+        // 按可能性排序启动项，默认启动项使用排名第一的启动项
+        let start_path = Self::ranked_start_paths(item, &item.child_root_name);
+        let start_path_defualt = start_path.first().cloned().unwrap_or_default();
+        let pe_info = self.launcher_version_info(item);
+        let platform = Self::local_platform(item, &start_path_defualt);
 
         GameInfo {
-            title: item.child_root_name.clone(),
+            title: item
+                .fallback_title
+                .clone()
+                .or_else(|| pe_info.as_ref().and_then(|info| info.product_name.clone()))
+                .unwrap_or_else(|| item.child_root_name.clone()),
             sub_title: item.child_root_name.clone(), // 副标题始终使用本地目录名
-            version: item.version.clone(),
-            cover_urls: Vec::new(),
+            version: item.version.clone().or_else(|| pe_info.and_then(|info| info.version())),
+            cover_urls: self.local_covers(item),
             dir_path,
-            start_path: item.child_path.clone(),
+            start_path,
             start_path_defualt,
             description: None,
-            release_date: Utc::now(),
+            release_date: None,
             developer: None,
             publisher: None,
+            developers: Vec::new(),
+            publishers: Vec::new(),
             tabs: None,
-            platform: None,
+            tab_list: Vec::new(),
+            platform,
+            engine: Self::group_engine(item),
             byte_size,
             scan_time: Utc::now(),
+            pinned_match: None,
+            source: None,
+            provider_id: None,
+            annotations: Default::default(),
         }
     }
 }
@@ -531,3 +1817,765 @@ impl GameScanner {
 pub async fn walk_path(root_path: String) -> Vec<GameInfo> {
     GameScanner::new().scan(root_path).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::mock_provider::{metadata, MockProvider};
+
+    fn group(name: &str, search_key: &str) -> PathGroupResult {
+        PathGroupResult {
+            root_path: format!("/games/{}", name),
+            child_root_name: name.to_string(),
+            child_path: vec!["game.exe".to_string()],
+            search_key: search_key.to_string(),
+            version: None,
+            episodes: Vec::new(),
+            standalone: false,
+            fallback_title: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_alias_search_finds_original_title() {
+        let provider = MockProvider::new("Mock")
+            .with_results_for("魔女的夜宴", Vec::new())
+            .with_results_for("サノバウィッチ", vec![metadata("サノバウィッチ", "ゆずソフト")]);
+
+        let aliases = HashMap::from([("【汉化】魔女的夜宴".to_string(), "サノバウィッチ".to_string())]);
+        let scanner = GameScanner::new()
+            .with_provider(Arc::new(provider))
+            .await
+            .with_alias_map(aliases);
+
+        let results = scanner
+            .query_group(&group("【汉化】魔女的夜宴", "魔女的夜宴"))
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].info.title.as_deref(), Some("サノバウィッチ"));
+    }
+
+    #[tokio::test]
+    async fn test_search_key_fallbacks_use_raw_name() {
+        // 完全清理后的关键词丢失了续作编号，只有原始目录名能匹配
+        let provider = Arc::new(
+            MockProvider::new("Mock").with_results_for("Portal 2", vec![metadata("Portal 2", "Valve")]),
+        );
+        let item = group("Portal 2", "Portal");
+
+        let scanner = GameScanner::new().with_provider(provider.clone()).await;
+        assert!(scanner.query_group(&item).await.unwrap().is_empty());
+
+        let scanner = GameScanner::new()
+            .with_provider(provider.clone())
+            .await
+            .with_search_key_fallbacks(true);
+        let results = scanner.query_group(&item).await.unwrap();
+        assert_eq!(results[0].info.title.as_deref(), Some("Portal 2"));
+    }
+
+    #[tokio::test]
+    async fn test_max_games_stops_early_with_complete_games() {
+        let provider = Arc::new(MockProvider::new("Mock").with_results(vec![metadata("Sampled", "Studio")]));
+        let groups: Vec<PathGroupResult> = (0..20)
+            .map(|i| group(&format!("Game {}", i), &format!("Game {}", i)))
+            .collect();
+
+        let scanner = GameScanner::new().with_provider(provider.clone()).await.with_max_games(5);
+        let games = scanner.process_groups(&groups, None).await.unwrap();
+
+        assert_eq!(games.len(), 5);
+        assert_eq!(provider.calls(), 5);
+        assert!(games.iter().all(|g| g.title == "Sampled" && g.developer.as_deref() == Some("Studio")));
+        assert_eq!(games[4].sub_title, "Game 4");
+    }
+
+    #[tokio::test]
+    async fn test_local_cover_is_included_first() {
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        let game_dir = dir.path().join("Local Cover Game");
+        std::fs::create_dir_all(&game_dir).unwrap();
+        std::fs::write(game_dir.join("game.exe"), b"MZ").unwrap();
+        std::fs::write(game_dir.join("Cover.PNG"), b"\x89PNG").unwrap();
+
+        let mut scraped = metadata("Local Cover Game", "Studio");
+        scraped.cover_url = Some("https://example.com/cover.jpg".to_string());
+        let provider = MockProvider::new("Mock").with_results(vec![scraped]);
+        let item = PathGroupResult {
+            root_path: game_dir.to_string_lossy().to_string(),
+            ..group("Local Cover Game", "Local Cover Game")
+        };
+
+        let scanner = GameScanner::new().with_provider(Arc::new(provider)).await;
+        let games = scanner.process_groups(std::slice::from_ref(&item), None).await.unwrap();
+        assert_eq!(games[0].cover_urls, vec!["https://example.com/cover.jpg"]);
+
+        let scanner = scanner.with_local_covers(true);
+        let games = scanner.process_groups(std::slice::from_ref(&item), None).await.unwrap();
+        assert_eq!(games[0].cover_urls.len(), 2);
+        assert!(games[0].cover_urls[0].starts_with("file://"));
+        assert!(games[0].cover_urls[0].ends_with("/Cover.PNG"));
+        assert_eq!(games[0].cover_urls[1], "https://example.com/cover.jpg");
+    }
+
+    #[tokio::test]
+    async fn test_local_cover_used_without_provider_results() {
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        for name in ["Boxed Game", "Bare Game"] {
+            std::fs::create_dir_all(dir.path().join(name)).unwrap();
+            std::fs::write(dir.path().join(name).join("game.exe"), b"MZ").unwrap();
+        }
+        std::fs::write(dir.path().join("Boxed Game").join("cover.png"), b"\x89PNG").unwrap();
+        // 扫描根目录中的平铺游戏不使用根目录中的图片
+        std::fs::write(dir.path().join("Loose Game.exe"), b"MZ").unwrap();
+        std::fs::write(dir.path().join("folder.jpg"), b"JFIF").unwrap();
+
+        let scanner = GameScanner::new()
+            .with_provider(Arc::new(MockProvider::new("Mock")))
+            .await
+            .with_local_covers(true);
+        let games = scanner.scan_ref(&dir.path().to_string_lossy()).await;
+        assert_eq!(games.len(), 3);
+
+        let covers = |name: &str| games.iter().find(|g| g.sub_title == name).unwrap().cover_urls.clone();
+        let boxed = covers("Boxed Game");
+        assert_eq!(boxed.len(), 1);
+        assert!(boxed[0].starts_with("file://") && boxed[0].ends_with("/Boxed%20Game/cover.png"));
+        assert!(covers("Bare Game").is_empty());
+        assert!(covers("Loose Game").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_scan_skips_excluded_files() {
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        let files = [
+            "Some Game/game.exe",
+            "Some Game/unins000.exe",
+            "Other Game/other.exe",
+            "Other Game/Redist/vcredist_x64.exe",
+            "Uninstaller Only/unins000.exe",
+        ];
+        for file in files {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"MZ").unwrap();
+        }
+
+        let scanner = GameScanner::new().with_provider(Arc::new(MockProvider::new("Mock"))).await;
+        let mut games = scanner.scan_ref(&dir.path().to_string_lossy()).await;
+        games.sort_by(|a, b| a.sub_title.cmp(&b.sub_title));
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].sub_title, "Other Game");
+        assert_eq!(games[0].start_path, vec!["other.exe"]);
+        assert_eq!(games[1].sub_title, "Some Game");
+        assert_eq!(games[1].start_path, vec!["game.exe"]);
+
+        // 替换默认列表后卸载程序重新被收集
+        let scanner = scanner.with_exclude_patterns(vec!["vcredist*".to_string()]);
+        let games = scanner.scan_ref(&dir.path().to_string_lossy()).await;
+        assert_eq!(games.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_pe_metadata_fills_version_and_title() {
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        let launcher = crate::scan::pe_version::version_fixture(
+            &[("ProductName", "Sample Quest"), ("ProductVersion", "1.2.3")],
+            [1, 2, 3, 0],
+        );
+        for name in ["sample_quest", "Versioned Game v2.0"] {
+            std::fs::create_dir_all(dir.path().join(name)).unwrap();
+            std::fs::write(dir.path().join(name).join("game.exe"), &launcher).unwrap();
+        }
+
+        let scanner = GameScanner::new().with_provider(Arc::new(MockProvider::new("Mock"))).await;
+        let games = scanner.scan_ref(&dir.path().to_string_lossy()).await;
+        let plain = games.iter().find(|g| g.sub_title == "sample_quest").unwrap();
+        assert_eq!(plain.version, None);
+        assert_eq!(plain.title, "sample_quest");
+
+        let scanner = scanner.with_pe_metadata(true);
+        let games = scanner.scan_ref(&dir.path().to_string_lossy()).await;
+        let plain = games.iter().find(|g| g.sub_title == "sample_quest").unwrap();
+        assert_eq!(plain.version.as_deref(), Some("1.2.3"));
+        assert_eq!(plain.title, "Sample Quest");
+        // 目录名中的版本号优先
+        let versioned = games.iter().find(|g| g.sub_title == "Versioned Game v2.0").unwrap();
+        assert_eq!(versioned.version.as_deref(), Some("2.0"));
+    }
+
+    #[tokio::test]
+    async fn test_scan_detects_game_engine() {
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        let files = [
+            "Maker Game/Game.exe",
+            "Maker Game/www/js/rpg_core.js",
+            "Unity Game/Unity Game.exe",
+            "Unity Game/Unity Game_Data/globalgamemanagers",
+            "Plain Game/plain.exe",
+        ];
+        for file in files {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"MZ").unwrap();
+        }
+
+        let scanner = GameScanner::new().with_provider(Arc::new(MockProvider::new("Mock"))).await;
+        let games = scanner.scan_ref(&dir.path().to_string_lossy()).await;
+        let engine = |name: &str| games.iter().find(|g| g.sub_title == name).unwrap().engine.clone();
+        assert_eq!(engine("Maker Game").as_deref(), Some("RPG Maker"));
+        assert_eq!(engine("Unity Game").as_deref(), Some("Unity"));
+        assert_eq!(engine("Plain Game"), None);
+    }
+
+    #[tokio::test]
+    async fn test_scan_many_groups_each_root_separately() {
+        let root_a = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        let root_b = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        for (root, name) in [(&root_a, "Game A"), (&root_a, "Shared Game"), (&root_b, "Game B"), (&root_b, "Shared Game")] {
+            std::fs::create_dir_all(root.path().join(name)).unwrap();
+            std::fs::write(root.path().join(name).join("game.exe"), b"MZ").unwrap();
+        }
+
+        let provider = Arc::new(MockProvider::new("Mock").with_results_for(
+            "Shared Game",
+            vec![metadata("Shared Game", "Studio")],
+        ));
+        let scanner = GameScanner::new().with_provider(provider.clone()).await;
+        let games = scanner
+            .scan_many(vec![
+                root_a.path().to_string_lossy().to_string(),
+                root_b.path().to_string_lossy().to_string(),
+                root_b.path().join("missing").to_string_lossy().to_string(),
+            ])
+            .await;
+
+        let mut titles: Vec<&str> = games.iter().map(|g| g.sub_title.as_str()).collect();
+        titles.sort();
+        assert_eq!(titles, vec!["Game A", "Game B", "Shared Game", "Shared Game"]);
+        assert!(games.iter().any(|g| g.dir_path.starts_with(root_a.path())));
+        assert!(games.iter().any(|g| g.dir_path.starts_with(root_b.path())));
+        // 两个根目录中的同名游戏共享查询缓存
+        assert_eq!(provider.calls(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_min_exe_size_drops_small_helpers() {
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        for name in ["Game A", "Game B"] {
+            let game_dir = dir.path().join(name);
+            std::fs::create_dir_all(&game_dir).unwrap();
+            std::fs::write(game_dir.join("game.exe"), vec![0u8; 4096]).unwrap();
+            std::fs::write(game_dir.join("helper.exe"), [0u8; 10]).unwrap();
+        }
+
+        let scanner = GameScanner::new().with_provider(Arc::new(MockProvider::new("Mock"))).await;
+        let games = scanner.scan_ref(&dir.path().to_string_lossy()).await;
+        assert!(games.iter().all(|g| g.start_path.len() == 2));
+
+        let scanner = scanner.with_min_exe_size(1024);
+        let games = scanner.scan_ref(&dir.path().to_string_lossy()).await;
+        assert_eq!(games.len(), 2);
+        assert!(games.iter().all(|g| g.start_path == vec!["game.exe"]));
+    }
+
+    #[tokio::test]
+    async fn test_scan_with_configured_extensions() {
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        let files = [
+            "RenPy Game/RenPy Game.sh",
+            "Windows Game/Game.EXE",
+            "Other Game/other.exe",
+            "Roms/Some Rom.sfc",
+        ];
+        for file in files {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"launcher").unwrap();
+        }
+
+        let scanner = GameScanner::new().with_provider(Arc::new(MockProvider::new("Mock"))).await;
+        let mut titles: Vec<String> = scanner
+            .scan_ref(&dir.path().to_string_lossy())
+            .await
+            .into_iter()
+            .map(|g| g.sub_title)
+            .collect();
+        titles.sort();
+        assert_eq!(titles, vec!["Other Game", "Windows Game"]);
+
+        let scanner = scanner.with_extensions(vec![".sh".to_string(), "exe".to_string()]);
+        let mut titles: Vec<String> = scanner
+            .scan_ref(&dir.path().to_string_lossy())
+            .await
+            .into_iter()
+            .map(|g| g.sub_title)
+            .collect();
+        titles.sort();
+        assert_eq!(titles, vec!["Other Game", "RenPy Game", "Windows Game"]);
+
+        // 空列表表示收集任意文件
+        let scanner = scanner.with_extensions(Vec::new());
+        let games = scanner.scan_ref(&dir.path().to_string_lossy()).await;
+        assert_eq!(games.len(), 4);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_detect_executable_bit_finds_extensionless_launcher() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        let game_dir = dir.path().join("Linux Game");
+        std::fs::create_dir_all(&game_dir).unwrap();
+        let launcher = game_dir.join("run");
+        std::fs::write(&launcher, b"#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(&launcher, std::fs::Permissions::from_mode(0o755)).unwrap();
+        std::fs::write(game_dir.join("readme"), b"not a launcher").unwrap();
+        for name in ["Windows Game A", "Windows Game B"] {
+            let windows_dir = dir.path().join(name);
+            std::fs::create_dir_all(&windows_dir).unwrap();
+            std::fs::write(windows_dir.join("game.exe"), b"MZ").unwrap();
+        }
+
+        let scanner = GameScanner::new().with_provider(Arc::new(MockProvider::new("Mock"))).await;
+        let games = scanner.scan_ref(&dir.path().to_string_lossy()).await;
+        assert_eq!(games.len(), 2);
+
+        let scanner = scanner.with_detect_executable_bit(true);
+        let games = scanner.scan_ref(&dir.path().to_string_lossy()).await;
+        assert_eq!(games.len(), 3);
+        let linux_game = games.iter().find(|g| g.sub_title == "Linux Game").unwrap();
+        assert_eq!(linux_game.start_path_defualt, "run");
+    }
+
+    #[tokio::test]
+    async fn test_offline_scan_uses_only_local_sources() {
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        for name in ["Nfo Game", "Json Game", "Plain Game"] {
+            std::fs::create_dir_all(dir.path().join(name)).unwrap();
+            std::fs::write(dir.path().join(name).join("game.exe"), b"MZ").unwrap();
+        }
+        std::fs::write(
+            dir.path().join("Nfo Game").join("game.nfo"),
+            "<game><title>Curated Title</title><year>1999</year></game>",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("Json Game").join("gamebox.json"),
+            r#"{"title": "Catalogued Game", "developer": "Me"}"#,
+        )
+        .unwrap();
+
+        let remote = Arc::new(MockProvider::new("Remote").with_results(vec![metadata("Remote Game", "Remote Studio")]));
+        let scanner = GameScanner::offline()
+            .with_provider(remote.clone())
+            .await
+            .with_sidecar_provider()
+            .await
+            .with_cover_validation(true);
+        let games = scanner.scan_ref(&dir.path().to_string_lossy()).await;
+
+        assert_eq!(remote.calls(), 0);
+        assert_eq!(games.len(), 3);
+        let title_of = |sub_title: &str| games.iter().find(|g| g.sub_title == sub_title).unwrap().title.clone();
+        assert_eq!(title_of("Nfo Game"), "Curated Title");
+        assert_eq!(title_of("Json Game"), "Catalogued Game");
+        assert_eq!(title_of("Plain Game"), "Plain Game");
+        assert!(games.iter().all(|g| g.byte_size > 0));
+    }
+
+    #[tokio::test]
+    async fn test_scan_with_progress_reports_each_group() {
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        for name in ["Game A", "Game B", "Game C"] {
+            std::fs::create_dir_all(dir.path().join(name)).unwrap();
+            std::fs::write(dir.path().join(name).join("game.exe"), b"MZ").unwrap();
+        }
+
+        let scanner = GameScanner::new().with_provider(Arc::new(MockProvider::new("Mock"))).await;
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+        let collector = tokio::spawn(async move {
+            let mut events = Vec::new();
+            while let Some(event) = receiver.recv().await {
+                events.push(event);
+            }
+            events
+        });
+        let games = scanner.scan_with_progress(&dir.path().to_string_lossy(), sender).await;
+        let events = collector.await.unwrap();
+
+        assert_eq!(games.len(), 3);
+        assert_eq!(events.len(), 4);
+        let (completed, per_group) = events.split_last().unwrap();
+        assert_eq!(per_group.iter().map(|e| e.current).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert!(per_group.iter().all(|e| e.total == 3 && !e.finished));
+        assert!(completed.finished);
+        assert_eq!(completed.current, 3);
+
+        // 接收端已关闭时扫描照常完成
+        let (sender, receiver) = tokio::sync::mpsc::channel(1);
+        drop(receiver);
+        assert_eq!(scanner.scan_with_progress(&dir.path().to_string_lossy(), sender).await.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_persistent_cache_saved_on_drop() {
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        let path = dir.path().join("cache.json");
+        let provider = Arc::new(MockProvider::new("Mock").with_results(vec![metadata("Celeste", "Maddy Makes Games")]));
+
+        let scanner = GameScanner::new()
+            .with_provider(provider.clone())
+            .await
+            .with_persistent_cache(&path)
+            .await;
+        assert_eq!(scanner.flush_cache().await.unwrap(), 0);
+        scanner.search("Celeste".to_string()).await.unwrap();
+        assert!(path.exists());
+
+        let results = GameScanner::new()
+            .with_provider(provider.clone())
+            .await
+            .with_persistent_cache(&path)
+            .await
+            .search("Celeste".to_string())
+            .await
+            .unwrap();
+        assert_eq!(results[0].info.title.as_deref(), Some("Celeste"));
+        assert_eq!(provider.calls(), 1);
+
+        // 之后设置的有效期同样用于保存时过滤过期条目
+        let scanner = GameScanner::new()
+            .with_provider(provider.clone())
+            .await
+            .with_persistent_cache(dir.path().join("short.json"))
+            .await
+            .with_cache_ttl(std::time::Duration::from_millis(50));
+        scanner.search_ref("Celeste").await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert_eq!(scanner.flush_cache().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_search_ref_reuses_scanner_cache() {
+        let provider = Arc::new(MockProvider::new("Mock").with_results(vec![metadata("Reused", "Studio")]));
+        let scanner = GameScanner::new().with_provider(provider.clone()).await;
+
+        let first = scanner.search_ref("Reused").await.unwrap();
+        let second = scanner.search_ref("Reused").await.unwrap();
+
+        assert_eq!(first.len(), 1);
+        assert_eq!(second[0].info.title.as_deref(), Some("Reused"));
+        assert_eq!(provider.calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_pin_overrides_confidence_pick() {
+        let provider = MockProvider::new("Mock")
+            .with_results(vec![metadata("Twin Game", "Studio A")])
+            .with_item("b-2", metadata("Twin Game Remake", "Studio B"));
+
+        let item = group("Twin Game", "Twin Game");
+        let pins = HashMap::from([(
+            "Twin Game".to_string(),
+            ("Mock".to_string(), "b-2".to_string()),
+        )]);
+        let scanner = GameScanner::new()
+            .with_provider(Arc::new(provider))
+            .await
+            .with_pins(pins);
+
+        // 模糊搜索会选中完全同名的结果
+        let searched = scanner.query_group(&item).await.unwrap();
+        assert_eq!(searched[0].info.developer.as_deref(), Some("Studio A"));
+
+        // 固定匹配直接获取指定结果
+        let (pin, result) = scanner.fetch_pinned(&item).await.unwrap();
+        assert_eq!(pin, ("Mock".to_string(), "b-2".to_string()));
+        let game_info = scanner.build_game_info(&item, vec![result]).await;
+        assert_eq!(game_info.title, "Twin Game Remake");
+        assert_eq!(game_info.developer.as_deref(), Some("Studio B"));
+    }
+
+
+    #[tokio::test]
+    async fn test_product_code_in_dir_name_uses_get_by_id() {
+        let provider = MockProvider::new(DLSITE_PROVIDER)
+            .with_results(vec![metadata("游戏名称 体験版", "Other Circle")])
+            .with_item("RJ01014447", metadata("游戏名称", "Circle"));
+        let scanner = GameScanner::new().with_provider(Arc::new(provider)).await;
+
+        let coded = group("[RJ01014447] 游戏名称", "游戏名称");
+        let result = scanner.fetch_by_product_code(&coded).await.unwrap();
+        assert_eq!(result.confidence, 1.0);
+        assert_eq!(result.info.developer.as_deref(), Some("Circle"));
+        assert!(scanner.fetch_by_product_code(&group("游戏名称", "游戏名称")).await.is_none());
+
+        let games = scanner
+            .process_groups(&[coded, group("游戏名称 v1.0", "游戏名称")], None)
+            .await
+            .unwrap();
+        assert_eq!(games[0].developer.as_deref(), Some("Circle"));
+        assert_eq!(games[1].developer.as_deref(), Some("Other Circle"));
+    }
+
+    #[tokio::test]
+    async fn test_unparsed_release_date_stays_empty() {
+        let scanner = GameScanner::new();
+        let build = |date: &str| {
+            let mut info = metadata("Dated Game", "Studio");
+            info.release_date = Some(date.to_string());
+            vec![GameQueryResult { info, source: "Mock".to_string(), confidence: 0.9 }]
+        };
+
+        let game_info = scanner.build_game_info(&group("Dated Game", "Dated Game"), build("2019年12月20日")).await;
+        assert_eq!(game_info.release_date.unwrap().format("%Y-%m-%d").to_string(), "2019-12-20");
+        let game_info = scanner.build_game_info(&group("Dated Game", "Dated Game"), build("Coming soon")).await;
+        assert_eq!(game_info.release_date, None);
+    }
+
+    #[tokio::test]
+    async fn test_field_aware_merge_prefers_declared_provider() {
+        let sparse = MockProvider::new("Sparse").with_provided_fields(FieldSet::TITLE | FieldSet::DEVELOPER);
+        let rich = MockProvider::new("Rich");
+        let scanner = GameScanner::new()
+            .with_provider(Arc::new(sparse))
+            .await
+            .with_provider(Arc::new(rich))
+            .await;
+
+        let mut sparse_info = metadata("Field Game", "Studio");
+        sparse_info.genres = Some(vec!["Misc".to_string()]);
+        sparse_info.description = Some("placeholder".to_string());
+        let mut rich_info = metadata("Field Game", "Studio");
+        rich_info.genres = Some(vec!["RPG".to_string(), "Fantasy".to_string()]);
+        rich_info.description = Some("A real description".to_string());
+
+        let results = vec![
+            GameQueryResult { info: sparse_info, source: "Sparse".to_string(), confidence: 0.9 },
+            GameQueryResult { info: rich_info, source: "Rich".to_string(), confidence: 0.8 },
+        ];
+        let game_info = scanner.build_game_info(&group("Field Game", "Field Game"), results).await;
+
+        assert_eq!(game_info.tabs.as_deref(), Some("RPG, Fantasy"));
+        assert_eq!(game_info.description.as_deref(), Some("A real description"));
+    }
+
+    #[tokio::test]
+    async fn test_platform_from_provider_or_launcher() {
+        let scanner = GameScanner::new();
+        let mut info = metadata("Platform Game", "Studio");
+        info.platforms = Some(vec!["PC (Microsoft Windows)".to_string(), "Nintendo Switch".to_string()]);
+        let results = vec![GameQueryResult { info, source: "Mock".to_string(), confidence: 0.9 }];
+        let game_info = scanner.build_game_info(&group("Platform Game", "Platform Game"), results).await;
+        assert_eq!(game_info.platform.as_deref(), Some("PC (Microsoft Windows), Nintendo Switch"));
+
+        // 没有提供者提供平台时按启动项推断
+        let results = vec![GameQueryResult {
+            info: metadata("Platform Game", "Studio"),
+            source: "Mock".to_string(),
+            confidence: 0.9,
+        }];
+        let game_info = scanner.build_game_info(&group("Platform Game", "Platform Game"), results).await;
+        assert_eq!(game_info.platform.as_deref(), Some("Windows"));
+
+        let linux = PathGroupResult {
+            child_path: vec!["start.sh".to_string()],
+            ..group("Linux Game", "Linux Game")
+        };
+        assert_eq!(scanner.build_fallback_game_info(&linux).await.platform.as_deref(), Some("Linux"));
+    }
+
+    #[tokio::test]
+    async fn test_tab_merge_keeps_tags_containing_commas() {
+        let scanner = GameScanner::new();
+        let mut first = metadata("Comma Game", "Studio");
+        first.genres = Some(vec!["Action, Adventure".to_string()]);
+        first.tags = Some(vec!["Indie".to_string(), "Action, Adventure".to_string()]);
+        let mut second = metadata("Comma Game", "Studio");
+        second.genres = Some(vec!["Action".to_string(), "Indie".to_string()]);
+
+        let results = vec![
+            GameQueryResult { info: first, source: "First".to_string(), confidence: 0.9 },
+            GameQueryResult { info: second, source: "Second".to_string(), confidence: 0.8 },
+        ];
+        let game_info = scanner.build_game_info(&group("Comma Game", "Comma Game"), results).await;
+
+        assert_eq!(game_info.tab_list, vec!["Action, Adventure", "Indie", "Action"]);
+        assert_eq!(game_info.tabs.as_deref(), Some("Action, Adventure, Indie, Action"));
+    }
+
+    #[tokio::test]
+    async fn test_preferred_region_release_date() {
+        let mut info = metadata("Region Game", "Studio");
+        info.release_date = Some("2020-09-24".to_string());
+        info.release_dates = Some(vec![
+            ("north_america".to_string(), "2020-10-01".to_string()),
+            ("worldwide".to_string(), "2020-09-24".to_string()),
+            ("japan".to_string(), "2021-08-10".to_string()),
+        ]);
+        let results = vec![GameQueryResult { info, source: "Mock".to_string(), confidence: 0.9 }];
+        let item = group("Region Game", "Region Game");
+
+        let earliest = GameScanner::new().build_game_info(&item, results.clone()).await;
+        assert_eq!(earliest.release_date.unwrap().format("%Y-%m-%d").to_string(), "2020-09-24");
+
+        let japan = GameScanner::new()
+            .with_preferred_region("Japan")
+            .build_game_info(&item, results.clone())
+            .await;
+        assert_eq!(japan.release_date.unwrap().format("%Y-%m-%d").to_string(), "2021-08-10");
+
+        let missing_region = GameScanner::new()
+            .with_preferred_region("korea")
+            .build_game_info(&item, results.clone())
+            .await;
+        assert_eq!(missing_region.release_date.unwrap().format("%Y-%m-%d").to_string(), "2020-09-24");
+
+        // 排名较低的结果的地区发布日期不会覆盖排名最高的结果的发布日期
+        let mut top = metadata("Region Game", "Studio");
+        top.release_date = Some("2019-03-01".to_string());
+        let mut results = results;
+        results.insert(0, GameQueryResult { info: top, source: "Mock".to_string(), confidence: 0.95 });
+        let ranked = GameScanner::new().build_game_info(&item, results).await;
+        assert_eq!(ranked.release_date.unwrap().format("%Y-%m-%d").to_string(), "2019-03-01");
+    }
+
+    #[tokio::test]
+    async fn test_scan_from_manifest() {
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        let manifest_path = dir.path().join("installed.json");
+        std::fs::write(
+            &manifest_path,
+            r#"{"games": [
+                {"name": "Hades", "install_dir": "/launcher/Hades", "launcher": "x64/Hades.exe"},
+                {"name": "Unknown Game", "install_dir": "/launcher/Unknown"}
+            ]}"#,
+        )
+        .unwrap();
+
+        let provider = MockProvider::new("Mock").with_results_for("Hades", vec![metadata("Hades", "Supergiant Games")]);
+        let scanner = GameScanner::new().with_provider(Arc::new(provider)).await;
+
+        let games = scanner.scan_from_manifest(&manifest_path).await.unwrap();
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].title, "Hades");
+        assert_eq!(games[0].developer.as_deref(), Some("Supergiant Games"));
+        assert_eq!(games[0].start_path_defualt, "x64/Hades.exe");
+        // 没有匹配结果时使用清单中的名称作为标题，目录名作为副标题
+        assert_eq!(games[1].title, "Unknown Game");
+        assert_eq!(games[1].sub_title, "Unknown");
+        assert_eq!(games[1].dir_path, PathBuf::from("/launcher/Unknown"));
+    }
+
+    #[tokio::test]
+    async fn test_deterministic_output_is_byte_identical() {
+        use crate::traits::JsonOutput;
+
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        for i in 0..12 {
+            let game_dir = dir.path().join(format!("Game {:02}", (i * 7) % 12)).join("bin");
+            std::fs::create_dir_all(&game_dir).unwrap();
+            std::fs::write(game_dir.join("game.exe"), vec![0u8; i]).unwrap();
+        }
+
+        let scanner = GameScanner::new()
+            .with_provider(Arc::new(MockProvider::new("Mock")))
+            .await
+            .with_deterministic_output(true);
+
+        let mut outputs = Vec::new();
+        for _ in 0..2 {
+            let mut games = scanner.scan_ref(&dir.path().to_string_lossy()).await;
+            assert_eq!(games.len(), 12);
+            // 扫描时间取当前时间，不属于扫描结果本身
+            let epoch = chrono::DateTime::<Utc>::UNIX_EPOCH;
+            for game in &mut games {
+                game.scan_time = epoch;
+            }
+
+            let mut json = Vec::new();
+            games.write_json(&mut json).unwrap();
+            outputs.push(json);
+        }
+
+        assert_eq!(outputs[0], outputs[1]);
+    }
+
+    #[tokio::test]
+    async fn test_strict_mode_aborts_scan_on_provider_error() {
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        for (name, exe) in [("Some Game", "game.exe"), ("Other Game", "other.exe")] {
+            let game_dir = dir.path().join(name);
+            std::fs::create_dir_all(&game_dir).unwrap();
+            std::fs::write(game_dir.join(exe), b"MZ").unwrap();
+        }
+        let scan_path = dir.path().display().to_string();
+
+        // 非严格模式：使用本地信息构建回退结果
+        let lenient = GameScanner::new()
+            .with_provider(Arc::new(MockProvider::new("Down").failing()))
+            .await
+            .try_scan(scan_path.clone())
+            .await
+            .unwrap();
+        assert_eq!(lenient.len(), 2);
+        assert!(lenient.iter().any(|game| game.title == "Some Game"));
+
+        // 严格模式：提供者出错时中止扫描
+        let strict = GameScanner::new()
+            .with_provider(Arc::new(MockProvider::new("Down").failing()))
+            .await
+            .with_strict_mode(true)
+            .try_scan(scan_path)
+            .await;
+        let error = strict.unwrap_err().to_string();
+        assert!(error.contains("Down"), "{}", error);
+    }
+
+
+    #[tokio::test]
+    async fn test_invalid_scan_paths_are_rejected() {
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        let file = dir.path().join("game.exe");
+        std::fs::write(&file, b"MZ").unwrap();
+
+        let error = GameScanner::new().try_scan(String::new()).await.unwrap_err();
+        assert_eq!(error.to_string(), "扫描路径为空");
+
+        let missing = dir.path().join("does/not/exist").display().to_string();
+        let error = GameScanner::new().try_scan(missing).await.unwrap_err();
+        assert!(error.to_string().starts_with("扫描路径不存在"));
+
+        let error = GameScanner::new().try_scan(file.display().to_string()).await.unwrap_err();
+        assert!(error.to_string().starts_with("扫描路径不是目录"));
+
+        // scan 记录错误并返回空列表
+        assert!(GameScanner::new().scan(String::new()).await.is_empty());
+    }
+
+
+    #[tokio::test]
+    async fn test_sidecar_metadata_used_before_providers() {
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("game.nfo"),
+            "<game><title>Curated Title</title><year>1999</year></game>",
+        )
+        .unwrap();
+
+        let mut item = group("Local Game", "Local Game");
+        item.root_path = dir.path().display().to_string();
+
+        let scanner = GameScanner::new();
+        let result = scanner.read_sidecar_result(&item).unwrap();
+        assert_eq!(result.source, "NFO");
+        assert_eq!(result.info.title.as_deref(), Some("Curated Title"));
+
+        let disabled = GameScanner::new().with_sidecar_metadata(false);
+        assert!(disabled.read_sidecar_result(&item).is_none());
+    }
+
+}
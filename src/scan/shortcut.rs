@@ -0,0 +1,250 @@
+//! Windows 快捷方式（.lnk）解析
+//!
+//! 按照 MS-SHLLINK 格式读取快捷方式指向的目标路径。解析本身不依赖平台，
+//! 但解析出的目标是 Windows 路径，扫描时只在 Windows 上使用。
+
+use std::path::Path;
+
+/// Shell Link 头部大小
+const HEADER_SIZE: usize = 0x4C;
+
+/// Shell Link 的 LinkCLSID（00021401-0000-0000-C000-000000000046）
+const LINK_CLSID: [u8; 16] = [
+    0x01, 0x14, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46,
+];
+
+// LinkFlags
+const HAS_LINK_TARGET_ID_LIST: u32 = 0x01;
+const HAS_LINK_INFO: u32 = 0x02;
+const HAS_NAME: u32 = 0x04;
+const HAS_RELATIVE_PATH: u32 = 0x08;
+const IS_UNICODE: u32 = 0x80;
+
+// LinkInfoFlags
+const VOLUME_ID_AND_LOCAL_BASE_PATH: u32 = 0x01;
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// 读取以 NUL 结尾的单字节字符串
+fn read_ansi_z(data: &[u8], offset: usize) -> Option<String> {
+    let bytes = data.get(offset..)?;
+    let end = bytes.iter().position(|&b| b == 0)?;
+    Some(String::from_utf8_lossy(&bytes[..end]).into_owned())
+}
+
+/// 读取以 NUL 结尾的 UTF-16LE 字符串
+fn read_unicode_z(data: &[u8], offset: usize) -> Option<String> {
+    let bytes = data.get(offset..)?;
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|&u| u != 0)
+        .collect();
+    Some(String::from_utf16_lossy(&units))
+}
+
+/// 解析快捷方式数据中的目标路径
+///
+/// 优先使用 LinkInfo 中的本地绝对路径；没有时使用 StringData 中的相对路径
+/// （相对于快捷方式所在目录）。
+///
+/// # 参数
+/// - `data`: .lnk 文件的完整内容
+///
+/// # 返回
+/// 目标路径；数据不是有效的快捷方式或不包含路径时返回 `None`
+pub fn parse_shortcut_target(data: &[u8]) -> Option<String> {
+    if read_u32(data, 0)? as usize != HEADER_SIZE || data.get(4..20)? != LINK_CLSID {
+        return None;
+    }
+
+    let flags = read_u32(data, 0x14)?;
+    let mut pos = HEADER_SIZE;
+
+    if flags & HAS_LINK_TARGET_ID_LIST != 0 {
+        pos += 2 + read_u16(data, pos)? as usize;
+    }
+
+    if flags & HAS_LINK_INFO != 0 {
+        let link_info_size = read_u32(data, pos)? as usize;
+        let header_size = read_u32(data, pos + 4)? as usize;
+        let link_info_flags = read_u32(data, pos + 8)?;
+
+        if link_info_flags & VOLUME_ID_AND_LOCAL_BASE_PATH != 0 {
+            let target = if header_size >= 0x24 {
+                let base = read_unicode_z(data, pos + read_u32(data, pos + 0x1C)? as usize)?;
+                let suffix = read_unicode_z(data, pos + read_u32(data, pos + 0x20)? as usize)?;
+                base + &suffix
+            } else {
+                let base = read_ansi_z(data, pos + read_u32(data, pos + 0x10)? as usize)?;
+                let suffix = read_ansi_z(data, pos + read_u32(data, pos + 0x18)? as usize)?;
+                base + &suffix
+            };
+            if !target.is_empty() {
+                return Some(target);
+            }
+        }
+
+        pos += link_info_size;
+    }
+
+    // StringData：NAME_STRING 在 RELATIVE_PATH 之前
+    let char_size = if flags & IS_UNICODE != 0 { 2 } else { 1 };
+    if flags & HAS_NAME != 0 {
+        pos += 2 + read_u16(data, pos)? as usize * char_size;
+    }
+    if flags & HAS_RELATIVE_PATH != 0 {
+        let count = read_u16(data, pos)? as usize;
+        let bytes = data.get(pos + 2..pos + 2 + count * char_size)?;
+        let relative = if char_size == 2 {
+            let units: Vec<u16> = bytes.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+            String::from_utf16_lossy(&units)
+        } else {
+            String::from_utf8_lossy(bytes).into_owned()
+        };
+        if !relative.is_empty() {
+            return Some(relative);
+        }
+    }
+
+    None
+}
+
+/// 读取快捷方式文件的目标路径
+///
+/// 相对路径会基于快捷方式所在目录解析为完整路径
+pub fn read_shortcut_target<P: AsRef<Path>>(path: P) -> Option<String> {
+    let path = path.as_ref();
+    let data = std::fs::read(path).ok()?;
+    let target = parse_shortcut_target(&data)?;
+
+    if Path::new(&target).is_absolute() {
+        Some(target)
+    } else {
+        let parent = path.parent()?;
+        Some(parent.join(target).display().to_string())
+    }
+}
+
+/// 将游戏目录中的快捷方式解析为启动程序
+///
+/// 目标不存在（失效的快捷方式）或不是 .exe 文件时返回 `None`。
+/// 目标位于游戏目录内时返回相对于游戏目录的路径（使用 `/` 分隔），与其他启动项的格式一致；
+/// 位于游戏目录外时返回完整路径
+///
+/// # 参数
+/// - `root`: 游戏根目录
+/// - `shortcut`: 快捷方式相对于游戏根目录的路径
+///
+/// # 返回
+/// 启动程序路径
+pub fn resolve_shortcut_launcher(root: &Path, shortcut: &str) -> Option<String> {
+    let target = read_shortcut_target(root.join(shortcut))?;
+    let target_path = Path::new(&target);
+    let is_exe = target_path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("exe"));
+    if !is_exe || !target_path.is_file() {
+        return None;
+    }
+
+    let relative = std::fs::canonicalize(target_path)
+        .ok()
+        .zip(std::fs::canonicalize(root).ok())
+        .and_then(|(target, root)| {
+            target
+                .strip_prefix(&root)
+                .ok()
+                .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+        });
+    Some(relative.unwrap_or(target))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 构造 Shell Link 头部
+    fn header(flags: u32) -> Vec<u8> {
+        let mut data = vec![0u8; HEADER_SIZE];
+        data[0..4].copy_from_slice(&(HEADER_SIZE as u32).to_le_bytes());
+        data[4..20].copy_from_slice(&LINK_CLSID);
+        data[0x14..0x18].copy_from_slice(&flags.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_parse_shortcut_local_base_path() {
+        // 头部 + 空的 IDList + 只含本地路径的 LinkInfo
+        let mut data = header(HAS_LINK_TARGET_ID_LIST | HAS_LINK_INFO);
+        data.extend_from_slice(&0u16.to_le_bytes());
+
+        let base_path = b"C:\\Games\\Real Game\\game.exe\0";
+        let header_size = 0x1Cu32;
+        let base_offset = header_size;
+        let suffix_offset = base_offset + base_path.len() as u32;
+        let link_info_size = suffix_offset + 1;
+
+        for value in [link_info_size, header_size, VOLUME_ID_AND_LOCAL_BASE_PATH, 0, base_offset, 0, suffix_offset] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(base_path);
+        data.push(0);
+
+        assert_eq!(
+            parse_shortcut_target(&data).as_deref(),
+            Some("C:\\Games\\Real Game\\game.exe")
+        );
+    }
+
+    #[test]
+    fn test_parse_shortcut_relative_path() {
+        let mut data = header(HAS_RELATIVE_PATH | IS_UNICODE);
+        let relative: Vec<u16> = "..\\Real\\ゲーム.exe".encode_utf16().collect();
+        data.extend_from_slice(&(relative.len() as u16).to_le_bytes());
+        for unit in relative {
+            data.extend_from_slice(&unit.to_le_bytes());
+        }
+
+        assert_eq!(parse_shortcut_target(&data).as_deref(), Some("..\\Real\\ゲーム.exe"));
+        assert_eq!(parse_shortcut_target(b"not a shortcut"), None);
+    }
+
+    /// 构造只包含相对路径的快捷方式
+    fn relative_shortcut(target: &str) -> Vec<u8> {
+        let mut data = header(HAS_RELATIVE_PATH | IS_UNICODE);
+        let units: Vec<u16> = target.encode_utf16().collect();
+        data.extend_from_slice(&(units.len() as u16).to_le_bytes());
+        for unit in units {
+            data.extend_from_slice(&unit.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn test_resolve_shortcut_launcher() {
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("Bin/x64")).unwrap();
+        std::fs::write(dir.path().join("Bin/x64/game.exe"), b"MZ").unwrap();
+        std::fs::write(dir.path().join("manual.pdf"), b"%PDF").unwrap();
+        std::fs::write(dir.path().join("Play.lnk"), relative_shortcut("Bin/x64/game.exe")).unwrap();
+        std::fs::write(dir.path().join("Broken.lnk"), relative_shortcut("Bin/missing.exe")).unwrap();
+        std::fs::write(dir.path().join("Manual.lnk"), relative_shortcut("manual.pdf")).unwrap();
+        std::fs::write(dir.path().join("Garbage.lnk"), b"not a shortcut").unwrap();
+
+        assert_eq!(
+            resolve_shortcut_launcher(dir.path(), "Play.lnk").as_deref(),
+            Some("Bin/x64/game.exe")
+        );
+        assert_eq!(resolve_shortcut_launcher(dir.path(), "Broken.lnk"), None);
+        assert_eq!(resolve_shortcut_launcher(dir.path(), "Manual.lnk"), None);
+        assert_eq!(resolve_shortcut_launcher(dir.path(), "Garbage.lnk"), None);
+        assert_eq!(resolve_shortcut_launcher(dir.path(), "Missing.lnk"), None);
+    }
+}
@@ -0,0 +1,179 @@
+//! NFO/XML 附属元数据文件
+//!
+//! 媒体中心（Kodi 等）用户会在游戏目录中放置描述游戏的 `.nfo` 或 `game.xml` 文件。
+//! 扫描时如果游戏根目录中存在这类文件，会优先使用其中由用户整理的元数据。
+//!
+//! # 支持的标签
+//!
+//! | 标签 | 对应字段 |
+//! |------|----------|
+//! | `<title>` | `title` |
+//! | `<plot>` / `<outline>` | `description` |
+//! | `<releasedate>` / `<premiered>` / `<year>` | `release_date` |
+//! | `<developer>` / `<studio>` | `developer` |
+//! | `<publisher>` | `publisher` |
+//! | `<genre>`（可多个） | `genres` |
+//! | `<tag>`（可多个） | `tags` |
+//! | `<thumb>` | `cover_url`（取第一个） |
+//!
+//! 解析是宽松的：缺少的标签对应字段为空，无法识别的标签和嵌套结构会被忽略，
+//! 文件中 XML 之外的内容（如 Kodi 允许附加的刮削 URL）也不影响解析。
+
+use std::path::{Path, PathBuf};
+
+use crate::models::game_meta_data::GameMetadata;
+use crate::scan::patterns::NFO_TAG_PATTERN;
+
+/// 附属文件来源名称
+pub const SIDECAR_SOURCE: &str = "NFO";
+
+/// 附属文件元数据的置信度
+pub const SIDECAR_CONFIDENCE: f32 = 0.95;
+
+/// 解码 XML 实体和 CDATA
+fn decode_text(raw: &str) -> String {
+    let text = raw.trim();
+    if let Some(cdata) = text.strip_prefix("<![CDATA[").and_then(|t| t.strip_suffix("]]>")) {
+        return cdata.trim().to_string();
+    }
+
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// 解析 NFO/XML 内容
+///
+/// # 参数
+/// - `content`: 文件内容
+///
+/// # 返回
+/// 解析出的元数据；不包含任何支持的标签时返回 `None`
+pub fn parse_nfo(content: &str) -> Option<GameMetadata> {
+    let mut metadata = GameMetadata::default();
+    let mut year = None;
+    let mut genres: Vec<String> = Vec::new();
+    let mut tags: Vec<String> = Vec::new();
+    let mut found = false;
+
+    for caps in NFO_TAG_PATTERN.captures_iter(content) {
+        let tag = caps[1].to_lowercase();
+        if tag != caps[3].to_lowercase() {
+            continue;
+        }
+        let value = decode_text(&caps[2]);
+        if value.is_empty() {
+            continue;
+        }
+
+        // 同名标签只取第一个（genre/tag 除外）
+        let field = match tag.as_str() {
+            "title" => &mut metadata.title,
+            "plot" | "outline" => &mut metadata.description,
+            "releasedate" | "premiered" => &mut metadata.release_date,
+            "year" => &mut year,
+            "developer" | "studio" => &mut metadata.developer,
+            "publisher" => &mut metadata.publisher,
+            "thumb" => &mut metadata.cover_url,
+            "genre" => {
+                genres.push(value);
+                found = true;
+                continue;
+            }
+            "tag" => {
+                tags.push(value);
+                found = true;
+                continue;
+            }
+            _ => continue,
+        };
+        if field.is_none() {
+            *field = Some(value);
+        }
+        found = true;
+    }
+
+    if metadata.release_date.is_none() {
+        metadata.release_date = year;
+    }
+    if !genres.is_empty() {
+        metadata.genres = Some(genres);
+    }
+    if !tags.is_empty() {
+        metadata.tags = Some(tags);
+    }
+
+    found.then_some(metadata)
+}
+
+/// 查找目录中的附属文件
+///
+/// 优先使用 `game.xml`，其次是按文件名排序的第一个 `.nfo` 文件
+pub fn find_sidecar(dir: &Path) -> Option<PathBuf> {
+    let game_xml = dir.join("game.xml");
+    if game_xml.is_file() {
+        return Some(game_xml);
+    }
+
+    let mut nfo_files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("nfo"))
+        })
+        .collect();
+    nfo_files.sort();
+    nfo_files.into_iter().next()
+}
+
+/// 读取目录中附属文件的元数据
+///
+/// 没有附属文件、读取失败或内容无法解析时返回 `None`
+pub fn read_sidecar(dir: &Path) -> Option<GameMetadata> {
+    let path = find_sidecar(dir)?;
+    let bytes = std::fs::read(path).ok()?;
+    parse_nfo(&String::from_utf8_lossy(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_NFO: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes" ?>
+<game>
+    <title>Cave Story</title>
+    <originaltitle>洞窟物語</originaltitle>
+    <plot><![CDATA[A classic <platformer> & adventure.]]></plot>
+    <year>2004</year>
+    <genre>Platform</genre>
+    <genre>Metroidvania</genre>
+    <developer>Studio Pixel</developer>
+    <thumb aspect="poster">https://example.com/cave&amp;story.jpg</thumb>
+    <thumb aspect="fanart">https://example.com/fanart.jpg</thumb>
+    <rating></rating>
+</game>
+https://example.com/scraper/cave-story
+"#;
+
+    #[test]
+    fn test_parse_sample_nfo() {
+        let metadata = parse_nfo(SAMPLE_NFO).unwrap();
+
+        assert_eq!(metadata.title.as_deref(), Some("Cave Story"));
+        assert_eq!(metadata.description.as_deref(), Some("A classic <platformer> & adventure."));
+        assert_eq!(metadata.release_date.as_deref(), Some("2004"));
+        assert_eq!(metadata.developer.as_deref(), Some("Studio Pixel"));
+        assert_eq!(metadata.publisher, None);
+        assert_eq!(
+            metadata.genres,
+            Some(vec!["Platform".to_string(), "Metroidvania".to_string()])
+        );
+        assert_eq!(metadata.cover_url.as_deref(), Some("https://example.com/cave&story.jpg"));
+
+        assert!(parse_nfo("not an nfo file").is_none());
+    }
+}
@@ -1,9 +1,11 @@
 //! 扫描相关的工具函数
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use crate::scan::patterns::{
     VERSION_PATTERNS, PREFIX_PATTERNS, VERSION_REMOVAL_PATTERNS,
-    PLATFORM_PATTERNS, SUFFIX_PATTERNS,
+    PLATFORM_PATTERNS, SUFFIX_PATTERNS, CIRCLE_CATEGORY_PATTERN, CIRCLE_PREFIX_PATTERN,
+    CIRCLE_SUFFIX_PATTERN, NON_CIRCLE_TAG_PATTERN, IGDB_IMAGE_PATTERN, DLSITE_IMAGE_PATTERN,
+    TRIAL_MARKER_PATTERN, DLSITE_PRODUCT_CODE_PATTERN,
 };
 
 /// 计算目录大小（异步版本，使用迭代而非递归避免栈溢出）
@@ -67,6 +69,26 @@ pub fn extract_version(dir_name: &str) -> Option<String> {
     None
 }
 
+/// 生成逐步清理的搜索关键词列表
+///
+/// 依次为：原始目录名、去除前缀标签后的名称、完全清理后的关键词（与 [`extract_search_key`] 相同），
+/// 去除重复和空的关键词。完全清理有时会去掉有意义的部分（如续作编号），
+/// 扫描器可以按顺序尝试这些关键词，使用第一个得到可信匹配的结果。
+pub(crate) fn search_key_fallbacks(dir_name: &str) -> Vec<String> {
+    let mut prefix_stripped = dir_name.to_string();
+    for re in PREFIX_PATTERNS.iter() {
+        prefix_stripped = re.replace_all(&prefix_stripped, "").to_string();
+    }
+
+    let mut keys: Vec<String> = Vec::new();
+    for key in [dir_name.trim().to_string(), prefix_stripped.trim().to_string(), extract_search_key(dir_name)] {
+        if !key.is_empty() && !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    keys
+}
+
 /// 从游戏目录名中提取搜索关键词
 ///
 /// 去除常见的前缀标签和版本号，如：【RPG官中】、【SLG汉化】、v1.0 等
@@ -125,6 +147,253 @@ pub fn extract_search_key(dir_name: &str) -> String {
     }
 }
 
+/// 从同人作品目录名中分离社团（サークル）名称和作品标题
+///
+/// 识别 DLsite 同人作品常见的命名方式：`[サークル名] 作品名`、`[サークル名 (作者名)] 作品名`
+/// 和 `作品名 (サークル名)`，可带 `(同人ゲーム)` 等分类前缀。作品编号、汉化标签、版本号等
+/// 括号内容不会被当作社团。标题部分再经过 [`extract_search_key`] 清理。
+///
+/// # 参数
+/// - `dir_name`: 目录名称
+///
+/// # 返回
+/// `(社团名称, 搜索标题)`，未识别到社团时社团为 `None`
+///
+/// # 示例
+/// ```
+/// use gamebox::scan::extract_circle_and_title;
+///
+/// let (circle, title) = extract_circle_and_title("(同人ゲーム) [ぷらずまそふと] 魔法少女の冒険");
+/// assert_eq!(circle.as_deref(), Some("ぷらずまそふと"));
+/// assert_eq!(title, "魔法少女の冒険");
+/// ```
+pub fn extract_circle_and_title(dir_name: &str) -> (Option<String>, String) {
+    let name = CIRCLE_CATEGORY_PATTERN.replace(dir_name.trim(), "").to_string();
+
+    let candidates = [
+        CIRCLE_PREFIX_PATTERN.captures(&name).map(|caps| (caps[1].to_string(), caps[2].to_string())),
+        CIRCLE_SUFFIX_PATTERN.captures(&name).map(|caps| (caps[2].to_string(), caps[1].to_string())),
+    ];
+
+    for (circle, rest) in candidates.into_iter().flatten() {
+        // `サークル名 (作者名)` 只保留社团部分
+        let circle = circle
+            .split(['(', '（'])
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        if !circle.is_empty() && !NON_CIRCLE_TAG_PATTERN.is_match(&circle) {
+            return (Some(circle), extract_search_key(&rest));
+        }
+    }
+
+    (None, extract_search_key(&name))
+}
+
+/// 按字符数截断字符串（不会在多字节字符中间截断）
+///
+/// 超过 `max_chars` 个字符时截取前 `max_chars` 个字符并追加 `...`，否则原样返回。
+/// 直接使用 `&s[..n]` 按字节切片在日文、中文简介上会因落在字符中间而 panic。
+///
+/// # 示例
+/// ```
+/// use gamebox::scan::truncate_chars;
+///
+/// assert_eq!(truncate_chars("ゲーム紹介文", 3), "ゲーム...");
+/// assert_eq!(truncate_chars("short", 10), "short");
+/// ```
+pub fn truncate_chars(s: &str, max_chars: usize) -> String {
+    match s.char_indices().nth(max_chars) {
+        Some((byte_index, _)) => format!("{}...", &s[..byte_index]),
+        None => s.to_string(),
+    }
+}
+
+/// 大小写折叠，用于不区分大小写的比较
+///
+/// 纯 ASCII 字符串只做 ASCII 小写转换；其他字符串逐字符使用 Unicode 简单大小写折叠。
+/// 与 `to_lowercase` 不同，简单折叠不会改变字符数量（如 `İ` 不会变成 `i` 加组合点、
+/// `ß` 不会展开），避免土耳其语、德语等文本在相似度计算中产生偏差
+///
+/// # 示例
+/// ```
+/// use gamebox::scan::fold_case;
+///
+/// assert_eq!(fold_case("Elden RING"), "elden ring");
+/// assert_eq!(fold_case("ΟΔΥΣΣΕΙΑ"), "οδυσσεια");
+/// ```
+pub fn fold_case(s: &str) -> String {
+    if s.is_ascii() {
+        return s.to_ascii_lowercase();
+    }
+
+    s.chars()
+        .map(|c| {
+            unicode_case_mapping::case_folded(c)
+                .and_then(|folded| char::from_u32(folded.get()))
+                .unwrap_or(c)
+        })
+        .collect()
+}
+
+/// 从目录名中提取 DLsite 作品编号（`RJ`、`VJ`、`BJ` 开头）
+///
+/// # 参数
+/// - `dir_name`: 目录名称
+///
+/// # 返回
+/// 大写的作品编号，如果没有找到则返回 `None`
+///
+/// # 示例
+/// ```
+/// use gamebox::scan::extract_dlsite_code;
+///
+/// assert_eq!(extract_dlsite_code("[rj01014447] 游戏名称").as_deref(), Some("RJ01014447"));
+/// assert_eq!(extract_dlsite_code("游戏名称"), None);
+/// ```
+pub fn extract_dlsite_code(dir_name: &str) -> Option<String> {
+    DLSITE_PRODUCT_CODE_PATTERN
+        .captures(dir_name)
+        .map(|caps| caps[1].to_ascii_uppercase())
+}
+
+/// 判断标题是否为体验版/试玩版
+///
+/// `demo`、`trial` 只在括号内或标题结尾时视为标记，避免误判 `Demon's Souls` 这类标题
+///
+/// # 示例
+/// ```
+/// use gamebox::scan::is_trial_title;
+///
+/// assert!(is_trial_title("ゲーム名【体験版】"));
+/// assert!(is_trial_title("Elden Ring Demo"));
+/// assert!(!is_trial_title("Demon's Souls"));
+/// ```
+pub fn is_trial_title(title: &str) -> bool {
+    TRIAL_MARKER_PATTERN.is_match(title)
+}
+
+/// 判断字符串是否包含中日韩统一表意文字（汉字）
+pub fn contains_cjk(s: &str) -> bool {
+    s.chars().any(|c| {
+        matches!(c,
+            '\u{4E00}'..='\u{9FFF}'   // CJK 统一表意文字
+            | '\u{3400}'..='\u{4DBF}' // 扩展 A
+            | '\u{F900}'..='\u{FAFF}' // 兼容表意文字
+        )
+    })
+}
+
+/// 启动项降权关键词：安装、卸载、调试、配置等辅助程序通常不是游戏本体
+const LAUNCHER_DEMOTE_KEYWORDS: &[&str] = &[
+    "setup", "unins", "install", "debug", "config", "crash", "update", "redist",
+];
+
+/// 归一化名称：转小写并只保留字母和数字，便于比较可执行文件名与游戏名
+fn normalize_launcher_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// 为单个启动项打分（0.0 ~ 1.0，越高越可能是游戏本体）
+///
+/// - 文件名包含 setup/uninstall/debug 等辅助程序关键词时大幅降权
+/// - 文件名与游戏名一致（或互相包含）时加分
+/// - 位于游戏根目录时加分，目录层级越深分数越低
+///
+/// # 参数
+/// - `relative_path`: 相对于游戏根目录的启动项路径
+/// - `game_names`: 用于匹配的游戏名称（目录名、标题等）
+pub fn score_launcher(relative_path: &str, game_names: &[&str]) -> f32 {
+    let normalized_path = relative_path.replace('\\', "/");
+    let file_name = normalized_path.rsplit('/').next().unwrap_or(&normalized_path);
+    let stem = file_name.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(file_name);
+    let stem_lower = stem.to_lowercase();
+    let stem_normalized = normalize_launcher_name(stem);
+
+    let mut score: f32 = 0.5;
+
+    // 1. 辅助程序降权
+    if LAUNCHER_DEMOTE_KEYWORDS.iter().any(|k| stem_lower.contains(k)) {
+        score -= 0.4;
+    }
+
+    // 2. 名称匹配加分（取最佳匹配）
+    let name_bonus = game_names
+        .iter()
+        .map(|name| normalize_launcher_name(name))
+        .filter(|name| !name.is_empty() && !stem_normalized.is_empty())
+        .map(|name| {
+            if name == stem_normalized {
+                0.3
+            } else if name.contains(&stem_normalized) || stem_normalized.contains(&name) {
+                0.15
+            } else {
+                0.0
+            }
+        })
+        .fold(0.0, f32::max);
+    score += name_bonus;
+
+    // 3. 位置：根目录加分，每深一层减分
+    let depth = normalized_path.matches('/').count();
+    if depth == 0 {
+        score += 0.1;
+    } else {
+        score -= 0.05 * depth as f32;
+    }
+
+    score.clamp(0.0, 1.0)
+}
+
+/// 对启动项进行排序打分
+///
+/// # 参数
+/// - `paths`: 相对于游戏根目录的启动项路径列表
+/// - `game_names`: 用于匹配的游戏名称（目录名、标题等）
+///
+/// # 返回
+/// `(启动项, 分数)` 列表，按分数从高到低排序；分数相同时保持原有顺序
+pub fn rank_launchers(paths: &[String], game_names: &[&str]) -> Vec<(String, f32)> {
+    let mut ranked: Vec<(String, f32)> = paths
+        .iter()
+        .map(|path| (path.clone(), score_launcher(path, game_names)))
+        .collect();
+    ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    ranked
+}
+
+/// 对游戏目录中的启动项进行排序打分，分数相同时文件越大越靠前
+///
+/// 游戏本体通常比同目录下的配置工具、更新程序大得多，
+/// 文件名都无法区分时按文件大小选择；无法读取大小的文件视为 0 字节
+///
+/// # 参数
+/// - `root`: 游戏根目录
+/// - `paths`: 相对于游戏根目录的启动项路径列表
+/// - `game_names`: 用于匹配的游戏名称（目录名、标题等）
+///
+/// # 返回
+/// `(启动项, 分数)` 列表，按分数从高到低排序
+pub fn rank_launchers_in_dir(root: &Path, paths: &[String], game_names: &[&str]) -> Vec<(String, f32)> {
+    let mut ranked: Vec<(String, f32, u64)> = paths
+        .iter()
+        .map(|path| {
+            let size = std::fs::metadata(root.join(path)).map(|metadata| metadata.len()).unwrap_or(0);
+            (path.clone(), score_launcher(path, game_names), size)
+        })
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(b.2.cmp(&a.2))
+    });
+    ranked.into_iter().map(|(path, score, _)| (path, score)).collect()
+}
+
 /// 找到一组路径的最近公共父目录（不包括文件名）
 ///
 /// # 参数
@@ -167,6 +436,440 @@ pub fn find_common_parent_dir(paths: &[Vec<String>]) -> usize {
     common_len
 }
 
+/// 根据游戏目录中的文件检测非 PC 平台
+///
+/// 目前识别：目录中包含 `.apk` 安装包时视为 `Android`。
+/// 只检查目录本身（不递归），检测不到特定平台时返回 `None`。
+///
+/// # 参数
+/// - `dir`: 游戏根目录
+///
+/// # 返回
+/// 检测到的平台名称
+pub fn detect_platform(dir: &std::path::Path) -> Option<&'static str> {
+    let has_apk = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .any(|entry| {
+            entry
+                .path()
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("apk"))
+        });
+
+    has_apk.then_some("Android")
+}
+
+/// 根据游戏目录中的文件检测游戏引擎
+///
+/// 只检查目录本身的直接子项（不递归），按以下特征识别：
+/// - `RPG Maker`：`www/` 目录（MV）、`js/rmmz_core.js`（MZ）或 `Game.rgss*a` 加密归档（XP/VX/VX Ace）
+/// - `Ren'Py`：`renpy/` 目录
+/// - `Unity`：`<名称>_Data/` 目录或 `UnityPlayer.dll`
+/// - `Unreal Engine`：`Engine/` 目录
+/// - `WOLF RPG Editor`：`Data.wolf` 归档
+///
+/// # 参数
+/// - `dir`: 游戏根目录
+///
+/// # 返回
+/// 检测到的引擎名称，无法识别时返回 `None`
+pub fn detect_engine(dir: &Path) -> Option<&'static str> {
+    let entries: Vec<(String, bool)> = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| {
+            let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+            (entry.file_name().to_string_lossy().to_lowercase(), is_dir)
+        })
+        .collect();
+    let has_dir = |name: &str| entries.iter().any(|(entry, is_dir)| *is_dir && entry == name);
+    let has_file = |name: &str| entries.iter().any(|(entry, is_dir)| !*is_dir && entry == name);
+
+    if has_dir("www")
+        || dir.join("js").join("rmmz_core.js").is_file()
+        || entries.iter().any(|(entry, is_dir)| !*is_dir && entry.starts_with("game.rgss"))
+    {
+        Some("RPG Maker")
+    } else if has_dir("renpy") {
+        Some("Ren'Py")
+    } else if has_file("unityplayer.dll") || entries.iter().any(|(entry, is_dir)| *is_dir && entry.ends_with("_data")) {
+        Some("Unity")
+    } else if has_dir("engine") {
+        Some("Unreal Engine")
+    } else if has_file("data.wolf") {
+        Some("WOLF RPG Editor")
+    } else {
+        None
+    }
+}
+
+/// 判断文件是否设置了可执行权限位（仅 Unix，其他平台总是返回 `false`）
+///
+/// # 参数
+/// - `path`: 文件路径
+pub(crate) fn has_executable_bit(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::metadata(path).is_ok_and(|metadata| metadata.permissions().mode() & 0o111 != 0)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+/// 公司名称之间的常见分隔符
+const COMPANY_SEPARATORS: &[char] = &[',', '/', ';', '、', '，', '／', '；'];
+
+/// 拆分包含多个公司的开发商/发行商字符串
+///
+/// 按逗号、斜杠、分号等常见分隔符拆分，去除首尾空白，并按忽略大小写去重（保留首次出现的写法）
+///
+/// # 参数
+/// - `s`: 开发商或发行商字符串
+///
+/// # 返回
+/// 公司名称列表
+///
+/// # 示例
+/// ```
+/// use gamebox::scan::normalize_company_field;
+///
+/// assert_eq!(
+///     normalize_company_field("Studio A, Studio B / studio a"),
+///     vec!["Studio A".to_string(), "Studio B".to_string()]
+/// );
+/// ```
+pub fn normalize_company_field(s: &str) -> Vec<String> {
+    let mut companies: Vec<String> = Vec::new();
+    for part in s.split(COMPANY_SEPARATORS) {
+        let name = part.trim();
+        if name.is_empty() {
+            continue;
+        }
+        if !companies.iter().any(|c| c.to_lowercase() == name.to_lowercase()) {
+            companies.push(name.to_string());
+        }
+    }
+    companies
+}
+
+/// 按指定语言区域的排序规则排序
+///
+/// 使用 Unicode 排序算法（CLDR 排序规则），使中日文等标题按读音等符合习惯的顺序排列，
+/// 例如中文（`zh`）按拼音排序。语言区域无效或没有对应的排序数据时按码点顺序排序。
+///
+/// # 参数
+/// - `items`: 要排序的元素
+/// - `locale`: BCP 47 语言标签（如 `zh`、`ja`、`en-US`）
+/// - `key`: 获取排序依据字符串的函数
+pub fn sort_by_locale<T, F>(items: &mut [T], locale: &str, key: F)
+where
+    F: Fn(&T) -> &str,
+{
+    use icu_collator::{Collator, CollatorOptions};
+
+    let collator = locale
+        .parse::<icu_locid::Locale>()
+        .ok()
+        .and_then(|locale| Collator::try_new(&locale.into(), CollatorOptions::new()).ok());
+
+    match collator {
+        Some(collator) => items.sort_by(|a, b| collator.compare(key(a), key(b))),
+        None => items.sort_by(|a, b| key(a).cmp(key(b))),
+    }
+}
+
+/// 默认排除的文件名模式：卸载程序、运行库和引擎附带的辅助程序
+pub const DEFAULT_EXCLUDE_PATTERNS: [&str; 12] = [
+    "unins*.exe",
+    "vcredist*",
+    "vc_redist*",
+    "dxsetup.exe",
+    "dxwebsetup.exe",
+    "oalinst.exe",
+    "dotnetfx*",
+    "physx*",
+    "UnityCrashHandler*",
+    "CrashReportClient.exe",
+    "UE4PrereqSetup*",
+    "UEPrereqSetup*",
+];
+
+/// 将文件名通配模式编译为正则表达式
+///
+/// 支持 `*`（任意多个字符）和 `?`（单个字符），其余字符按字面匹配。
+/// 匹配整个文件名，不区分大小写
+///
+/// # 参数
+/// - `pattern`: 通配模式，如 `unins*.exe`
+///
+/// # 返回
+/// 编译后的正则表达式
+///
+/// # 示例
+///
+/// ```
+/// use gamebox::scan::glob_to_regex;
+///
+/// let pattern = glob_to_regex("unins*.exe");
+/// assert!(pattern.is_match("UNINS000.EXE"));
+/// assert!(!pattern.is_match("game.exe"));
+/// ```
+pub fn glob_to_regex(pattern: &str) -> regex::Regex {
+    let mut source = String::from("(?i)^");
+    for c in pattern.chars() {
+        match c {
+            '*' => source.push_str(".*"),
+            '?' => source.push('.'),
+            c => source.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    source.push('$');
+    regex::Regex::new(&source).expect("转义后的通配模式总是有效的正则")
+}
+
+/// 默认识别的本地封面文件名（按优先级排列）
+pub const DEFAULT_LOCAL_COVER_NAMES: [&str; 10] = [
+    "cover.png",
+    "cover.jpg",
+    "folder.jpg",
+    "folder.png",
+    "box.jpg",
+    "box.png",
+    "poster.jpg",
+    "poster.png",
+    "icon.png",
+    "icon.ico",
+];
+
+/// 查找游戏目录中的本地封面图片
+///
+/// 文件名匹配不区分大小写，只查找目录本身，不递归子目录
+///
+/// # 参数
+/// - `dir`: 游戏根目录
+/// - `names`: 识别的文件名（按优先级排列）
+///
+/// # 返回
+/// 找到的图片的 `file://` URL，按 `names` 的顺序排列
+pub fn find_local_covers(dir: &Path, names: &[String]) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let files: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+
+    names
+        .iter()
+        .filter_map(|name| {
+            files.iter().find(|path| {
+                path.file_name()
+                    .is_some_and(|file_name| file_name.to_string_lossy().eq_ignore_ascii_case(name))
+            })
+        })
+        .filter_map(|path| std::path::absolute(path).ok())
+        .filter_map(|path| reqwest::Url::from_file_path(path).ok())
+        .map(|url| url.to_string())
+        .collect()
+}
+
+/// IGDB 图片尺寸标记，按分辨率从低到高排列
+const IGDB_IMAGE_SIZES: &[&str] = &[
+    "micro", "thumb", "cover_small", "logo_med", "screenshot_med",
+    "cover_big", "screenshot_big", "screenshot_huge", "720p", "1080p",
+];
+
+/// 识别封面 URL 指向的图片及其分辨率等级
+///
+/// # 返回
+/// `(图片标识, 分辨率等级)`，同一图片的不同尺寸得到相同的标识；无法识别的 URL 返回 `None`
+fn cover_image_key(url: &str) -> Option<(String, u32)> {
+    if let Some(caps) = IGDB_IMAGE_PATTERN.captures(url) {
+        let size = caps[1].to_ascii_lowercase();
+        let (size, retina) = match size.strip_suffix("_2x") {
+            Some(size) => (size.to_string(), 1),
+            None => (size, 0),
+        };
+        let rank = IGDB_IMAGE_SIZES.iter().position(|s| *s == size).map_or(0, |i| i as u32 + 1);
+        return Some((format!("igdb:{}", &caps[2]), rank * 2 + retina));
+    }
+
+    if let Some(caps) = DLSITE_IMAGE_PATTERN.captures(url) {
+        let kind = caps[2].to_ascii_lowercase();
+        // `sam` 是主图的小缩略图；带尺寸后缀的是按尺寸缩放的缩略图，不带后缀的是原图
+        let (kind, rank) = match (kind.as_str(), caps.get(3)) {
+            ("sam", _) => ("main".to_string(), 1),
+            (_, Some(width)) => (kind, width.as_str().parse().unwrap_or(0)),
+            (_, None) => (kind, u32::MAX),
+        };
+        return Some((format!("dlsite:{}:{}", caps[1].to_ascii_uppercase(), kind), rank));
+    }
+
+    None
+}
+
+/// 合并指向同一图片不同尺寸的封面 URL
+///
+/// 识别 IGDB 的尺寸标记（`t_cover_big`、`t_1080p` 等）和 DLsite 的缩略图/原图，
+/// 同一图片只保留分辨率最高的 URL，放在该图片首次出现的位置。无法识别的 URL 按完全相同去重
+///
+/// # 参数
+/// - `urls`: 封面 URL 列表
+///
+/// # 返回
+/// 去重后的封面 URL
+///
+/// # 示例
+///
+/// ```
+/// use gamebox::scan::dedup_cover_urls;
+///
+/// let urls = dedup_cover_urls(vec![
+///     "https://images.igdb.com/igdb/image/upload/t_cover_big/co1abc.jpg".to_string(),
+///     "https://images.igdb.com/igdb/image/upload/t_1080p/co1abc.jpg".to_string(),
+/// ]);
+/// assert_eq!(urls, vec!["https://images.igdb.com/igdb/image/upload/t_1080p/co1abc.jpg"]);
+/// ```
+pub fn dedup_cover_urls(urls: Vec<String>) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    // 已保留的图片：标识 -> (在结果中的位置, 分辨率等级)
+    let mut images: std::collections::HashMap<String, (usize, u32)> = std::collections::HashMap::new();
+
+    for url in urls {
+        if result.contains(&url) {
+            continue;
+        }
+        match cover_image_key(&url) {
+            Some((key, rank)) => match images.get_mut(&key) {
+                Some((index, best)) => {
+                    if rank > *best {
+                        result[*index] = url;
+                        *best = rank;
+                    }
+                }
+                None => {
+                    images.insert(key, (result.len(), rank));
+                    result.push(url);
+                }
+            },
+            None => result.push(url),
+        }
+    }
+
+    result
+}
+
+/// 解析提供者返回的发布日期
+///
+/// 支持 `YYYY-MM-DD`、`YYYY/MM/DD`、`YYYY.MM.DD`、日文的 `YYYY年MM月DD日`、纯年份 `YYYY`（取 1 月 1 日）
+/// 以及 RFC 3339 时间；日期之后的时间部分（如 `2024/01/15 00:00`）会被忽略
+///
+/// # 参数
+/// - `date`: 日期字符串
+///
+/// # 返回
+/// 解析出的日期（UTC 零点），无法解析时返回 `None`
+///
+/// # 示例
+/// ```
+/// use gamebox::scan::parse_release_date;
+///
+/// let date = parse_release_date("2021年08月10日").unwrap();
+/// assert_eq!(date.format("%Y-%m-%d").to_string(), "2021-08-10");
+/// assert_eq!(parse_release_date("TBA"), None);
+/// ```
+pub fn parse_release_date(date: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    let date = date.trim();
+    if let Ok(datetime) = chrono::DateTime::parse_from_rfc3339(date) {
+        return Some(datetime.with_timezone(&chrono::Utc));
+    }
+
+    let day = date.split([' ', 'T']).next().unwrap_or(date);
+    let parsed = ["%Y-%m-%d", "%Y/%m/%d", "%Y.%m.%d", "%Y年%m月%d日"]
+        .iter()
+        .find_map(|format| chrono::NaiveDate::parse_from_str(day, format).ok())
+        .or_else(|| {
+            let is_year = day.len() == 4 && day.chars().all(|c| c.is_ascii_digit());
+            is_year.then(|| chrono::NaiveDate::from_ymd_opt(day.parse().ok()?, 1, 1)).flatten()
+        })?;
+    Some(parsed.and_hms_opt(0, 0, 0)?.and_utc())
+}
+
+/// 从各地区的发布日期中选择一个日期
+///
+/// 设置了首选地区且存在该地区的日期时使用该日期，否则使用最早的日期。
+/// 日期为 `YYYY-MM-DD` 或 `YYYY` 格式，按字符串比较即为时间先后
+///
+/// # 参数
+/// - `dates`: (地区, 日期) 列表
+/// - `preferred_region`: 首选地区（如 `japan`，忽略大小写）
+///
+/// # 示例
+/// ```
+/// use gamebox::scan::select_release_date;
+///
+/// let dates = vec![
+///     ("japan".to_string(), "2021-08-10".to_string()),
+///     ("worldwide".to_string(), "2020-09-24".to_string()),
+/// ];
+/// assert_eq!(select_release_date(&dates, None).as_deref(), Some("2020-09-24"));
+/// assert_eq!(select_release_date(&dates, Some("Japan")).as_deref(), Some("2021-08-10"));
+/// ```
+pub fn select_release_date(dates: &[(String, String)], preferred_region: Option<&str>) -> Option<String> {
+    preferred_region
+        .and_then(|preferred| dates.iter().find(|(region, _)| region.eq_ignore_ascii_case(preferred)))
+        .or_else(|| dates.iter().min_by(|a, b| a.1.cmp(&b.1)))
+        .map(|(_, date)| date.clone())
+}
+
+/// 封面检查的最大并发请求数
+const COVER_CHECK_CONCURRENCY: usize = 8;
+
+/// 单个封面检查请求的超时时间，超时的 URL 视为不可访问
+const COVER_CHECK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// 检查封面 URL 是否可访问，只保留可访问的 URL
+///
+/// 对每个 URL 发送 HEAD 请求（最多同时 8 个，每个最多等待 5 秒），返回状态码为成功的 URL，
+/// 保持原有顺序。超时的 URL 视为不可访问，响应缓慢的图片服务器不会阻塞扫描
+///
+/// # 参数
+/// - `client`: HTTP 客户端
+/// - `urls`: 候选封面 URL
+///
+/// # 返回
+/// 可访问的封面 URL
+pub async fn validate_cover_urls(client: &reqwest::Client, urls: Vec<String>) -> Vec<String> {
+    check_cover_urls(client, urls, COVER_CHECK_TIMEOUT).await
+}
+
+/// 使用指定的单个请求超时时间检查封面 URL
+async fn check_cover_urls(client: &reqwest::Client, urls: Vec<String>, timeout: std::time::Duration) -> Vec<String> {
+    use futures::StreamExt;
+
+    futures::stream::iter(urls)
+        .map(|url| async move {
+            let reachable = client
+                .head(&url)
+                .timeout(timeout)
+                .send()
+                .await
+                .is_ok_and(|response| response.status().is_success());
+            reachable.then_some(url)
+        })
+        .buffered(COVER_CHECK_CONCURRENCY)
+        .filter_map(|url| async move { url })
+        .collect()
+        .await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -202,5 +905,234 @@ mod tests {
         ];
         assert_eq!(find_common_parent_dir(&paths), 2);
     }
+
+    #[test]
+    fn test_truncate_chars_respects_char_boundaries() {
+        // 2 个 ASCII 字符 + 40 个 3 字节字符：第 100 个字节落在第 33 个假名的中间
+        let description = format!("ab{}", "あ".repeat(40));
+        assert!(!description.is_char_boundary(100));
+
+        let truncated = truncate_chars(&description, 100);
+        assert_eq!(truncated, description);
+
+        let truncated = truncate_chars(&description, 34);
+        assert_eq!(truncated, format!("ab{}...", "あ".repeat(32)));
+        assert_eq!(truncate_chars("", 5), "");
+    }
+
+    #[test]
+    fn test_fold_case() {
+        // ASCII 快速路径
+        assert_eq!(fold_case("Elden RING"), "elden ring");
+        // 非 ASCII 使用 Unicode 大小写折叠
+        assert_eq!(fold_case("ÉLDEN Ωmega"), "élden ωmega");
+        assert_eq!(fold_case("ΣΊΣΥΦΟΣ"), fold_case("σίσυφοσ"));
+        // 简单折叠保持长度不变：ß 不展开为 ss，İ 不变为 i + 组合点
+        assert_eq!(fold_case("Straße"), "straße");
+        assert_eq!(fold_case("İstanbul").chars().count(), 8);
+        assert_eq!(fold_case("游戏名称"), "游戏名称");
+    }
+
+    #[test]
+    fn test_is_trial_title() {
+        for title in ["ゲーム名 体験版", "游戏 体验版", "Game [Demo]", "Game (Trial Version)", "Game Demo ver.", "Game - Trial"] {
+            assert!(is_trial_title(title), "{}", title);
+        }
+        for title in ["Demon's Souls", "Demolition Racer", "Trials of Mana", "The Trial", "Demo Derby"] {
+            assert!(!is_trial_title(title), "{}", title);
+        }
+    }
+
+    #[test]
+    fn test_contains_cjk() {
+        assert!(contains_cjk("游戏名称"));
+        assert!(contains_cjk("Game 汉化版"));
+        assert!(!contains_cjk("Elden Ring"));
+    }
+
+    #[test]
+    fn test_rank_launchers_demotes_helpers() {
+        let paths = vec![
+            "setup.exe".to_string(),
+            "game_debug.exe".to_string(),
+            "Game.exe".to_string(),
+            "tools/config.exe".to_string(),
+        ];
+        let ranked = rank_launchers(&paths, &["Game"]);
+
+        assert_eq!(ranked[0].0, "Game.exe");
+        let score_of = |name: &str| ranked.iter().find(|(p, _)| p == name).unwrap().1;
+        assert!(score_of("setup.exe") < score_of("Game.exe"));
+        assert!(score_of("game_debug.exe") < score_of("Game.exe"));
+    }
+
+    #[test]
+    fn test_parse_release_date_formats() {
+        let day = |date: &str| parse_release_date(date).map(|d| d.format("%Y-%m-%d").to_string());
+        assert_eq!(day("2020-09-24").as_deref(), Some("2020-09-24"));
+        assert_eq!(day("2020/09/24").as_deref(), Some("2020-09-24"));
+        assert_eq!(day("2020/9/4 00:00").as_deref(), Some("2020-09-04"));
+        assert_eq!(day("2021年08月10日").as_deref(), Some("2021-08-10"));
+        assert_eq!(day("1998").as_deref(), Some("1998-01-01"));
+        assert_eq!(day(" 2020-09-24T12:30:00+09:00 ").as_deref(), Some("2020-09-24"));
+        assert_eq!(day("2020-09-24 00:00:00").as_deref(), Some("2020-09-24"));
+        assert_eq!(day("TBA"), None);
+        assert_eq!(day("2020-13-01"), None);
+        assert_eq!(day(""), None);
+    }
+
+    #[test]
+    fn test_rank_launchers_in_dir_prefers_game_name_then_size() {
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        let files = [
+            ("config.exe", 64),
+            ("launcher-update.exe", 4096),
+            ("UnityCrashHandler64.exe", 2048),
+            ("Tool.exe", 512),
+            ("Big Tool.exe", 1024),
+            ("My Game.exe", 256),
+        ];
+        for (name, size) in files {
+            std::fs::write(dir.path().join(name), vec![0u8; size]).unwrap();
+        }
+        let paths: Vec<String> = files.iter().map(|(name, _)| name.to_string()).collect();
+
+        let ranked = rank_launchers_in_dir(dir.path(), &paths, &["My Game"]);
+        let order: Vec<&str> = ranked.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(order[0], "My Game.exe");
+        // 名称无法区分时较大的文件优先，辅助程序即使更大也排在后面
+        assert_eq!(&order[1..3], &["Big Tool.exe", "Tool.exe"]);
+        assert_eq!(&order[3..], &["launcher-update.exe", "UnityCrashHandler64.exe", "config.exe"]);
+    }
+
+    #[tokio::test]
+    async fn test_validate_cover_urls_drops_unreachable() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        for (cover, status) in [("/a.jpg", 200), ("/b.jpg", 404), ("/c.jpg", 200)] {
+            Mock::given(method("HEAD"))
+                .and(path(cover))
+                .respond_with(ResponseTemplate::new(status))
+                .mount(&server)
+                .await;
+        }
+
+        let urls: Vec<String> = ["/a.jpg", "/b.jpg", "/c.jpg", "/missing.jpg"]
+            .iter()
+            .map(|p| format!("{}{}", server.uri(), p))
+            .collect();
+        let valid = validate_cover_urls(&reqwest::Client::new(), urls).await;
+
+        assert_eq!(
+            valid,
+            vec![format!("{}/a.jpg", server.uri()), format!("{}/c.jpg", server.uri())]
+        );
+
+        // 响应缓慢的 URL 超时后视为不可访问
+        Mock::given(method("HEAD"))
+            .and(path("/slow.jpg"))
+            .respond_with(ResponseTemplate::new(200).set_delay(std::time::Duration::from_secs(10)))
+            .mount(&server)
+            .await;
+        let started = std::time::Instant::now();
+        let urls = vec![format!("{}/slow.jpg", server.uri()), format!("{}/a.jpg", server.uri())];
+        let valid = check_cover_urls(&reqwest::Client::new(), urls, std::time::Duration::from_millis(100)).await;
+        assert_eq!(valid, vec![format!("{}/a.jpg", server.uri())]);
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+
+    #[test]
+    fn test_normalize_company_field() {
+        assert_eq!(
+            normalize_company_field("Studio A, Studio B / Studio A"),
+            vec!["Studio A".to_string(), "Studio B".to_string()]
+        );
+        assert_eq!(normalize_company_field("ゆずソフト、 ，"), vec!["ゆずソフト".to_string()]);
+        assert!(normalize_company_field("  ").is_empty());
+    }
+
+
+    #[test]
+    fn test_sort_by_locale_orders_cjk_by_reading() {
+        let mut titles = vec!["中文游戏", "安装向导", "北方物语"];
+
+        // 码点顺序：中 (U+4E2D) < 北 (U+5317) < 安 (U+5B89)
+        let mut raw = titles.clone();
+        raw.sort();
+        assert_eq!(raw, vec!["中文游戏", "北方物语", "安装向导"]);
+
+        // 拼音顺序：an < bei < zhong
+        sort_by_locale(&mut titles, "zh", |t| t);
+        assert_eq!(titles, vec!["安装向导", "北方物语", "中文游戏"]);
+
+        // 无效的语言区域按码点排序
+        sort_by_locale(&mut titles, "not a locale!", |t| t);
+        assert_eq!(titles, raw);
+    }
+
+
+    #[test]
+    fn test_detect_platform() {
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        std::fs::write(dir.path().join("game.exe"), b"MZ").unwrap();
+        assert_eq!(detect_platform(dir.path()), None);
+
+        std::fs::write(dir.path().join("game.APK"), b"PK").unwrap();
+        assert_eq!(detect_platform(dir.path()), Some("Android"));
+    }
+
+
+    #[test]
+    fn test_extract_circle_and_title() {
+        let cases = [
+            ("(同人ゲーム) [ぷらずまそふと] 魔法少女の冒険", Some("ぷらずまそふと"), "魔法少女の冒険"),
+            ("[スタジオ・ハッピー (山田太郎)] ふしぎな館 ver1.02", Some("スタジオ・ハッピー"), "ふしぎな館"),
+            ("［ねこまんま堂］勇者の旅路", Some("ねこまんま堂"), "勇者の旅路"),
+            ("勇者の旅路 (ねこまんま堂)", Some("ねこまんま堂"), "勇者の旅路"),
+            ("[RJ01014447] 作品名", None, "作品名"),
+            ("【汉化】魔女的夜宴", None, "魔女的夜宴"),
+            ("ふしぎな館 (体験版)", None, "ふしぎな館 (体験版)"),
+            ("Plain Game", None, "Plain Game"),
+        ];
+
+        for (dir_name, circle, title) in cases {
+            let (actual_circle, actual_title) = extract_circle_and_title(dir_name);
+            assert_eq!(actual_circle.as_deref(), circle, "{}", dir_name);
+            assert_eq!(actual_title, title, "{}", dir_name);
+        }
+    }
+
+
+    #[test]
+    fn test_dedup_cover_urls_keeps_highest_resolution() {
+        let igdb = |size: &str, id: &str| format!("https://images.igdb.com/igdb/image/upload/t_{}/{}.jpg", size, id);
+        let urls = dedup_cover_urls(vec![
+            igdb("cover_big", "co1abc"),
+            "https://example.com/cover.jpg".to_string(),
+            igdb("1080p", "co1abc"),
+            igdb("thumb", "co1abc"),
+            igdb("cover_big", "co2xyz"),
+            "https://example.com/cover.jpg".to_string(),
+        ]);
+        assert_eq!(
+            urls,
+            vec![igdb("1080p", "co1abc"), "https://example.com/cover.jpg".to_string(), igdb("cover_big", "co2xyz")]
+        );
+
+        let dlsite = "https://img.dlsite.jp";
+        let urls = dedup_cover_urls(vec![
+            format!("{}/resize/images2/work/doujin/RJ01015000/RJ01014447_img_main_240x240.jpg", dlsite),
+            format!("{}/modpub/images2/work/doujin/RJ01015000/RJ01014447_img_main.jpg", dlsite),
+            format!("{}/modpub/images2/work/doujin/RJ01015000/RJ01014447_img_sam.jpg", dlsite),
+            format!("{}/modpub/images2/work/doujin/RJ01015000/RJ01014447_img_smp1.jpg", dlsite),
+        ]);
+        assert_eq!(urls.len(), 2);
+        assert!(urls[0].ends_with("RJ01014447_img_main.jpg"));
+        assert!(urls[1].ends_with("RJ01014447_img_smp1.jpg"));
+    }
+
 }
 
@@ -1,5 +1,5 @@
 use gamebox::logger::{init_logger, get_logger, LogEvent, LogLevel};
-use gamebox::scan::GameScanner;
+use gamebox::scan::{truncate_chars, GameScanner};
 use gamebox::traits::JsonOutput;
 
 #[tokio::main]
@@ -57,12 +57,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         }
         
         if let Some(description) = &result.info.description {
-            let short_desc = if description.len() > 100 {
-                format!("{}...", &description[..100])
-            } else {
-                description.clone()
-            };
-            println!("  简介: {}", short_desc);
+            println!("  简介: {}", truncate_chars(description, 100));
         }
         
         if let Some(genres) = &result.info.genres {
@@ -0,0 +1,139 @@
+//! CSV 输出 trait
+//!
+//! 为扫描和搜索结果提供 CSV 输出功能，便于导入表格软件
+
+use std::io::Write;
+
+/// CSV 输出 trait
+pub trait CsvOutput {
+    /// 表头
+    fn csv_header() -> &'static [&'static str];
+
+    /// 数据行，每行的列数与表头一致
+    fn csv_records(&self) -> Vec<Vec<String>>;
+
+    /// 以 CSV 格式写入任意输出目标
+    ///
+    /// # 参数
+    /// - `writer`: 输出目标
+    ///
+    /// # 返回
+    /// - `Ok(usize)`: 写入的数据行数（不含表头）
+    /// - `Err`: 写入失败时返回错误信息
+    fn write_csv<W: Write>(&self, mut writer: W) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let header: Vec<String> = Self::csv_header().iter().map(|h| escape_field(h)).collect();
+        writeln!(writer, "{}", header.join(","))?;
+
+        let records = self.csv_records();
+        for record in &records {
+            let fields: Vec<String> = record.iter().map(|f| escape_field(f)).collect();
+            writeln!(writer, "{}", fields.join(","))?;
+        }
+
+        writer.flush()?;
+        Ok(records.len())
+    }
+}
+
+/// 按 RFC 4180 转义字段：包含逗号、引号或换行时加引号，内部引号加倍
+fn escape_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// 为 Vec<GameInfo> 实现 CsvOutput
+impl CsvOutput for Vec<crate::models::game_info::GameInfo> {
+    fn csv_header() -> &'static [&'static str] {
+        &[
+            "title",
+            "sub_title",
+            "version",
+            "developer",
+            "publisher",
+            "release_date",
+            "dir_path",
+            "start_path",
+            "byte_size",
+        ]
+    }
+
+    fn csv_records(&self) -> Vec<Vec<String>> {
+        self.iter()
+            .map(|game| {
+                vec![
+                    game.title.clone(),
+                    game.sub_title.clone(),
+                    game.version.clone().unwrap_or_default(),
+                    game.developer.clone().unwrap_or_default(),
+                    game.publisher.clone().unwrap_or_default(),
+                    game.release_date.map(|date| date.format("%Y-%m-%d").to_string()).unwrap_or_default(),
+                    game.dir_path.display().to_string(),
+                    game.start_path_defualt.clone(),
+                    game.byte_size.to_string(),
+                ]
+            })
+            .collect()
+    }
+}
+
+// 为 Vec<GameQueryResult> 实现 CsvOutput
+impl CsvOutput for Vec<crate::providers::GameQueryResult> {
+    fn csv_header() -> &'static [&'static str] {
+        &[
+            "title",
+            "source",
+            "confidence",
+            "developer",
+            "publisher",
+            "release_date",
+            "cover_url",
+        ]
+    }
+
+    fn csv_records(&self) -> Vec<Vec<String>> {
+        self.iter()
+            .map(|result| {
+                vec![
+                    result.info.title.clone().unwrap_or_default(),
+                    result.source.clone(),
+                    format!("{:.2}", result.confidence),
+                    result.info.developer.clone().unwrap_or_default(),
+                    result.info.publisher.clone().unwrap_or_default(),
+                    result.info.release_date.clone().unwrap_or_default(),
+                    result.info.cover_url.clone().unwrap_or_default(),
+                ]
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::game_meta_data::GameMetadata;
+    use crate::providers::GameQueryResult;
+
+    #[test]
+    fn test_write_csv_escapes_fields() {
+        let results = vec![GameQueryResult {
+            info: GameMetadata {
+                title: Some("Hello, \"World\"".to_string()),
+                developer: Some("Studio".to_string()),
+                ..Default::default()
+            },
+            source: "Mock".to_string(),
+            confidence: 0.5,
+        }];
+
+        let mut buffer: Vec<u8> = Vec::new();
+        assert_eq!(results.write_csv(&mut buffer).unwrap(), 1);
+
+        let output = String::from_utf8(buffer).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "title,source,confidence,developer,publisher,release_date,cover_url");
+        assert_eq!(lines[1], "\"Hello, \"\"World\"\"\",Mock,0.50,Studio,,,");
+    }
+}
@@ -0,0 +1,204 @@
+//! 游戏库 trait
+//!
+//! 为扫描结果（`Vec<GameInfo>`）提供面向整个游戏库的操作
+
+use std::io::Write;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::models::game_info::GameInfo;
+
+/// 游戏库中的单个启动项
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LauncherEntry {
+    /// 所属游戏的标题
+    pub game_title: String,
+    /// 相对于游戏目录的启动项路径
+    pub relative_path: String,
+    /// 启动项的绝对路径
+    pub absolute_path: PathBuf,
+    /// 是否为该游戏的默认启动项
+    pub is_default: bool,
+}
+
+/// 游戏库 trait
+///
+/// 为扫描得到的游戏列表提供批量操作
+pub trait GameLibrary {
+    /// 以 NUL 字节分隔输出每个游戏默认启动项的绝对路径
+    ///
+    /// 输出格式与 `find -print0` 相同，可直接交给 `xargs -0` 等工具处理，
+    /// 路径中的空格、引号等字符不需要额外转义。没有启动项的游戏会被跳过。
+    ///
+    /// # 参数
+    /// - `writer`: 输出目标（标准输出、文件、内存缓冲区等）
+    ///
+    /// # 返回
+    /// 实际输出的路径数量
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use gamebox::scan::GameScanner;
+    /// use gamebox::traits::GameLibrary;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let game_infos = GameScanner::new().scan("D:/Games".to_string()).await;
+    ///     game_infos.out_paths_null_delimited(std::io::stdout().lock())?;
+    ///     Ok(())
+    /// }
+    /// ```
+    fn out_paths_null_delimited<W: Write>(&self, writer: W) -> std::io::Result<usize>;
+
+    /// 筛选带有指定标注的游戏
+    ///
+    /// # 参数
+    /// - `annotation`: 标注名称（如 `owned`、`wishlist`）
+    ///
+    /// # 返回
+    /// 带有该标注的游戏，保持原有顺序
+    fn filter_annotated(&self, annotation: &str) -> Vec<GameInfo>;
+
+    /// 列出游戏库中所有游戏的所有启动项
+    ///
+    /// 将每个游戏的启动项与游戏目录拼接为绝对路径，用于批量创建快捷方式或检查启动项是否存在
+    ///
+    /// # 返回
+    /// 启动项列表，按游戏顺序及每个游戏中启动项的顺序排列
+    fn all_launchers(&self) -> Vec<LauncherEntry>;
+}
+
+impl GameLibrary for Vec<GameInfo> {
+    fn out_paths_null_delimited<W: Write>(&self, mut writer: W) -> std::io::Result<usize> {
+        let mut count = 0;
+        for game in self {
+            let Some(path) = game.default_launcher_path() else {
+                continue;
+            };
+            let path = std::path::absolute(&path)?;
+
+            writer.write_all(path.as_os_str().as_encoded_bytes())?;
+            writer.write_all(b"\0")?;
+            count += 1;
+        }
+        writer.flush()?;
+        Ok(count)
+    }
+
+    fn filter_annotated(&self, annotation: &str) -> Vec<GameInfo> {
+        self.iter()
+            .filter(|game| game.has_annotation(annotation))
+            .cloned()
+            .collect()
+    }
+
+    fn all_launchers(&self) -> Vec<LauncherEntry> {
+        let mut launchers = Vec::new();
+        for game in self {
+            let default = game.default_launcher_path();
+            for relative_path in &game.start_path {
+                let path = game.dir_path.join(relative_path);
+                let is_default = default.as_ref() == Some(&path);
+                launchers.push(LauncherEntry {
+                    game_title: game.title.clone(),
+                    relative_path: relative_path.clone(),
+                    absolute_path: std::path::absolute(&path).unwrap_or(path),
+                    is_default,
+                });
+            }
+        }
+        launchers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn game(dir_path: &str, start_path: &[&str], default: &str) -> GameInfo {
+        GameInfo {
+            dir_path: PathBuf::from(dir_path),
+            start_path: start_path.iter().map(|s| s.to_string()).collect(),
+            start_path_defualt: default.to_string(),
+            ..GameInfo::new()
+        }
+    }
+
+    #[test]
+    fn test_out_paths_null_delimited() {
+        let root = std::env::temp_dir().join("My Games");
+        let games = vec![
+            game(root.join("Game \"One\"").to_str().unwrap(), &["bin/game.exe", "setup.exe"], "bin/game.exe"),
+            game(root.join("No Launcher").to_str().unwrap(), &[], ""),
+            game(root.join("Two").to_str().unwrap(), &["two.exe"], ""),
+        ];
+
+        let mut buffer: Vec<u8> = Vec::new();
+        let count = games.out_paths_null_delimited(&mut buffer).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(buffer.last(), Some(&0u8));
+
+        let paths: Vec<PathBuf> = buffer
+            .split(|&b| b == 0)
+            .filter(|p| !p.is_empty())
+            .map(|p| PathBuf::from(String::from_utf8(p.to_vec()).unwrap()))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                root.join("Game \"One\"").join("bin/game.exe"),
+                root.join("Two").join("two.exe"),
+            ]
+        );
+        assert!(paths.iter().all(|p| p.is_absolute()));
+    }
+
+    #[test]
+    fn test_all_launchers_flags_default() {
+        let root = std::env::temp_dir().join("My Games");
+        let mut games = vec![
+            game(root.join("One").to_str().unwrap(), &["setup.exe", "bin/game.exe"], "bin/game.exe"),
+            game(root.join("No Launcher").to_str().unwrap(), &[], ""),
+            game(root.join("Two").to_str().unwrap(), &["two.exe", "config.exe"], ""),
+        ];
+        games[0].title = "One".to_string();
+
+        let launchers = games.all_launchers();
+        assert_eq!(launchers.len(), 4);
+        assert_eq!(launchers.iter().filter(|l| l.is_default).count(), 2);
+
+        assert_eq!(launchers[1].game_title, "One");
+        assert_eq!(launchers[1].relative_path, "bin/game.exe");
+        assert_eq!(launchers[1].absolute_path, root.join("One").join("bin/game.exe"));
+        assert!(launchers[1].is_default && !launchers[0].is_default);
+        // 未设置默认启动项时第一个启动项为默认
+        assert!(launchers[2].is_default && !launchers[3].is_default);
+    }
+
+    #[test]
+    fn test_annotations_round_trip_and_filter() {
+        let mut owned = game("/games/Owned", &["owned.exe"], "");
+        assert!(owned.add_annotation("owned"));
+        assert!(!owned.add_annotation("owned"));
+        owned.add_annotation("hidden");
+        let mut wishlist = game("/games/Wishlist", &[], "");
+        wishlist.add_annotation("wishlist");
+        let plain = game("/games/Plain", &[], "");
+
+        let json = serde_json::to_string(&vec![owned, wishlist, plain]).unwrap();
+        let mut games: Vec<GameInfo> = serde_json::from_str(&json).unwrap();
+        assert!(games[0].has_annotation("owned") && games[0].has_annotation("hidden"));
+        assert!(games[2].annotations.is_empty());
+
+        let owned = games.filter_annotated("owned");
+        assert_eq!(owned.len(), 1);
+        assert_eq!(owned[0].dir_path, PathBuf::from("/games/Owned"));
+
+        assert!(games[0].remove_annotation("owned"));
+        assert!(games.filter_annotated("owned").is_empty());
+        assert_eq!(games.filter_annotated("wishlist").len(), 1);
+    }
+}
@@ -1,5 +1,6 @@
 use std::cmp::Ordering;
 use crate::models::game_meta_data::GameMetadata;
+use crate::scan::fold_case;
 
 /// 实现查询多内容匹配值最大的游戏元数据
 pub trait GameMetadataFilter {
@@ -41,8 +42,8 @@ impl GameMetadataFilter for Vec<GameMetadata> {
 
 /// 计算匹配分数（0.0 ~ 1.0）
 fn calculate_match_score(query: &str, title: &str) -> f64 {
-    let query = query.trim().to_lowercase();
-    let title = title.trim().to_lowercase();
+    let query = fold_case(query.trim());
+    let title = fold_case(title.trim());
 
     if query.is_empty() || title.is_empty() {
         return 0.0;
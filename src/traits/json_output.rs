@@ -1,12 +1,131 @@
 //! JSON 输出 trait
 //!
-//! 为扫描和搜索结果提供 JSON 输出功能
+//! 为扫描和搜索结果提供 JSON 输出功能，可写入文件或任意实现了 `Write` 的目标。
+//! 启用 `zstd` feature 后支持读写 zstd 压缩的 JSON 文件（`.zst` 扩展名）。
 
+use std::fmt;
 use std::fs::File;
-use std::io::Write;
+use std::io::{BufReader, BufWriter, Read, Write};
 use std::path::Path;
+use serde::de::{DeserializeOwned, Deserializer, SeqAccess, Visitor};
 use serde::Serialize;
 
+use crate::models::game_info::GameInfo;
+
+/// zstd 压缩级别
+#[cfg(feature = "zstd")]
+const ZSTD_LEVEL: i32 = 3;
+
+/// 路径是否使用 `.zst` 扩展名
+pub(crate) fn is_zstd_path(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("zst"))
+}
+
+/// 打开 JSON 文件用于读取，`.zst` 文件自动解压
+pub(crate) fn open_json_reader(path: &Path) -> Result<Box<dyn Read>, Box<dyn std::error::Error + Send + Sync>> {
+    let file = BufReader::new(File::open(path)?);
+    if is_zstd_path(path) {
+        #[cfg(feature = "zstd")]
+        return Ok(Box::new(zstd::Decoder::with_buffer(file)?));
+        #[cfg(not(feature = "zstd"))]
+        return Err(format!("读取压缩文件需要启用 zstd feature: {}", path.display()).into());
+    }
+    Ok(Box::new(file))
+}
+
+/// JSON 文件写入器，`.zst` 文件写入时自动压缩
+///
+/// 写入完成后必须调用 [`finish`](Self::finish)：压缩流在此时写入结尾帧，
+/// 出错时返回错误，避免截断的文件被当作写入成功
+pub(crate) enum JsonWriter {
+    /// 未压缩的文件
+    Plain(BufWriter<File>),
+    /// zstd 压缩的文件
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::Encoder<'static, BufWriter<File>>),
+}
+
+impl JsonWriter {
+    /// 完成写入：结束压缩流并刷新缓冲区
+    pub(crate) fn finish(self) -> std::io::Result<()> {
+        match self {
+            JsonWriter::Plain(mut file) => file.flush(),
+            #[cfg(feature = "zstd")]
+            JsonWriter::Zstd(encoder) => encoder.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for JsonWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            JsonWriter::Plain(file) => file.write(buf),
+            #[cfg(feature = "zstd")]
+            JsonWriter::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            JsonWriter::Plain(file) => file.flush(),
+            #[cfg(feature = "zstd")]
+            JsonWriter::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// 创建 JSON 文件用于写入，`.zst` 文件自动压缩
+pub(crate) fn create_json_writer(path: &Path) -> Result<JsonWriter, Box<dyn std::error::Error + Send + Sync>> {
+    let file = BufWriter::new(File::create(path)?);
+    if is_zstd_path(path) {
+        #[cfg(feature = "zstd")]
+        return Ok(JsonWriter::Zstd(zstd::Encoder::new(file, ZSTD_LEVEL)?));
+        #[cfg(not(feature = "zstd"))]
+        return Err(format!("写入压缩文件需要启用 zstd feature: {}", path.display()).into());
+    }
+    Ok(JsonWriter::Plain(file))
+}
+
+/// 逐个读取数组元素的访问器
+struct GameInfoSeqVisitor;
+
+impl<'de> Visitor<'de> for GameInfoSeqVisitor {
+    type Value = Vec<GameInfo>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("游戏信息数组")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut games = Vec::new();
+        while let Some(game) = seq.next_element::<GameInfo>()? {
+            games.push(game);
+        }
+        Ok(games)
+    }
+}
+
+/// 流式读取之前输出的扫描结果（如 `scan_result.json`）
+///
+/// 通过带缓冲的读取器逐个反序列化数组中的游戏信息，不会先把整个文件读入内存，
+/// 适合加载很大的扫描结果。`.zst` 扩展名的文件会自动解压（需要启用 `zstd` feature）
+///
+/// # 参数
+/// - `path`: 文件路径
+///
+/// # 返回
+/// 文件中的全部游戏信息
+pub fn load_game_infos_streaming<P: AsRef<Path>>(path: P) -> Result<Vec<GameInfo>, Box<dyn std::error::Error + Send + Sync>> {
+    let path = path.as_ref();
+    let reader = open_json_reader(path)?;
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let games = deserializer
+        .deserialize_seq(GameInfoSeqVisitor)
+        .map_err(|e| format!("无法读取扫描结果 {}: {}", path.display(), e))?;
+    deserializer.end()?;
+    Ok(games)
+}
+
 /// JSON 输出 trait
 ///
 /// 为结果类型提供输出为 JSON 文件的功能
@@ -14,6 +133,28 @@ pub trait JsonOutput: Serialize {
     /// 获取默认输出文件名
     fn default_filename() -> &'static str;
 
+    /// 以 JSON 格式写入任意输出目标
+    ///
+    /// 可写入标准输出、内存缓冲区、网络连接或压缩流等
+    ///
+    /// # 参数
+    /// - `writer`: 输出目标
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use gamebox::models::game_info::GameInfo;
+    /// use gamebox::traits::JsonOutput;
+    ///
+    /// let game_infos: Vec<GameInfo> = Vec::new();
+    /// game_infos.write_json(std::io::stdout().lock()).unwrap();
+    /// ```
+    fn write_json<W: Write>(&self, mut writer: W) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer.flush()?;
+        Ok(())
+    }
+
     /// 输出为 JSON 文件
     ///
     /// # 参数
@@ -56,23 +197,62 @@ pub trait JsonOutput: Serialize {
             std::path::PathBuf::from(Self::default_filename())
         };
 
-        // 序列化为 JSON
-        let json_output = serde_json::to_string_pretty(self)?;
-
         // 写入文件
-        let mut file = File::create(&output_path)?;
-        file.write_all(json_output.as_bytes())?;
+        let file = File::create(&output_path)?;
+        self.write_json(BufWriter::new(file))?;
 
         // 返回实际使用的路径
         Ok(output_path.display().to_string())
     }
+
+    /// 输出为 zstd 压缩的 JSON 文件
+    ///
+    /// # 参数
+    /// - `path`: 可选的输出路径，如果为 None 则使用默认文件名加 `.zst`（如 `scan_result.json.zst`）
+    ///
+    /// # 返回
+    /// - `Ok(String)`: 成功时返回实际使用的文件路径
+    /// - `Err`: 失败时返回错误信息
+    #[cfg(feature = "zstd")]
+    fn out_json_compressed<P: AsRef<Path>>(&self, path: Option<P>) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let output_path = match path {
+            Some(p) => p.as_ref().to_path_buf(),
+            None => std::path::PathBuf::from(format!("{}.zst", Self::default_filename())),
+        };
+
+        let file = BufWriter::new(File::create(&output_path)?);
+        let mut encoder = zstd::Encoder::new(file, ZSTD_LEVEL)?;
+        serde_json::to_writer(&mut encoder, self)?;
+        encoder.finish()?.flush()?;
+
+        Ok(output_path.display().to_string())
+    }
+
+    /// 读取 JSON 文件（如之前输出的扫描结果）
+    ///
+    /// `.zst` 扩展名的文件会自动解压（需要启用 `zstd` feature）
+    ///
+    /// # 参数
+    /// - `path`: 文件路径
+    fn load_json<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error + Send + Sync>>
+    where
+        Self: DeserializeOwned,
+    {
+        let reader = open_json_reader(path.as_ref())?;
+        Ok(serde_json::from_reader(reader)?)
+    }
 }
 
 // 为 Vec<GameInfo> 实现 JsonOutput
-impl JsonOutput for Vec<crate::models::game_info::GameInfo> {
+impl JsonOutput for Vec<GameInfo> {
     fn default_filename() -> &'static str {
         "scan_result.json"
     }
+
+    /// 扫描结果可能很大，使用流式读取
+    fn load_json<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        load_game_infos_streaming(path)
+    }
 }
 
 // 为 Vec<GameQueryResult> 实现 JsonOutput
@@ -82,3 +262,86 @@ impl JsonOutput for Vec<crate::providers::GameQueryResult> {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_json_to_buffer() {
+        let mut game = GameInfo::new();
+        game.title = "Test Game".to_string();
+        let game_infos = vec![game];
+
+        let mut buffer: Vec<u8> = Vec::new();
+        game_infos.write_json(&mut buffer).unwrap();
+
+        let parsed: Vec<GameInfo> = serde_json::from_slice(&buffer).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].title, "Test Game");
+    }
+
+    #[test]
+    fn test_load_large_scan_result_streaming() {
+        let game_infos: Vec<GameInfo> = (0..5000)
+            .map(|i| GameInfo {
+                title: format!("Streamed Game {}", i),
+                description: Some("x".repeat(200)),
+                ..GameInfo::new()
+            })
+            .collect();
+
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        let path = dir.path().join("scan_result.json");
+        game_infos.out_json(Some(&path)).unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() > 1_000_000);
+
+        let loaded = load_game_infos_streaming(&path).unwrap();
+        assert_eq!(loaded.len(), 5000);
+        assert_eq!(loaded[0].title, "Streamed Game 0");
+        assert_eq!(loaded[4999].title, "Streamed Game 4999");
+        assert_eq!(Vec::<GameInfo>::load_json(&path).unwrap().len(), 5000);
+
+        // 截断或包含多余内容的文件应报错
+        std::fs::write(&path, "[{\"title\": \"Broken\"").unwrap();
+        assert!(load_game_infos_streaming(&path).is_err());
+        std::fs::write(&path, "[] []").unwrap();
+        assert!(load_game_infos_streaming(&path).is_err());
+        std::fs::write(&path, "[]").unwrap();
+        assert!(load_game_infos_streaming(&path).unwrap().is_empty());
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_compressed_json_round_trip() {
+        let game_infos: Vec<GameInfo> = (0..50)
+            .map(|i| GameInfo {
+                title: format!("Compressed Game {}", i),
+                ..GameInfo::new()
+            })
+            .collect();
+
+        let dir = tempfile::Builder::new().prefix("gamebox").tempdir().unwrap();
+        let plain = dir.path().join("scan_result.json");
+        let compressed = dir.path().join("scan_result.json.zst");
+        game_infos.out_json(Some(&plain)).unwrap();
+        game_infos.out_json_compressed(Some(&compressed)).unwrap();
+
+        // zstd 帧魔数
+        let bytes = std::fs::read(&compressed).unwrap();
+        assert_eq!(&bytes[..4], &[0x28, 0xB5, 0x2F, 0xFD]);
+        assert!(bytes.len() < std::fs::metadata(&plain).unwrap().len() as usize);
+
+        let loaded = Vec::<GameInfo>::load_json(&compressed).unwrap();
+        assert_eq!(loaded.len(), 50);
+        assert_eq!(loaded[49].title, "Compressed Game 49");
+        assert_eq!(Vec::<GameInfo>::load_json(&plain).unwrap().len(), 50);
+
+        // 通过写入器写入的压缩文件在 finish 后是完整的 zstd 流
+        let mut writer = create_json_writer(&compressed).unwrap();
+        serde_json::to_writer(&mut writer, &game_infos).unwrap();
+        writer.finish().unwrap();
+        assert_eq!(Vec::<GameInfo>::load_json(&compressed).unwrap().len(), 50);
+    }
+
+}
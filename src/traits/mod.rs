@@ -1,5 +1,11 @@
+pub mod csv_output;
+pub mod game_library;
 pub mod game_metadata_filter;
 pub mod json_output;
+pub mod query_result_filter;
 
 // 重新导出常用的 trait
-pub use json_output::JsonOutput;
+pub use csv_output::CsvOutput;
+pub use game_library::{GameLibrary, LauncherEntry};
+pub use json_output::{load_game_infos_streaming, JsonOutput};
+pub use query_result_filter::QueryResultFilter;
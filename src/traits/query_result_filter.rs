@@ -0,0 +1,94 @@
+//! 查询结果过滤 trait
+//!
+//! 为数据库查询结果提供按发行年份等条件过滤的功能
+
+use chrono::Datelike;
+
+use crate::providers::GameQueryResult;
+use crate::scan::parse_release_date;
+
+/// 查询结果过滤 trait
+pub trait QueryResultFilter {
+    /// 按发行年份范围过滤结果
+    ///
+    /// 年份由 [`parse_release_date`] 从提供者返回的 `release_date` 中解析，
+    /// 与构建游戏信息时的日期解析一致：无法解析的日期（如 `TBA 2025`）视为缺少日期
+    ///
+    /// # 参数
+    /// - `min`: 最早年份（包含），`None` 表示不限制
+    /// - `max`: 最晚年份（包含），`None` 表示不限制
+    /// - `keep_undated`: 是否保留缺少日期或日期无法解析的结果
+    ///
+    /// # 返回
+    /// 过滤后的结果，保持原有顺序
+    fn filter_by_year(self, min: Option<i32>, max: Option<i32>, keep_undated: bool) -> Self;
+}
+
+impl QueryResultFilter for Vec<GameQueryResult> {
+    fn filter_by_year(self, min: Option<i32>, max: Option<i32>, keep_undated: bool) -> Self {
+        self.into_iter()
+            .filter(|result| {
+                let year = result.info.release_date.as_deref().and_then(parse_release_date).map(|date| date.year());
+                match year {
+                    Some(year) => {
+                        min.is_none_or(|min| year >= min) && max.is_none_or(|max| year <= max)
+                    }
+                    None => keep_undated,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::game_meta_data::GameMetadata;
+
+    fn result(title: &str, release_date: Option<&str>) -> GameQueryResult {
+        GameQueryResult {
+            info: GameMetadata {
+                title: Some(title.to_string()),
+                release_date: release_date.map(|d| d.to_string()),
+                ..Default::default()
+            },
+            source: "Mock".to_string(),
+            confidence: 0.5,
+        }
+    }
+
+    #[test]
+    fn test_filter_by_year_mixed_dates() {
+        let results = vec![
+            result("Eighties", Some("1989")),
+            result("Nineties", Some("1995-03-24")),
+            result("Nineties JP", Some("1999年12月01日")),
+            result("Modern", Some("2021/06/10")),
+            result("Undated", None),
+            result("Garbled", Some("unknown")),
+            result("Announced", Some("TBA 2025")),
+        ];
+
+        let titles = |results: Vec<GameQueryResult>| -> Vec<String> {
+            results.into_iter().filter_map(|r| r.info.title).collect()
+        };
+
+        assert_eq!(
+            titles(results.clone().filter_by_year(Some(1990), Some(1999), false)),
+            vec!["Nineties", "Nineties JP"]
+        );
+        assert_eq!(
+            titles(results.clone().filter_by_year(Some(1990), Some(1999), true)),
+            vec!["Nineties", "Nineties JP", "Undated", "Garbled", "Announced"]
+        );
+        assert_eq!(
+            titles(results.clone().filter_by_year(None, Some(1990), false)),
+            vec!["Eighties"]
+        );
+        // 无法解析的日期不会按其中的数字归入年份范围
+        assert_eq!(
+            titles(results.filter_by_year(Some(2020), None, false)),
+            vec!["Modern"]
+        );
+    }
+}